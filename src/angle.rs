@@ -0,0 +1,89 @@
+//! Antenna angle conventions and normalization.
+//!
+//! `antenna_angle` travels on the wire as plain signed degrees with no defined
+//! wrap behavior, so a slew through 180° or a value like 200 is ambiguous.
+//! This module pins down two explicit conventions and provides the shortest
+//! angular distance, which the misalignment logic uses instead of a naive
+//! `abs()` that would call 350° a gross misalignment when it is 10° off
+//! boresight.
+
+/// Wrap convention an angle is expressed in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AngleConvention {
+    /// Degrees in `[-180, 180)`.
+    Signed180,
+    /// Degrees in `[0, 360)`.
+    Unsigned360,
+}
+
+impl AngleConvention {
+    pub fn parse(s: &str) -> Option<AngleConvention> {
+        match s {
+            "signed" | "+-180" => Some(AngleConvention::Signed180),
+            "unsigned" | "0-360" => Some(AngleConvention::Unsigned360),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            AngleConvention::Signed180 => "signed (+-180)",
+            AngleConvention::Unsigned360 => "unsigned (0-360)",
+        }
+    }
+}
+
+/// Normalizes `deg` into the convention's range: `[-180, 180)` signed or
+/// `[0, 360)` unsigned.
+pub fn normalize_angle(deg: f64, convention: AngleConvention) -> f64 {
+    let wrapped = deg.rem_euclid(360.0);
+    match convention {
+        AngleConvention::Unsigned360 => wrapped,
+        AngleConvention::Signed180 => {
+            if wrapped >= 180.0 {
+                wrapped - 360.0
+            } else {
+                wrapped
+            }
+        }
+    }
+}
+
+/// Shortest angular distance between two angles in degrees, in `[0, 180]`.
+/// Convention-independent: both inputs are wrapped first.
+pub fn angular_distance(a: f64, b: f64) -> f64 {
+    let diff = (a - b).rem_euclid(360.0);
+    diff.min(360.0 - diff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalization_wraps_into_each_convention() {
+        assert_eq!(normalize_angle(200.0, AngleConvention::Signed180), -160.0);
+        assert_eq!(normalize_angle(-10.0, AngleConvention::Unsigned360), 350.0);
+        assert_eq!(normalize_angle(360.0, AngleConvention::Unsigned360), 0.0);
+        assert_eq!(normalize_angle(180.0, AngleConvention::Signed180), -180.0);
+        assert_eq!(normalize_angle(-45.0, AngleConvention::Signed180), -45.0);
+        assert_eq!(normalize_angle(725.0, AngleConvention::Unsigned360), 5.0);
+    }
+
+    #[test]
+    fn distance_takes_the_short_way_around_the_wrap() {
+        assert_eq!(angular_distance(350.0, 0.0), 10.0);
+        assert_eq!(angular_distance(-170.0, 170.0), 20.0);
+        assert_eq!(angular_distance(0.0, 180.0), 180.0);
+        assert_eq!(angular_distance(90.0, 90.0), 0.0);
+        // The same physical angle in either convention measures alike.
+        assert_eq!(angular_distance(-10.0, 0.0), angular_distance(350.0, 0.0));
+    }
+
+    #[test]
+    fn convention_names_round_trip() {
+        assert_eq!(AngleConvention::parse("signed"), Some(AngleConvention::Signed180));
+        assert_eq!(AngleConvention::parse("0-360"), Some(AngleConvention::Unsigned360));
+        assert_eq!(AngleConvention::parse("gradians"), None);
+    }
+}
@@ -0,0 +1,232 @@
+//! Batched telemetry frames with optional lightweight compression.
+//!
+//! High-rate and duty-cycled downlinks spend most of their bytes repeating
+//! what the previous packet already said. A batch frame carries several
+//! complete version-1 frames in one datagram:
+//!
+//! ```text
+//! offset  size  field
+//! 0       1     version            (TELEMETRY_VERSION_BATCH)
+//! 1       1     flags              (bit 0: payload is compressed)
+//! 2       2     uncompressed size  (little-endian)
+//! 4       ...   payload
+//! last 2  2     crc16              (CRC16-CCITT over everything before it)
+//! ```
+//!
+//! The payload is the concatenated per-sample wire frames, each still
+//! carrying its own CRC, so the receiver feeds them through the normal
+//! decode path after unpacking. Compression is a two-pass scheme with no
+//! external dependency: each byte is first XORed with the byte one frame
+//! earlier (consecutive samples differ in little more than the sequence
+//! number, so the delta stream is mostly zeros), then zero runs are
+//! run-length encoded. When that would *expand* the payload — pathological
+//! but possible, since an isolated zero costs two bytes — the frame is sent
+//! uncompressed with the flag clear.
+
+use crate::telemetry::{crc16_ccitt, Telemetry, TELEMETRY_WIRE_SIZE};
+
+/// Version byte of the batch container frame.
+pub const TELEMETRY_VERSION_BATCH: u8 = 5;
+
+/// Flags bit: the payload is delta+RLE compressed.
+pub const BATCH_FLAG_COMPRESSED: u8 = 0x01;
+
+/// Bytes of header before the payload (version, flags, uncompressed size).
+const BATCH_HEADER: usize = 4;
+
+/// Encodes `samples` as one batch frame, compressing the payload when that
+/// actually shrinks it.
+pub fn encode_batch(samples: &[Telemetry]) -> Vec<u8> {
+    let mut payload = Vec::with_capacity(samples.len() * TELEMETRY_WIRE_SIZE);
+    for sample in samples {
+        payload.extend_from_slice(&sample.to_bytes());
+    }
+    debug_assert!(payload.len() <= u16::MAX as usize, "batch too large for u16 size field");
+    let compressed = compress(&payload);
+    let (flags, body) = if compressed.len() < payload.len() {
+        (BATCH_FLAG_COMPRESSED, compressed)
+    } else {
+        (0, payload.clone())
+    };
+    let mut frame = Vec::with_capacity(BATCH_HEADER + body.len() + 2);
+    frame.push(TELEMETRY_VERSION_BATCH);
+    frame.push(flags);
+    frame.extend_from_slice(&(payload.len() as u16).to_le_bytes());
+    frame.extend_from_slice(&body);
+    let crc = crc16_ccitt(&frame);
+    frame.extend_from_slice(&crc.to_le_bytes());
+    frame
+}
+
+/// Decodes a batch frame into the individual per-sample wire frames, which
+/// still carry their own CRCs. Rejects a bad container CRC, a payload that
+/// does not decompress to the declared size, or one that is not a whole
+/// number of frames.
+pub fn decode_batch(data: &[u8]) -> Option<Vec<Vec<u8>>> {
+    if data.len() < BATCH_HEADER + 2 || data[0] != TELEMETRY_VERSION_BATCH {
+        return None;
+    }
+    let body_end = data.len() - 2;
+    let stored = u16::from_le_bytes([data[body_end], data[body_end + 1]]);
+    if crc16_ccitt(&data[..body_end]) != stored {
+        return None;
+    }
+    let raw_len = u16::from_le_bytes([data[2], data[3]]) as usize;
+    let body = &data[BATCH_HEADER..body_end];
+    let payload = if data[1] & BATCH_FLAG_COMPRESSED != 0 {
+        decompress(body, raw_len)?
+    } else {
+        body.to_vec()
+    };
+    if payload.len() != raw_len || !raw_len.is_multiple_of(TELEMETRY_WIRE_SIZE) {
+        return None;
+    }
+    Some(
+        payload
+            .chunks(TELEMETRY_WIRE_SIZE)
+            .map(<[u8]>::to_vec)
+            .collect(),
+    )
+}
+
+/// Delta pass then zero-RLE: a literal byte stands for itself, and a zero
+/// byte is followed by its run length (1..=255).
+pub fn compress(payload: &[u8]) -> Vec<u8> {
+    let mut delta = payload.to_vec();
+    for i in (TELEMETRY_WIRE_SIZE..delta.len()).rev() {
+        delta[i] ^= delta[i - TELEMETRY_WIRE_SIZE];
+    }
+    let mut out = Vec::with_capacity(delta.len());
+    let mut i = 0;
+    while i < delta.len() {
+        if delta[i] == 0 {
+            let mut run = 1usize;
+            while run < 255 && i + run < delta.len() && delta[i + run] == 0 {
+                run += 1;
+            }
+            out.push(0);
+            out.push(run as u8);
+            i += run;
+        } else {
+            out.push(delta[i]);
+            i += 1;
+        }
+    }
+    out
+}
+
+/// Inverse of [`compress`]. `raw_len` bounds the output so a corrupt run
+/// length cannot balloon it; any mismatch returns `None`.
+pub fn decompress(data: &[u8], raw_len: usize) -> Option<Vec<u8>> {
+    let mut delta = Vec::with_capacity(raw_len);
+    let mut i = 0;
+    while i < data.len() {
+        if data[i] == 0 {
+            let run = *data.get(i + 1)? as usize;
+            if run == 0 || delta.len() + run > raw_len {
+                return None;
+            }
+            delta.resize(delta.len() + run, 0);
+            i += 2;
+        } else {
+            delta.push(data[i]);
+            i += 1;
+        }
+        if delta.len() > raw_len {
+            return None;
+        }
+    }
+    if delta.len() != raw_len {
+        return None;
+    }
+    for i in TELEMETRY_WIRE_SIZE..delta.len() {
+        delta[i] ^= delta[i - TELEMETRY_WIRE_SIZE];
+    }
+    Some(delta)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(seq: u32) -> Telemetry {
+        Telemetry {
+            seq,
+            timestamp_ms: 1_000 * seq as u64,
+            temperature: 21,
+            battery_mv: 11_900,
+            antenna_angle: 45,
+            boot_id: 0,
+            mode: None,
+            command_echo: None,
+        }
+    }
+
+    #[test]
+    fn batch_round_trips_and_consecutive_samples_compress() {
+        let samples: Vec<Telemetry> = (0..10).map(sample).collect();
+        let frame = encode_batch(&samples);
+        assert_eq!(frame[0], TELEMETRY_VERSION_BATCH);
+        assert_eq!(frame[1] & BATCH_FLAG_COMPRESSED, BATCH_FLAG_COMPRESSED);
+        assert!(
+            frame.len() < samples.len() * TELEMETRY_WIRE_SIZE,
+            "{} bytes on the wire for {} raw",
+            frame.len(),
+            samples.len() * TELEMETRY_WIRE_SIZE
+        );
+        let frames = decode_batch(&frame).expect("container decodes");
+        assert_eq!(frames.len(), samples.len());
+        for (wire, original) in frames.iter().zip(&samples) {
+            assert_eq!(Telemetry::from_bytes(wire), Some(*original));
+        }
+    }
+
+    #[test]
+    fn incompressible_payload_falls_back_to_uncompressed() {
+        // Alternating nonzero/zero bytes make every zero isolated, so the
+        // RLE pass would cost a byte per zero; the delta pass cannot help a
+        // single frame. The encoder must notice and send the payload as-is.
+        let awkward = Telemetry {
+            seq: 0x00FF_00FF,
+            timestamp_ms: 0x00FF_00FF_00FF_00FF,
+            temperature: 0x00FF,
+            battery_mv: 0x00FF,
+            antenna_angle: 0x00FF,
+            boot_id: 0,
+            mode: None,
+            command_echo: None,
+        };
+        let frame = encode_batch(&[awkward]);
+        assert_eq!(frame[1] & BATCH_FLAG_COMPRESSED, 0);
+        let frames = decode_batch(&frame).expect("container decodes");
+        assert_eq!(Telemetry::from_bytes(&frames[0]), Some(awkward));
+    }
+
+    #[test]
+    fn corrupt_containers_are_rejected() {
+        let frame = encode_batch(&(0..4).map(sample).collect::<Vec<_>>());
+        // Container CRC covers header and payload.
+        let mut corrupt = frame.clone();
+        corrupt[5] ^= 0x01;
+        assert_eq!(decode_batch(&corrupt), None);
+        // A wrong declared size fails after decompression.
+        let mut resized = frame.clone();
+        resized[2] = resized[2].wrapping_add(1);
+        let body_end = resized.len() - 2;
+        let crc = crc16_ccitt(&resized[..body_end]);
+        resized[body_end..].copy_from_slice(&crc.to_le_bytes());
+        assert_eq!(decode_batch(&resized), None);
+        // Truncation and foreign version bytes never panic.
+        assert_eq!(decode_batch(&frame[..3]), None);
+        assert_eq!(decode_batch(&[1u8, 0, 0, 0, 0, 0]), None);
+    }
+
+    #[test]
+    fn compress_round_trips_arbitrary_payloads() {
+        for len in [0usize, 1, TELEMETRY_WIRE_SIZE, 3 * TELEMETRY_WIRE_SIZE + 7] {
+            let payload: Vec<u8> = (0..len).map(|i| (i as u8).wrapping_mul(31)).collect();
+            let packed = compress(&payload);
+            assert_eq!(decompress(&packed, payload.len()), Some(payload));
+        }
+    }
+}
@@ -0,0 +1,1086 @@
+//! GCS binary: receives and monitors telemetry.
+
+use std::process;
+
+use wewinthis::gcs::GCS;
+use wewinthis::util::install_shutdown_flag;
+
+struct Args {
+    port: u16,
+    control_port: u16,
+    expected_interval_ms: u64,
+    status_every_secs: u64,
+    report_every_secs: u64,
+    warmup: u64,
+    warmup_secs: u64,
+    join_group: Option<std::net::Ipv4Addr>,
+    ocs_command: Option<String>,
+    ping_every_ms: u64,
+    critical_battery_mv: u16,
+    reuse_addr: bool,
+    key: Option<String>,
+    status_socket: Option<String>,
+    transport_tcp: bool,
+    inject_decode_delay_us: u64,
+    jitter_tolerance_ms: u64,
+    edge_streak: u64,
+    stuck_limit: u64,
+    duty_cycle: Option<(u64, u64)>,
+    forward: Option<String>,
+    allow: Vec<String>,
+    angle_convention: wewinthis::angle::AngleConvention,
+    alerts: Vec<(wewinthis::gcs::AlertField, wewinthis::gcs::FieldThreshold)>,
+    max_roc: Option<(f64, f64, f64)>,
+    health_weights: Option<(f64, f64, f64)>,
+    pin_cpu: Option<usize>,
+    rt_priority: Option<i32>,
+    log: Option<std::path::PathBuf>,
+    log_deltas: bool,
+    log_max_bytes: u64,
+    log_max_secs: u64,
+    log_keep: Option<usize>,
+    reject_log: Option<std::path::PathBuf>,
+    reject_log_rate: u32,
+    log_queue: usize,
+    degrade_budget_us: u64,
+    max_clock_skew_ms: u64,
+    max_clock_drift: f64,
+    busy_budget_pct: u64,
+    bind: Option<String>,
+    max_rate: Option<(u32, wewinthis::gcs::ShedPolicy)>,
+    export_histograms: Option<std::path::PathBuf>,
+    golden: Option<std::path::PathBuf>,
+    golden_tolerance_pct: f64,
+    save_golden: Option<std::path::PathBuf>,
+    no_self_check: bool,
+    dry_run: bool,
+}
+
+impl Args {
+    fn defaults() -> Self {
+        Args {
+            port: 8080,
+            control_port: 0,
+            expected_interval_ms: 1000,
+            status_every_secs: 5,
+            report_every_secs: wewinthis::gcs::DEFAULT_REPORT_EVERY_SECS,
+            warmup: wewinthis::gcs::DEFAULT_WARMUP_PACKETS,
+            warmup_secs: 0,
+            join_group: None,
+            ocs_command: None,
+            ping_every_ms: 0,
+            critical_battery_mv: 9_500,
+            reuse_addr: false,
+            key: None,
+            status_socket: None,
+            transport_tcp: false,
+            inject_decode_delay_us: 0,
+            jitter_tolerance_ms: wewinthis::gcs::DEFAULT_JITTER_TOLERANCE_MS,
+            edge_streak: wewinthis::gcs::DEFAULT_EDGE_STREAK_LIMIT,
+            stuck_limit: wewinthis::gcs::DEFAULT_STUCK_PACKET_LIMIT,
+            duty_cycle: None,
+            forward: None,
+            allow: Vec::new(),
+            angle_convention: wewinthis::angle::AngleConvention::Signed180,
+            alerts: Vec::new(),
+            max_roc: None,
+            health_weights: None,
+            pin_cpu: None,
+            rt_priority: None,
+            log: None,
+            log_deltas: false,
+            log_max_bytes: 10 * 1024 * 1024,
+            log_max_secs: 0,
+            log_keep: None,
+            reject_log: None,
+            reject_log_rate: wewinthis::logfile::DEFAULT_REJECTION_RATE,
+            log_queue: wewinthis::logfile::DEFAULT_LOG_QUEUE_CAPACITY,
+            degrade_budget_us: 0,
+            max_clock_skew_ms: 0,
+            max_clock_drift: wewinthis::gcs::DEFAULT_MAX_CLOCK_DRIFT_MS_PER_S,
+            busy_budget_pct: 0,
+            bind: None,
+            max_rate: None,
+            export_histograms: None,
+            golden: None,
+            golden_tolerance_pct: wewinthis::gcs::DEFAULT_GOLDEN_TOLERANCE_PCT,
+            save_golden: None,
+            no_self_check: false,
+            dry_run: false,
+        }
+    }
+}
+
+fn usage() -> ! {
+    eprintln!("usage: gcs [--config PATH] [--port PORT] [--control-port PORT][--expected-interval MS] [--status-every SECS (0=off)] [--report-every SECS (0=final only)][--warmup PACKETS] [--warmup-secs S] [--join MULTICAST_GROUP] [--ocs-command HOST:PORT] [--ping-every MS (0=off)] [--critical-battery MV] [--reuse-addr] [--key SECRET] [--status-socket PATH] [--transport udp|tcp] [--inject-decode-delay US] [--jitter-tolerance MS (0=off)] [--edge-streak N (0=off)] [--stuck-limit N (0=off)] [--duty-cycle ON_MS:OFF_MS] [--forward HOST:PORT] [--allow HOST,HOST (empty=accept all)] [--alert FIELD=WARN:ALARM:CLEAR] [--max-roc TEMP:BATT:ANT per packet] [--angle-convention signed|unsigned] [--health-weights TEMP:BATT:ANT] [--pin-cpu N] [--rt-priority 1-99] \
+         [--log FILE.csv|.jsonl] [--log-deltas] [--log-max-bytes N] [--log-max-secs S] [--log-keep K] [--reject-log FILE] [--reject-log-rate N/s (0=unlimited)] [--log-queue N (0=inline writes) [--degrade-budget US (0=off)] [--max-clock-skew MS (0=off)] [--max-clock-drift MS_PER_S] [--busy-budget PCT (0=off)] [--bind ADDR:PORT] [--max-rate N[:tail|:sample]] [--export-histograms FILE.csv] [--golden FILE.json] [--golden-tolerance PCT] [--save-golden FILE.json] [--no-self-check] [--dry-run]");
+    eprintln!("       gcs bench-decode [--frames N] [--seed N]");
+    eprintln!("       gcs decode HEXSTRING");
+    process::exit(2);
+}
+
+/// `gcs decode`: decodes one hex-encoded datagram through the same registry
+/// the receive path uses and prints the fields, or the specific reason the
+/// frame was rejected. Whitespace between digits is ignored, so a frame can
+/// be pasted straight from a rejection log or a packet dump.
+fn decode_hex(it: impl Iterator<Item = String>) -> ! {
+    let hex: String = it.collect::<Vec<_>>().join("");
+    let cleaned: String = hex.chars().filter(|c| !c.is_whitespace()).collect();
+    if cleaned.is_empty() {
+        eprintln!("missing hex string for decode");
+        usage();
+    }
+    if let Some(pos) = cleaned.chars().position(|c| !c.is_ascii_hexdigit()) {
+        eprintln!(
+            "[DECODE] not hex: character '{}' at digit {pos}",
+            cleaned.chars().nth(pos).unwrap()
+        );
+        process::exit(1);
+    }
+    if !cleaned.len().is_multiple_of(2) {
+        eprintln!(
+            "[DECODE] odd number of hex digits ({}); a byte needs two",
+            cleaned.len()
+        );
+        process::exit(1);
+    }
+    let data: Vec<u8> = (0..cleaned.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&cleaned[i..i + 2], 16).expect("digits checked above"))
+        .collect();
+
+    use wewinthis::telemetry::{
+        self, DecodeError, TELEMETRY_WIRE_SIZE, TELEMETRY_WIRE_SIZE_V2, TELEMETRY_WIRE_SIZE_V3,
+    };
+    println!("[DECODE] {} bytes", data.len());
+    match telemetry::DecoderRegistry::with_defaults().decode(&data) {
+        Ok(t) => {
+            println!("  version       {}", data[0]);
+            println!("  seq           {}", t.seq);
+            println!("  timestamp_ms  {}", t.timestamp_ms);
+            println!("  temperature   {} C", t.temperature);
+            println!("  battery       {} mV", t.battery_mv);
+            println!("  antenna       {} deg", t.antenna_angle);
+            if data[0] >= telemetry::TELEMETRY_VERSION_V2 {
+                println!("  boot_id       {}", t.boot_id);
+            }
+            if let Some(mode) = t.mode {
+                let label = wewinthis::mock_ocs::command::Mode::try_from_u8(mode)
+                    .map_or_else(|| format!("unknown({mode})"), |m| m.name().to_string());
+                println!("  mode          {label}");
+            }
+            process::exit(0);
+        }
+        Err(DecodeError::Empty) => {
+            eprintln!("[DECODE] rejected: empty frame (no version byte)");
+        }
+        Err(DecodeError::UnknownVersion(v)) => {
+            eprintln!(
+                "[DECODE] rejected: no decoder for version {v} (this build understands 1, 2, 3, 4, 6)"
+            );
+        }
+        Err(DecodeError::Malformed) => {
+            // Re-derive the precise reason the version's decoder gave up.
+            // TLV frames have no fixed size: report a CRC mismatch when that
+            // is the problem, otherwise blame the variable-length body.
+            if data[0] == telemetry::TELEMETRY_VERSION_TLV {
+                if data.len() >= 3 {
+                    let covered = data.len() - 2;
+                    let stored = u16::from_le_bytes([data[covered], data[covered + 1]]);
+                    let computed = telemetry::crc16_ccitt(&data[..covered]);
+                    if stored != computed {
+                        eprintln!(
+                            "[DECODE] rejected: CRC mismatch over bytes 0..{covered} \
+                             (stored {stored:#06x}, computed {computed:#06x})"
+                        );
+                        process::exit(1);
+                    }
+                }
+                eprintln!(
+                    "[DECODE] rejected: TLV body invalid (truncated triple, wrong \
+                     length for a known tag, or a mandatory field missing)"
+                );
+                process::exit(1);
+            }
+            // For the fixed formats it can only be a short frame or a
+            // checksum mismatch.
+            let expected = match data[0] {
+                telemetry::TELEMETRY_VERSION_V2 => TELEMETRY_WIRE_SIZE_V2,
+                telemetry::TELEMETRY_VERSION_V3 => TELEMETRY_WIRE_SIZE_V3,
+                _ => TELEMETRY_WIRE_SIZE,
+            };
+            if data.len() < expected {
+                eprintln!(
+                    "[DECODE] rejected: version {} frame needs {expected} bytes, got {}",
+                    data[0],
+                    data.len()
+                );
+            } else {
+                let covered = expected - 2;
+                let stored = u16::from_le_bytes([data[covered], data[covered + 1]]);
+                let computed = telemetry::crc16_ccitt(&data[..covered]);
+                eprintln!(
+                    "[DECODE] rejected: CRC mismatch over bytes 0..{covered} \
+                     (stored {stored:#06x}, computed {computed:#06x})"
+                );
+            }
+        }
+    }
+    process::exit(1);
+}
+
+/// `gcs bench-decode`: measures decode throughput over a seeded,
+/// pre-generated frame buffer, comparing both byte orders with and without
+/// checksum verification, then exits. No sockets are involved — this is the
+/// decode hot path in isolation.
+fn bench_decode(mut it: impl Iterator<Item = String>) -> ! {
+    let mut frames: usize = 200_000;
+    let mut seed: u64 = 1;
+    while let Some(flag) = it.next() {
+        let mut value = |name: &str| {
+            it.next().unwrap_or_else(|| {
+                eprintln!("missing value for {name}");
+                usage()
+            })
+        };
+        match flag.as_str() {
+            "--frames" => frames = value("--frames").parse().unwrap_or_else(|_| usage()),
+            "--seed" => seed = value("--seed").parse().unwrap_or_else(|_| usage()),
+            _ => usage(),
+        }
+    }
+    let frames = frames.max(1);
+
+    let mut generator = wewinthis::mock_ocs::generator::TelemetryGenerator::new(seed);
+    let mut le = Vec::with_capacity(frames);
+    let mut be = Vec::with_capacity(frames);
+    let mut tlv = Vec::with_capacity(frames);
+    let mut packed = Vec::with_capacity(frames);
+    for seq in 0..frames {
+        let mut t = generator.generate_normal(seq as u32, seq as u64 * 1000);
+        le.push(t.to_bytes());
+        be.push(t.to_bytes_be());
+        packed.push(t.to_bytes_packed());
+        // Fully-populated TLV frames, so the flexibility cost is measured
+        // against a body carrying the same information.
+        t.boot_id = 1;
+        t.mode = Some(0);
+        tlv.push(t.to_bytes_tlv());
+    }
+
+    println!("[BENCH] decoding {frames} frames per configuration (seed {seed})");
+    for (label, buffers, big_endian, verify_crc) in [
+        ("LE, CRC verified", &le, false, true),
+        ("LE, CRC skipped", &le, false, false),
+        ("BE, CRC verified", &be, true, true),
+        ("BE, CRC skipped", &be, true, false),
+    ] {
+        let start = std::time::Instant::now();
+        let mut decoded = 0u64;
+        for buf in buffers {
+            let t = wewinthis::telemetry::Telemetry::decode_with(buf, big_endian, verify_crc)
+                .expect("pre-generated frame decodes");
+            decoded += std::hint::black_box(t.seq) as u64 & 1;
+        }
+        let elapsed = start.elapsed();
+        std::hint::black_box(decoded);
+        let per_pkt_ns = elapsed.as_nanos() as f64 / frames as f64;
+        let rate = frames as f64 / elapsed.as_secs_f64();
+        println!("  {label:<18} {rate:>12.0} pkt/s  ({per_pkt_ns:.1} ns/pkt)");
+    }
+    // The self-describing format in the same harness, so its tag-walking
+    // overhead reads directly against the fixed-offset rows above.
+    for (label, verify_crc) in [("TLV, CRC verified", true), ("TLV, CRC skipped", false)] {
+        let start = std::time::Instant::now();
+        let mut decoded = 0u64;
+        for buf in &tlv {
+            let t = wewinthis::telemetry::Telemetry::decode_tlv_with(buf, verify_crc)
+                .expect("pre-generated frame decodes");
+            decoded += std::hint::black_box(t.seq) as u64 & 1;
+        }
+        let elapsed = start.elapsed();
+        std::hint::black_box(decoded);
+        let per_pkt_ns = elapsed.as_nanos() as f64 / frames as f64;
+        let rate = frames as f64 / elapsed.as_secs_f64();
+        println!("  {label:<18} {rate:>12.0} pkt/s  ({per_pkt_ns:.1} ns/pkt)");
+    }
+    // The bit-packed format: shift-and-mask decoding against the fixed
+    // rows, for sizing the CPU side of the bytes-saved trade.
+    for (label, verify_crc) in [("Packed, CRC verified", true), ("Packed, CRC skipped", false)] {
+        let start = std::time::Instant::now();
+        let mut decoded = 0u64;
+        for buf in &packed {
+            let t = wewinthis::telemetry::Telemetry::decode_packed_with(buf, verify_crc)
+                .expect("pre-generated frame decodes");
+            decoded += std::hint::black_box(t.seq) as u64 & 1;
+        }
+        let elapsed = start.elapsed();
+        std::hint::black_box(decoded);
+        let per_pkt_ns = elapsed.as_nanos() as f64 / frames as f64;
+        let rate = frames as f64 / elapsed.as_secs_f64();
+        println!("  {label:<18} {rate:>12.0} pkt/s  ({per_pkt_ns:.1} ns/pkt)");
+    }
+    process::exit(0);
+}
+
+/// Applies one option by its key name (the CLI flag without the leading
+/// `--`). Shared by the flag parser and the `[gcs]` section of a `--config`
+/// file so both accept the same keys with the same value syntax.
+fn apply_option(args: &mut Args, key: &str, value: &str) -> Result<(), String> {
+    let bad = || format!("invalid value '{value}' for {key}");
+    match key {
+        "port" => args.port = value.parse().map_err(|_| bad())?,
+        "control-port" => args.control_port = value.parse().map_err(|_| bad())?,
+        "expected-interval" => args.expected_interval_ms = value.parse().map_err(|_| bad())?,
+        "status-every" => args.status_every_secs = value.parse().map_err(|_| bad())?,
+        "report-every" => args.report_every_secs = value.parse().map_err(|_| bad())?,
+        "warmup" => args.warmup = value.parse().map_err(|_| bad())?,
+        "warmup-secs" => args.warmup_secs = value.parse().map_err(|_| bad())?,
+        "join" => args.join_group = Some(value.parse().map_err(|_| bad())?),
+        "ocs-command" => args.ocs_command = Some(value.to_string()),
+        "ping-every" => args.ping_every_ms = value.parse().map_err(|_| bad())?,
+        "key" => args.key = Some(value.to_string()),
+        "status-socket" => args.status_socket = Some(value.to_string()),
+        "inject-decode-delay" => {
+            args.inject_decode_delay_us = value.parse().map_err(|_| bad())?
+        }
+        "jitter-tolerance" => args.jitter_tolerance_ms = value.parse().map_err(|_| bad())?,
+        "edge-streak" => args.edge_streak = value.parse().map_err(|_| bad())?,
+        "stuck-limit" => args.stuck_limit = value.parse().map_err(|_| bad())?,
+        "duty-cycle" => {
+            let (on, off) = value.split_once(':').ok_or_else(bad)?;
+            args.duty_cycle =
+                Some((on.parse().map_err(|_| bad())?, off.parse().map_err(|_| bad())?));
+        }
+        "forward" => args.forward = Some(value.to_string()),
+        "allow" => args.allow.extend(
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|host| !host.is_empty())
+                .map(String::from),
+        ),
+        "angle-convention" => {
+            args.angle_convention =
+                wewinthis::angle::AngleConvention::parse(value).ok_or_else(bad)?
+        }
+        "alert" => {
+            let (name, levels) = value.split_once('=').ok_or_else(bad)?;
+            let field = wewinthis::gcs::AlertField::parse(name).ok_or_else(bad)?;
+            let mut parts = levels.split(':').map(str::parse::<f64>);
+            match (parts.next(), parts.next(), parts.next(), parts.next()) {
+                (Some(Ok(warn)), Some(Ok(alarm)), Some(Ok(clear)), None) => args
+                    .alerts
+                    .push((field, wewinthis::gcs::FieldThreshold { warn, alarm, clear })),
+                _ => return Err(bad()),
+            }
+        }
+        "export-histograms" => args.export_histograms = Some(value.into()),
+        "golden" => args.golden = Some(value.into()),
+        "golden-tolerance" => args.golden_tolerance_pct = value.parse().map_err(|_| bad())?,
+        "save-golden" => args.save_golden = Some(value.into()),
+        "max-rate" => {
+            let (cap, policy) = match value.split_once(':') {
+                None => (value, wewinthis::gcs::ShedPolicy::TailDrop),
+                Some((cap, "tail")) => (cap, wewinthis::gcs::ShedPolicy::TailDrop),
+                Some((cap, "sample")) => (cap, wewinthis::gcs::ShedPolicy::Sample),
+                Some(_) => return Err(bad()),
+            };
+            args.max_rate = Some((cap.parse().map_err(|_| bad())?, policy));
+        }
+        "max-roc" => {
+            let mut parts = value.split(':').map(str::parse::<f64>);
+            match (parts.next(), parts.next(), parts.next(), parts.next()) {
+                (Some(Ok(t)), Some(Ok(b)), Some(Ok(a)), None) => {
+                    args.max_roc = Some((t, b, a))
+                }
+                _ => return Err(bad()),
+            }
+        }
+        "health-weights" => {
+            let mut parts = value.split(':').map(str::parse::<f64>);
+            match (parts.next(), parts.next(), parts.next(), parts.next()) {
+                (Some(Ok(t)), Some(Ok(b)), Some(Ok(a)), None) => {
+                    args.health_weights = Some((t, b, a))
+                }
+                _ => return Err(bad()),
+            }
+        }
+        "pin-cpu" => args.pin_cpu = Some(value.parse().map_err(|_| bad())?),
+        "rt-priority" => args.rt_priority = Some(value.parse().map_err(|_| bad())?),
+        "log" => args.log = Some(value.into()),
+        "log-max-bytes" => args.log_max_bytes = value.parse().map_err(|_| bad())?,
+        "log-max-secs" => args.log_max_secs = value.parse().map_err(|_| bad())?,
+        "log-keep" => args.log_keep = Some(value.parse().map_err(|_| bad())?),
+        "reject-log" => args.reject_log = Some(value.into()),
+        "reject-log-rate" => args.reject_log_rate = value.parse().map_err(|_| bad())?,
+        "log-queue" => args.log_queue = value.parse().map_err(|_| bad())?,
+        "degrade-budget" => args.degrade_budget_us = value.parse().map_err(|_| bad())?,
+        "max-clock-skew" => args.max_clock_skew_ms = value.parse().map_err(|_| bad())?,
+        "max-clock-drift" => args.max_clock_drift = value.parse().map_err(|_| bad())?,
+        "busy-budget" => args.busy_budget_pct = value.parse().map_err(|_| bad())?,
+        "bind" => args.bind = Some(value.to_string()),
+        "transport" => match value {
+            "udp" => args.transport_tcp = false,
+            "tcp" => args.transport_tcp = true,
+            _ => return Err(bad()),
+        },
+        "critical-battery" => args.critical_battery_mv = value.parse().map_err(|_| bad())?,
+        // Bare flags on the CLI; `key = true` in a config file.
+        "reuse-addr" => args.reuse_addr = wewinthis::config::parse_bool(value).ok_or_else(bad)?,
+        "log-deltas" => args.log_deltas = wewinthis::config::parse_bool(value).ok_or_else(bad)?,
+        "no-self-check" => {
+            args.no_self_check = wewinthis::config::parse_bool(value).ok_or_else(bad)?
+        }
+        _ => return Err(format!("unknown option '{key}'")),
+    }
+    Ok(())
+}
+
+fn parse_args() -> Args {
+    let argv: Vec<String> = std::env::args().skip(1).collect();
+    let mut args = Args::defaults();
+
+    // Apply any config file first so explicit CLI flags override its values.
+    let mut i = 0;
+    while i < argv.len() {
+        if argv[i] == "--config" {
+            let Some(path) = argv.get(i + 1) else {
+                eprintln!("missing value for --config");
+                usage()
+            };
+            let config = match wewinthis::config::Config::load(std::path::Path::new(path)) {
+                Ok(c) => c,
+                Err(e) => {
+                    eprintln!("[GCS] {e}");
+                    process::exit(e.exit_code());
+                }
+            };
+            for (key, value) in config.section("gcs") {
+                if let Err(e) = apply_option(&mut args, key, value) {
+                    eprintln!("[GCS] {path}: [gcs] {e}");
+                    process::exit(2);
+                }
+            }
+            i += 1;
+        }
+        i += 1;
+    }
+
+    let mut it = argv.iter();
+    while let Some(flag) = it.next() {
+        let mut value = |name: &str| {
+            it.next().cloned().unwrap_or_else(|| {
+                eprintln!("missing value for {name}");
+                usage()
+            })
+        };
+        match flag.as_str() {
+            "--config" => {
+                value("--config");
+            }
+            "--reuse-addr" => args.reuse_addr = true,
+            "--log-deltas" => args.log_deltas = true,
+            "--no-self-check" => args.no_self_check = true,
+            "--dry-run" => args.dry_run = true,
+            _ => {
+                let Some(key) = flag.strip_prefix("--") else { usage() };
+                let value = value(flag);
+                if let Err(e) = apply_option(&mut args, key, &value) {
+                    eprintln!("{e}");
+                    usage();
+                }
+            }
+        }
+    }
+    args
+}
+
+/// Validates the parsed configuration and prints the run plan without binding
+/// any socket, then exits (non-zero on any problem).
+fn dry_run(args: &Args) -> ! {
+    use std::net::ToSocketAddrs;
+
+    let mut problems = Vec::new();
+
+    if args.expected_interval_ms == 0 {
+        problems.push("expected interval must be at least 1 ms".to_string());
+    }
+    if let Some(group) = args.join_group {
+        if !group.is_multicast() {
+            problems.push(format!("{group} is not a multicast group"));
+        }
+        if args.transport_tcp {
+            problems.push("multicast join is meaningless over tcp transport".to_string());
+        }
+    }
+    if let Some(addr) = &args.bind {
+        if args.transport_tcp {
+            problems.push("--bind applies to the udp transport".to_string());
+        }
+        if args.reuse_addr {
+            problems.push("--reuse-addr is wired to the default all-interfaces bind; drop one".to_string());
+        }
+        if let Err(e) = addr.to_socket_addrs() {
+            problems.push(format!("bind address '{addr}' does not resolve: {e}"));
+        }
+    }
+    if let Some((on, off)) = args.duty_cycle {
+        if on == 0 || off == 0 {
+            problems.push(format!("duty cycle {on}:{off} windows must both be positive"));
+        }
+    }
+    if let Some(priority) = args.rt_priority {
+        if !(1..=99).contains(&priority) {
+            problems.push(format!("real-time priority {priority} outside 1..=99"));
+        }
+    }
+    if let Some((t, b, a)) = args.max_roc {
+        if t <= 0.0 || b <= 0.0 || a <= 0.0 {
+            problems.push(format!(
+                "rate-of-change limits {t}:{b}:{a} must all be positive"
+            ));
+        }
+    }
+    if let Some((t, b, a)) = args.health_weights {
+        if t < 0.0 || b < 0.0 || a < 0.0 {
+            problems.push(format!("health weights {t}:{b}:{a} must be non-negative"));
+        } else if t + b + a == 0.0 {
+            problems.push("health weights must not all be zero".to_string());
+        }
+    }
+    if args.ping_every_ms > 0 && args.ocs_command.is_none() {
+        problems.push("ping probe needs --ocs-command for the uplink address".to_string());
+    }
+    if let Some(addr) = &args.ocs_command {
+        if let Err(e) = addr.to_socket_addrs() {
+            problems.push(format!("OCS command address '{addr}' does not resolve: {e}"));
+        }
+    }
+    if let Some(addr) = &args.forward {
+        if let Err(e) = addr.to_socket_addrs() {
+            problems.push(format!("forward address '{addr}' does not resolve: {e}"));
+        }
+    }
+    for host in &args.allow {
+        if let Err(e) = (host.as_str(), 0u16).to_socket_addrs() {
+            problems.push(format!("allowed source '{host}' does not resolve: {e}"));
+        }
+    }
+    for (field, threshold) in &args.alerts {
+        if !threshold.ordered_for(*field) {
+            problems.push(format!(
+                "{} thresholds warn={} alarm={} clear={} out of order",
+                field.name(),
+                threshold.warn,
+                threshold.alarm,
+                threshold.clear
+            ));
+        }
+    }
+    if let Some(path) = &args.log {
+        let dir = path.parent().filter(|p| !p.as_os_str().is_empty());
+        if let Some(dir) = dir {
+            if !dir.is_dir() {
+                problems.push(format!("log directory {} does not exist", dir.display()));
+            }
+        }
+        if args.log_max_bytes == 0 {
+            problems.push("log max bytes must be positive".to_string());
+        }
+        if args.log_keep == Some(0) {
+            problems.push("log keep count must be positive".to_string());
+        }
+    }
+    if let Some(path) = &args.reject_log {
+        let dir = path.parent().filter(|p| !p.as_os_str().is_empty());
+        if let Some(dir) = dir {
+            if !dir.is_dir() {
+                problems.push(format!(
+                    "reject log directory {} does not exist",
+                    dir.display()
+                ));
+            }
+        }
+    }
+    if args.golden_tolerance_pct < 0.0 {
+        problems.push(format!(
+            "golden tolerance {}% must not be negative",
+            args.golden_tolerance_pct
+        ));
+    }
+    if let Some(path) = &args.golden {
+        if let Err(e) = wewinthis::gcs::MetricsSnapshot::load(path) {
+            problems.push(format!("golden snapshot {}: {e}", path.display()));
+        }
+    }
+
+    println!("[GCS] dry run: plan");
+    if let Some(addr) = &args.bind {
+        println!("  listen        {addr} (udp)");
+    } else {
+        println!(
+            "  listen        port {} ({})",
+            args.port,
+            if args.transport_tcp { "tcp" } else { "udp" }
+        );
+    }
+    if args.control_port > 0 {
+        println!("  control port  {}", args.control_port);
+    }
+    println!("  expected rate 1 packet / {} ms", args.expected_interval_ms);
+    println!(
+        "  reporting     status every {} s, full report every {} s (0=off)",
+        args.status_every_secs, args.report_every_secs
+    );
+    println!(
+        "  thresholds    jitter {} ms, edge streak {}, stuck limit {}, warmup {} packets{}",
+        args.jitter_tolerance_ms,
+        args.edge_streak,
+        args.stuck_limit,
+        args.warmup,
+        if args.warmup_secs > 0 {
+            format!(" or {} s", args.warmup_secs)
+        } else {
+            String::new()
+        }
+    );
+    if let Some((on, off)) = args.duty_cycle {
+        println!("  duty cycle    tolerating {off} ms scheduled gaps ({on} ms on-window)");
+    }
+    if let Some(addr) = &args.forward {
+        println!("  forward tap   {addr}");
+    }
+    if !args.allow.is_empty() {
+        println!("  allow         {}", args.allow.join(", "));
+    }
+    for (field, threshold) in &args.alerts {
+        println!(
+            "  alert         {} warn {} / alarm {} / clear {}",
+            field.name(),
+            threshold.warn,
+            threshold.alarm,
+            threshold.clear
+        );
+    }
+    if let Some((t, b, a)) = args.max_roc {
+        println!("  rate limits   temp {t} deg / batt {b} mV / ant {a} deg per packet");
+    }
+    if let Some((t, b, a)) = args.health_weights {
+        println!("  health score  weights temp={t} batt={b} ant={a}");
+    }
+    if args.pin_cpu.is_some() || args.rt_priority.is_some() {
+        println!(
+            "  scheduling    cpu {}, rt priority {}",
+            args.pin_cpu.map_or("any".to_string(), |c| c.to_string()),
+            args.rt_priority.map_or("normal".to_string(), |p| p.to_string())
+        );
+    }
+    if let Some(addr) = &args.ocs_command {
+        println!(
+            "  auto-safe     below {} mV via {addr}",
+            args.critical_battery_mv
+        );
+    }
+    if let Some(group) = args.join_group {
+        println!("  multicast     {group}");
+    }
+    if let Some(path) = &args.log {
+        println!(
+            "  capture log   {}{} (max {} bytes{}{})",
+            path.display(),
+            if args.log_deltas { " with arrival deltas" } else { "" },
+            args.log_max_bytes,
+            if args.log_max_secs > 0 {
+                format!(", max {} s", args.log_max_secs)
+            } else {
+                String::new()
+            },
+            args.log_keep
+                .map_or(String::new(), |k| format!(", keep {k}"))
+        );
+    }
+    if let Some(path) = &args.reject_log {
+        println!(
+            "  reject log    {} ({})",
+            path.display(),
+            if args.reject_log_rate > 0 {
+                format!("max {}/s", args.reject_log_rate)
+            } else {
+                "unlimited".to_string()
+            }
+        );
+    }
+    if args.degrade_budget_us > 0 {
+        println!(
+            "  degradation   shed optional work beyond {} us/packet",
+            args.degrade_budget_us
+        );
+    }
+    if args.max_clock_skew_ms > 0 {
+        println!(
+            "  clock skew    alarm beyond {} ms offset or {} ms/s drift",
+            args.max_clock_skew_ms, args.max_clock_drift
+        );
+    }
+    if args.busy_budget_pct > 0 {
+        println!(
+            "  busy budget   alarm beyond {}% receive-loop busy time",
+            args.busy_budget_pct
+        );
+    }
+    if args.log_queue > 0 && (args.log.is_some() || args.reject_log.is_some()) {
+        println!("  log queue     {} records (background writer)", args.log_queue);
+    }
+    if let Some(path) = &args.golden {
+        println!(
+            "  golden run    {} (tolerance {}%)",
+            path.display(),
+            args.golden_tolerance_pct
+        );
+    }
+    if args.key.is_some() {
+        println!("  auth          HMAC tags enforced");
+    }
+    if let Some(path) = &args.status_socket {
+        println!("  status stream {path}");
+    }
+
+    if problems.is_empty() {
+        println!("[GCS] dry run: configuration OK");
+        process::exit(0);
+    }
+    for problem in &problems {
+        eprintln!("[GCS] dry run: {problem}");
+    }
+    process::exit(1);
+}
+
+fn main() {
+    let mut raw = std::env::args().skip(1).peekable();
+    if raw.peek().map(String::as_str) == Some("bench-decode") {
+        raw.next();
+        bench_decode(raw);
+    }
+    if raw.peek().map(String::as_str) == Some("decode") {
+        raw.next();
+        decode_hex(raw);
+    }
+    let args = parse_args();
+    if args.dry_run {
+        dry_run(&args);
+    }
+    let shutdown = install_shutdown_flag();
+
+    if args.bind.is_some() && (args.transport_tcp || args.reuse_addr) {
+        eprintln!("[GCS] --bind applies to the udp transport and replaces the default bind; drop --transport tcp / --reuse-addr");
+        process::exit(2);
+    }
+    let bind_result = if args.transport_tcp {
+        GCS::bind_tcp(args.port, args.expected_interval_ms)
+    } else if let Some(addr) = &args.bind {
+        GCS::with_bind_addr(addr.as_str(), args.expected_interval_ms)
+    } else {
+        GCS::bind(args.port, args.expected_interval_ms, args.reuse_addr)
+    };
+    let mut gcs = match bind_result {
+        Ok(g) => g,
+        Err(e) => {
+            eprintln!("[GCS] startup failed: {e}");
+            process::exit(e.exit_code());
+        }
+    };
+    gcs.set_status_interval(args.status_every_secs);
+    gcs.set_report_interval(args.report_every_secs);
+    if args.control_port > 0 {
+        match gcs.bind_control(args.control_port) {
+            Ok(()) => println!("[GCS] control port {} open", args.control_port),
+            Err(e) => {
+                eprintln!("[GCS] {e}");
+                process::exit(1);
+            }
+        }
+    }
+    if args.inject_decode_delay_us > 0 {
+        gcs.set_inject_decode_delay(args.inject_decode_delay_us);
+        println!(
+            "[GCS] debug: injecting {} us of decode delay",
+            args.inject_decode_delay_us
+        );
+    }
+    gcs.set_warmup(args.warmup);
+    gcs.set_warmup_secs(args.warmup_secs);
+    gcs.set_angle_convention(args.angle_convention);
+    gcs.set_jitter_tolerance(args.jitter_tolerance_ms);
+    gcs.set_edge_streak_limit(args.edge_streak);
+    gcs.set_stuck_limit(args.stuck_limit);
+    if let Some((on, off)) = args.duty_cycle {
+        gcs.set_duty_cycle(off);
+        println!("[GCS] expecting duty-cycled downlink: {on} ms on, {off} ms off");
+    }
+    if let Some(addr) = &args.forward {
+        match gcs.set_forward(addr) {
+            Ok(()) => println!("[GCS] forwarding valid telemetry to {addr}"),
+            Err(e) => {
+                eprintln!("[GCS] cannot forward to {addr}: {e}");
+                process::exit(1);
+            }
+        }
+    }
+    if !args.allow.is_empty() {
+        match gcs.set_allowed_sources(&args.allow) {
+            Ok(()) => println!(
+                "[GCS] accepting telemetry only from: {}",
+                args.allow.join(", ")
+            ),
+            Err(e) => {
+                eprintln!("[GCS] bad allowlist: {e}");
+                process::exit(1);
+            }
+        }
+    }
+    for (field, threshold) in &args.alerts {
+        match gcs.set_alert_threshold(*field, *threshold) {
+            Ok(()) => println!(
+                "[GCS] {} alerts: warn {} / alarm {} / clear {}",
+                field.name(),
+                threshold.warn,
+                threshold.alarm,
+                threshold.clear
+            ),
+            Err(e) => {
+                eprintln!("[GCS] {e}");
+                process::exit(2);
+            }
+        }
+    }
+    if let Some((cap, policy)) = args.max_rate {
+        match gcs.set_rate_cap(cap, policy) {
+            Ok(()) => println!(
+                "[GCS] rate cap: {cap} datagrams/s ({})",
+                match policy {
+                    wewinthis::gcs::ShedPolicy::TailDrop => "tail-drop",
+                    wewinthis::gcs::ShedPolicy::Sample => "sampled",
+                }
+            ),
+            Err(e) => {
+                eprintln!("[GCS] {e}");
+                process::exit(2);
+            }
+        }
+    }
+    if let Some((t, b, a)) = args.max_roc {
+        match gcs.set_rate_of_change_limits(wewinthis::gcs::RateOfChangeLimits {
+            temperature: t,
+            battery: b,
+            antenna: a,
+        }) {
+            Ok(()) => println!(
+                "[GCS] rate-of-change limits: temp {t} deg / batt {b} mV / ant {a} deg per packet"
+            ),
+            Err(e) => {
+                eprintln!("[GCS] {e}");
+                process::exit(2);
+            }
+        }
+    }
+    if let Some((t, b, a)) = args.health_weights {
+        gcs.set_health_weights(wewinthis::gcs::HealthWeights {
+            temperature: t,
+            battery: b,
+            antenna: a,
+        });
+        println!("[GCS] health-score weights: temp={t} batt={b} ant={a}");
+    }
+    if let Some(path) = &args.log {
+        let policy = wewinthis::logfile::RotationPolicy {
+            max_bytes: args.log_max_bytes,
+            max_age: (args.log_max_secs > 0)
+                .then(|| std::time::Duration::from_secs(args.log_max_secs)),
+            keep: args.log_keep,
+        };
+        let format = wewinthis::logfile::LogFormat::from_path(path);
+        match wewinthis::logfile::TelemetryLog::create_with_deltas(
+            path,
+            format,
+            policy,
+            args.log_deltas,
+        ) {
+            Ok(log) => {
+                println!("[GCS] capturing telemetry to {}", log.current_path().display());
+                gcs.set_capture_log(log);
+            }
+            Err(e) => {
+                eprintln!("[GCS] cannot open capture log {}: {e}", path.display());
+                process::exit(1);
+            }
+        }
+    }
+    if let Some(path) = &args.reject_log {
+        match wewinthis::logfile::RejectionLog::create(path, args.reject_log_rate) {
+            Ok(log) => {
+                println!(
+                    "[GCS] logging rejected datagrams to {}",
+                    log.current_path().display()
+                );
+                gcs.set_reject_log(log);
+            }
+            Err(e) => {
+                eprintln!("[GCS] cannot open reject log {}: {e}", path.display());
+                process::exit(1);
+            }
+        }
+    }
+    if args.degrade_budget_us > 0 {
+        gcs.set_degradation_budget(args.degrade_budget_us);
+        println!(
+            "[GCS] degradation ladder armed: {} us/packet budget",
+            args.degrade_budget_us
+        );
+    }
+    if args.max_clock_skew_ms > 0 {
+        gcs.set_clock_skew_bound(args.max_clock_skew_ms, args.max_clock_drift);
+        println!(
+            "[GCS] clock-skew watchdog armed: +/-{} ms offset, {} ms/s drift",
+            args.max_clock_skew_ms, args.max_clock_drift
+        );
+    }
+    if args.busy_budget_pct > 0 {
+        gcs.set_busy_budget(args.busy_budget_pct);
+        println!(
+            "[GCS] busy-fraction gauge armed: {}% receive-loop budget",
+            args.busy_budget_pct
+        );
+    }
+    if args.log_queue > 0 && (args.log.is_some() || args.reject_log.is_some()) {
+        gcs.set_async_logging(args.log_queue);
+        println!(
+            "[GCS] file logging moved to a background thread ({} record queue)",
+            args.log_queue
+        );
+    }
+    if let Some(key) = &args.key {
+        gcs.set_key(key.clone().into_bytes());
+        println!("[GCS] telemetry authentication enforced");
+    }
+    if let Some(path) = &args.status_socket {
+        match gcs.set_status_socket(path) {
+            Ok(()) => println!("[GCS] status stream listening on {path}"),
+            Err(e) => {
+                eprintln!("[GCS] cannot open status socket {path}: {e}");
+                process::exit(1);
+            }
+        }
+    }
+    if let Some(addr) = &args.ocs_command {
+        let floor = args.critical_battery_mv;
+        match gcs.enable_auto_safe(addr, floor, floor.saturating_add(500)) {
+            Ok(()) => println!(
+                "[GCS] auto-safe armed: SET_MODE safe below {floor} mV (uplink {addr})"
+            ),
+            Err(e) => {
+                eprintln!("[GCS] cannot reach OCS command port {addr}: {e}");
+                process::exit(1);
+            }
+        }
+    }
+    if let (Some(addr), true) = (&args.ocs_command, args.ping_every_ms > 0) {
+        match gcs.enable_ping(addr, args.ping_every_ms) {
+            Ok(()) => println!(
+                "[GCS] probing command round trip every {} ms (uplink {addr})",
+                args.ping_every_ms
+            ),
+            Err(e) => {
+                eprintln!("[GCS] cannot start ping probe toward {addr}: {e}");
+                process::exit(1);
+            }
+        }
+    }
+    if let Some(group) = args.join_group {
+        match gcs.join_multicast(group) {
+            Ok(()) => println!("[GCS] joined multicast group {group}"),
+            Err(e) => {
+                eprintln!("[GCS] failed to join multicast group {group}: {e}");
+                process::exit(1);
+            }
+        }
+    }
+    // Loopback self-check last, once every decoder and option is in place.
+    // Only the UDP transport can receive its own probe.
+    if !args.no_self_check && !args.transport_tcp {
+        match gcs.self_check() {
+            Ok(()) => println!("[GCS] self-check OK"),
+            Err(e) => {
+                eprintln!("[GCS] self-check failed: {e}");
+                process::exit(1);
+            }
+        }
+    }
+    // Scheduling tweaks apply to this thread, which runs the receive loop;
+    // compare the report's jitter figures across runs to judge the effect.
+    if let Some(cpu) = args.pin_cpu {
+        match wewinthis::util::pin_to_cpu(cpu) {
+            Ok(()) => println!("[GCS] receive loop pinned to CPU {cpu}"),
+            Err(e) => eprintln!("[GCS] CPU pinning failed ({e}); continuing unpinned"),
+        }
+    }
+    if let Some(priority) = args.rt_priority {
+        match wewinthis::util::set_realtime_priority(priority) {
+            Ok(()) => println!("[GCS] receive loop at SCHED_FIFO priority {priority}"),
+            Err(e) => eprintln!(
+                "[GCS] real-time priority failed ({e}); continuing at normal priority \
+                 (needs root or CAP_SYS_NICE)"
+            ),
+        }
+    }
+    gcs.run(shutdown);
+    if let Some(path) = &args.export_histograms {
+        match gcs.metrics.export_histograms_csv(path) {
+            Ok(()) => println!("[GCS] histograms exported to {}", path.display()),
+            Err(e) => eprintln!("[GCS] histogram export to {} failed: {e}", path.display()),
+        }
+    }
+    if let Some(path) = &args.save_golden {
+        match gcs.metrics.snapshot().save(path) {
+            Ok(()) => println!("[GCS] golden snapshot saved to {}", path.display()),
+            Err(e) => eprintln!("[GCS] cannot save golden snapshot {}: {e}", path.display()),
+        }
+    }
+    // Severity bitmask from the final snapshot, so CI can classify the run
+    // without parsing the report; 0 means every constraint was met.
+    let mut code = gcs.metrics.exit_code();
+    if let Some(path) = &args.golden {
+        match wewinthis::gcs::MetricsSnapshot::load(path) {
+            Ok(golden) => {
+                let current = gcs.metrics.snapshot();
+                let regressions =
+                    current.regressions_against(&golden, args.golden_tolerance_pct);
+                if regressions.is_empty() {
+                    println!(
+                        "[GCS] golden comparison PASS (tolerance {}%, {} packets vs {} golden)",
+                        args.golden_tolerance_pct,
+                        current.packets_received,
+                        golden.packets_received
+                    );
+                } else {
+                    println!(
+                        "[GCS] golden comparison FAIL (tolerance {}%):",
+                        args.golden_tolerance_pct
+                    );
+                    for regression in &regressions {
+                        println!("  {regression}");
+                    }
+                    code |= wewinthis::gcs::EXIT_GOLDEN_REGRESSION;
+                }
+            }
+            Err(e) => {
+                eprintln!("[GCS] cannot load golden snapshot {}: {e}", path.display());
+                code |= wewinthis::gcs::EXIT_GOLDEN_REGRESSION;
+            }
+        }
+    }
+    if code != 0 {
+        println!("[GCS] exiting with severity code {code}");
+    }
+    process::exit(code);
+}
@@ -0,0 +1,1044 @@
+//! Mock OCS binary: generates and downlinks telemetry.
+
+use std::process;
+use std::sync::Arc;
+
+use wewinthis::mock_ocs::command::{CommandReceiver, Mode, OcsShared};
+use wewinthis::mock_ocs::state::PersistedState;
+use wewinthis::mock_ocs::{LinkImpairment, MockOCS};
+use wewinthis::util::install_shutdown_flag;
+
+struct Args {
+    target: String,
+    interval_ms: u64,
+    count: u64,
+    mode: Mode,
+    edge_ratio: f64,
+    command_port: u16,
+    seed: u64,
+    state_file: Option<std::path::PathBuf>,
+    send_log: Option<std::path::PathBuf>,
+    slew_rate: f64,
+    thermal_coupling: f64,
+    warmup: u64,
+    reuse_addr: bool,
+    history: usize,
+    key: Option<String>,
+    temp_expr: Option<String>,
+    battery_expr: Option<String>,
+    transport_tcp: bool,
+    extra_targets: Vec<String>,
+    target_set: bool,
+    reconnect_every_ms: u64,
+    reconnect_max: Option<u64>,
+    campaign: Option<String>,
+    recovery_budget_ms: u64,
+    corrupt_field: Option<wewinthis::mock_ocs::CorruptField>,
+    corrupt_before_crc: bool,
+    corrupt_rate: f64,
+    flatline_field: Option<wewinthis::mock_ocs::CorruptField>,
+    flatline_packets: u64,
+    spike_field: Option<wewinthis::mock_ocs::CorruptField>,
+    spike_value: i32,
+    spike_after: u64,
+    battery_floor_mv: u16,
+    battery_clear_mv: Option<u16>,
+    duty_cycle: Option<(u64, u64)>,
+    chaos_level: f64,
+    downlink_impair: Option<(u64, u64, f64)>,
+    uplink_impair: Option<(u64, u64, f64)>,
+    angle_convention: wewinthis::angle::AngleConvention,
+    dscp: Option<String>,
+    max_duration: Option<std::time::Duration>,
+    boot_id: bool,
+    mode_echo: bool,
+    command_echo: bool,
+    tlv: bool,
+    packed: bool,
+    batch: usize,
+    random_start: bool,
+    timestamp_base_ms: Option<u64>,
+    met_epoch_ms: Option<u64>,
+    dry_run: bool,
+}
+
+impl Args {
+    fn defaults() -> Self {
+        Args {
+            target: "127.0.0.1:8080".to_string(),
+            interval_ms: 1000,
+            count: 0,
+            mode: Mode::Normal,
+            edge_ratio: 0.2,
+            command_port: 9000,
+            seed: 0,
+            state_file: None,
+            send_log: None,
+            slew_rate: wewinthis::mock_ocs::generator::DEFAULT_SLEW_RATE_DEG,
+            thermal_coupling: 0.0,
+            warmup: wewinthis::mock_ocs::DEFAULT_WARMUP_PACKETS,
+            reuse_addr: false,
+            history: wewinthis::mock_ocs::command::DEFAULT_HISTORY_CAPACITY,
+            key: None,
+            temp_expr: None,
+            battery_expr: None,
+            transport_tcp: false,
+            extra_targets: Vec::new(),
+            target_set: false,
+            reconnect_every_ms: 0,
+            reconnect_max: None,
+            campaign: None,
+            recovery_budget_ms: 2_000,
+            corrupt_field: None,
+            corrupt_before_crc: false,
+            corrupt_rate: 1.0,
+            flatline_field: None,
+            flatline_packets: 100,
+            spike_field: None,
+            spike_value: 0,
+            spike_after: 0,
+            battery_floor_mv: 0,
+            battery_clear_mv: None,
+            duty_cycle: None,
+            chaos_level: 0.0,
+            downlink_impair: None,
+            uplink_impair: None,
+            angle_convention: wewinthis::angle::AngleConvention::Signed180,
+            dscp: None,
+            max_duration: None,
+            boot_id: false,
+            mode_echo: false,
+            command_echo: false,
+            tlv: false,
+            packed: false,
+            batch: 0,
+            random_start: false,
+            timestamp_base_ms: None,
+            met_epoch_ms: None,
+            dry_run: false,
+        }
+    }
+}
+
+fn usage() -> ! {
+    eprintln!(
+        "usage: ocs [--config PATH] [--target HOST:PORT (repeatable)] [--interval MS] [--count N] \
+         [--mode normal|edge|mixed|safe] [--edge-ratio R] [--command-port PORT] [--seed N] \
+         [--state-file PATH] [--send-log FILE.csv] [--slew-rate DEG_PER_PACKET] [--thermal-coupling DEG_PER_DEGC][--warmup PACKETS] [--reuse-addr] [--history N] [--key SECRET] \
+         [--temp-expr EXPR] [--battery-expr EXPR] [--transport udp|tcp] \
+         [--reconnect-every MS (0=off)] [--reconnect-max N|unlimited] \
+         [--campaign NAME] [--recovery-budget MS] \
+         [--corrupt-field temp|battery|antenna] [--corrupt-before-crc] [--corrupt-rate R] \
+         [--flatline-field temp|battery|antenna] [--flatline-packets N] \
+         [--spike-field temp|battery|antenna] [--spike-value V] [--spike-after N] \
+         [--battery-floor MV (0=off)] [--battery-clear MV] [--duty-cycle ON_MS:OFF_MS] [--chaos-level 0..1] \
+         [--downlink-impair DELAY_MS:JITTER_MS:LOSS] [--uplink-impair DELAY_MS:JITTER_MS:LOSS] [--angle-convention signed|unsigned] [--dscp NAME|0-63] [--max-duration 60s] [--boot-id] [--mode-echo] [--command-echo] [--tlv] [--packed] [--batch N (0=off)] [--random-start] [--timestamp-base MS] [--met-epoch MS] [--dry-run]"
+    );
+    eprintln!("       ocs gen-vectors DIR [--seed N]");
+    process::exit(2);
+}
+
+/// Formats each vector is emitted in: the two version-1 byte orders, the
+/// boot-id (v2) and mode (v3) extensions, the TLV container and the
+/// bit-packed frame.
+const VECTOR_FORMATS: [&str; 6] = ["v1le", "v1be", "v2", "v3", "tlv", "packed"];
+
+fn encode_vector(t: &wewinthis::telemetry::Telemetry, format: &str) -> Vec<u8> {
+    match format {
+        "v1le" => t.to_bytes().to_vec(),
+        "v1be" => t.to_bytes_be().to_vec(),
+        "v2" => t.to_bytes_v2().to_vec(),
+        "v3" => t.to_bytes_v3().to_vec(),
+        "tlv" => t.to_bytes_tlv(),
+        "packed" => t.to_bytes_packed().to_vec(),
+        _ => unreachable!("unknown vector format {format}"),
+    }
+}
+
+fn decode_vector(data: &[u8], format: &str) -> Option<wewinthis::telemetry::Telemetry> {
+    use wewinthis::telemetry::Telemetry;
+    match format {
+        "v1le" => Telemetry::from_bytes(data),
+        "v1be" => Telemetry::from_bytes_be(data),
+        "v2" => Telemetry::from_bytes_v2(data),
+        "v3" => Telemetry::from_bytes_v3(data),
+        "tlv" => Telemetry::from_bytes_tlv(data),
+        "packed" => Telemetry::from_bytes_packed(data),
+        _ => unreachable!("unknown vector format {format}"),
+    }
+}
+
+/// `ocs gen-vectors`: writes a fixed corpus of canonical telemetry frames —
+/// nominal, safe mode, every edge case and the encoding boundary values —
+/// to a directory, as raw binary plus hex for each wire format, with a CSV
+/// manifest of the decoded values every frame must produce. Another
+/// implementation (or our own decoder tests) can diff itself against the
+/// corpus, so endianness, checksum or version drift shows up as a changed
+/// file instead of a silent incompatibility. The manifest records what each
+/// *format* preserves: version 1 drops the boot id and mode, the packed
+/// frame saturates out-of-range fields, and the rows say so. Deterministic
+/// for a given `--seed`.
+fn gen_vectors(mut it: impl Iterator<Item = String>) -> ! {
+    use wewinthis::mock_ocs::generator::{EdgeKind, TelemetryGenerator};
+    use wewinthis::telemetry::{
+        Telemetry, PACKED_ANGLE_MAX, PACKED_ANGLE_MIN, PACKED_BATTERY_MAX, PACKED_TEMP_MAX,
+        PACKED_TEMP_MIN, PACKED_TIMESTAMP_MAX,
+    };
+
+    let mut dir: Option<std::path::PathBuf> = None;
+    let mut seed: u64 = 1;
+    while let Some(flag) = it.next() {
+        match flag.as_str() {
+            "--seed" => {
+                seed = it.next().and_then(|v| v.parse().ok()).unwrap_or_else(|| {
+                    eprintln!("invalid or missing value for --seed");
+                    usage()
+                })
+            }
+            _ if dir.is_none() && !flag.starts_with("--") => dir = Some(flag.into()),
+            _ => usage(),
+        }
+    }
+    let Some(dir) = dir else {
+        eprintln!("missing output directory for gen-vectors");
+        usage()
+    };
+
+    let mut generator = TelemetryGenerator::new(seed);
+    let mut vectors: Vec<(String, Telemetry)> = Vec::new();
+    vectors.push(("nominal".to_string(), generator.generate_normal(1, 1_000)));
+    vectors.push(("safe".to_string(), generator.generate_safe(2, 2_000)));
+    let kinds = [
+        EdgeKind::HotTemperature,
+        EdgeKind::ColdTemperature,
+        EdgeKind::DeadBattery,
+        EdgeKind::Overvolt,
+        EdgeKind::AntennaHigh,
+        EdgeKind::AntennaLow,
+    ];
+    for (i, kind) in kinds.iter().enumerate() {
+        let seq = 3 + i as u32;
+        let t = generator.generate_edge_sequence(seq, u64::from(seq) * 1_000, &[*kind]);
+        vectors.push((format!("edge-{}", kind.name()), t));
+    }
+    // Hand-built boundary samples: the optional fields populated, the exact
+    // limits of the bit-packed encoding, and the full fixed-field ranges
+    // (which the packed files saturate — the manifest rows carry the
+    // saturated values).
+    vectors.push((
+        "optional-fields".to_string(),
+        Telemetry {
+            seq: 100,
+            timestamp_ms: 100_000,
+            temperature: 21,
+            battery_mv: 12_000,
+            antenna_angle: -45,
+            boot_id: 7,
+            mode: Some(1),
+            command_echo: Some(42),
+        },
+    ));
+    let boundary = Telemetry {
+        seq: 0,
+        timestamp_ms: 0,
+        temperature: 0,
+        battery_mv: 0,
+        antenna_angle: 0,
+        boot_id: 0,
+        mode: None,
+        command_echo: None,
+    };
+    vectors.push((
+        "packed-limits-low".to_string(),
+        Telemetry {
+            temperature: PACKED_TEMP_MIN,
+            antenna_angle: PACKED_ANGLE_MIN,
+            ..boundary
+        },
+    ));
+    vectors.push((
+        "packed-limits-high".to_string(),
+        Telemetry {
+            seq: u32::MAX,
+            timestamp_ms: PACKED_TIMESTAMP_MAX,
+            temperature: PACKED_TEMP_MAX,
+            battery_mv: PACKED_BATTERY_MAX,
+            antenna_angle: PACKED_ANGLE_MAX,
+            ..boundary
+        },
+    ));
+    vectors.push((
+        "extremes-low".to_string(),
+        Telemetry { temperature: i16::MIN, antenna_angle: i16::MIN, ..boundary },
+    ));
+    vectors.push((
+        "extremes-high".to_string(),
+        Telemetry {
+            seq: u32::MAX,
+            timestamp_ms: u64::MAX,
+            temperature: i16::MAX,
+            battery_mv: u16::MAX,
+            antenna_angle: i16::MAX,
+            boot_id: u8::MAX,
+            mode: Some(2),
+            command_echo: Some(u32::MAX),
+        },
+    ));
+
+    if let Err(e) = std::fs::create_dir_all(&dir) {
+        eprintln!("[OCS] gen-vectors: cannot create {}: {e}", dir.display());
+        process::exit(1);
+    }
+    let mut manifest = String::from(
+        "file,format,length,seq,timestamp_ms,temperature,battery_mv,antenna_angle,boot_id,mode,command_echo\n",
+    );
+    for (name, t) in &vectors {
+        for format in VECTOR_FORMATS {
+            let data = encode_vector(t, format);
+            // A vector our own decoder rejects is a bug, not an artifact.
+            let Some(decoded) = decode_vector(&data, format) else {
+                eprintln!("[OCS] gen-vectors: {name}.{format} does not decode; refusing to write it");
+                process::exit(1);
+            };
+            let stem = format!("{name}.{format}");
+            let hex: String = data.iter().map(|b| format!("{b:02x}")).collect();
+            let write = std::fs::write(dir.join(format!("{stem}.bin")), &data)
+                .and_then(|()| std::fs::write(dir.join(format!("{stem}.hex")), format!("{hex}\n")));
+            if let Err(e) = write {
+                eprintln!("[OCS] gen-vectors: cannot write {stem}: {e}");
+                process::exit(1);
+            }
+            let mode = decoded.mode.map_or_else(|| "none".to_string(), |m| m.to_string());
+            let echo =
+                decoded.command_echo.map_or_else(|| "none".to_string(), |id| id.to_string());
+            manifest.push_str(&format!(
+                "{stem}.bin,{format},{},{},{},{},{},{},{},{mode},{echo}\n",
+                data.len(),
+                decoded.seq,
+                decoded.timestamp_ms,
+                decoded.temperature,
+                decoded.battery_mv,
+                decoded.antenna_angle,
+                decoded.boot_id,
+            ));
+        }
+    }
+    if let Err(e) = std::fs::write(dir.join("manifest.csv"), manifest) {
+        eprintln!("[OCS] gen-vectors: cannot write manifest: {e}");
+        process::exit(1);
+    }
+    println!(
+        "[OCS] wrote {} vectors x {} formats to {} (seed {seed})",
+        vectors.len(),
+        VECTOR_FORMATS.len(),
+        dir.display()
+    );
+    process::exit(0);
+}
+
+/// Applies one option by its key name (the CLI flag without the leading
+/// `--`). Shared by the flag parser and the `[ocs]` section of a `--config`
+/// file so both accept the same keys with the same value syntax.
+fn apply_option(args: &mut Args, key: &str, value: &str) -> Result<(), String> {
+    let bad = || format!("invalid value '{value}' for {key}");
+    match key {
+        // The first target replaces the default; later ones fan out.
+        "target" => {
+            if args.target_set {
+                args.extra_targets.push(value.to_string());
+            } else {
+                args.target = value.to_string();
+                args.target_set = true;
+            }
+        }
+        "interval" => args.interval_ms = value.parse().map_err(|_| bad())?,
+        "count" => args.count = value.parse().map_err(|_| bad())?,
+        "mode" => args.mode = Mode::parse(value).ok_or_else(bad)?,
+        "edge-ratio" => args.edge_ratio = value.parse().map_err(|_| bad())?,
+        "command-port" => args.command_port = value.parse().map_err(|_| bad())?,
+        "seed" => args.seed = value.parse().map_err(|_| bad())?,
+        "state-file" => args.state_file = Some(value.into()),
+        "send-log" => args.send_log = Some(value.into()),
+        "slew-rate" => args.slew_rate = value.parse().map_err(|_| bad())?,
+        "thermal-coupling" => args.thermal_coupling = value.parse().map_err(|_| bad())?,
+        "warmup" => args.warmup = value.parse().map_err(|_| bad())?,
+        "history" => args.history = value.parse().map_err(|_| bad())?,
+        "key" => args.key = Some(value.to_string()),
+        "temp-expr" => args.temp_expr = Some(value.to_string()),
+        "battery-expr" => args.battery_expr = Some(value.to_string()),
+        "transport" => match value {
+            "udp" => args.transport_tcp = false,
+            "tcp" => args.transport_tcp = true,
+            _ => return Err(bad()),
+        },
+        "reconnect-every" => args.reconnect_every_ms = value.parse().map_err(|_| bad())?,
+        "reconnect-max" => {
+            args.reconnect_max = match value {
+                "unlimited" => None,
+                n => Some(n.parse().map_err(|_| bad())?),
+            }
+        }
+        "campaign" => args.campaign = Some(value.to_string()),
+        "corrupt-field" => {
+            args.corrupt_field =
+                Some(wewinthis::mock_ocs::CorruptField::parse(value).ok_or_else(bad)?)
+        }
+        "corrupt-rate" => args.corrupt_rate = value.parse().map_err(|_| bad())?,
+        "flatline-field" => {
+            args.flatline_field =
+                Some(wewinthis::mock_ocs::CorruptField::parse(value).ok_or_else(bad)?)
+        }
+        "flatline-packets" => args.flatline_packets = value.parse().map_err(|_| bad())?,
+        "spike-field" => {
+            args.spike_field =
+                Some(wewinthis::mock_ocs::CorruptField::parse(value).ok_or_else(bad)?)
+        }
+        "spike-value" => args.spike_value = value.parse().map_err(|_| bad())?,
+        "spike-after" => args.spike_after = value.parse().map_err(|_| bad())?,
+        "duty-cycle" => {
+            let (on, off) = value.split_once(':').ok_or_else(bad)?;
+            let on = on.parse().map_err(|_| bad())?;
+            let off = off.parse().map_err(|_| bad())?;
+            args.duty_cycle = Some((on, off));
+        }
+        "chaos-level" => args.chaos_level = value.parse().map_err(|_| bad())?,
+        "downlink-impair" => args.downlink_impair = Some(parse_impair(value).ok_or_else(bad)?),
+        "uplink-impair" => args.uplink_impair = Some(parse_impair(value).ok_or_else(bad)?),
+        "angle-convention" => {
+            args.angle_convention =
+                wewinthis::angle::AngleConvention::parse(value).ok_or_else(bad)?
+        }
+        "dscp" => args.dscp = Some(value.to_string()),
+        "max-duration" => {
+            args.max_duration = Some(wewinthis::util::parse_duration(value).ok_or_else(bad)?)
+        }
+        "battery-floor" => args.battery_floor_mv = value.parse().map_err(|_| bad())?,
+        "battery-clear" => args.battery_clear_mv = Some(value.parse().map_err(|_| bad())?),
+        "recovery-budget" => args.recovery_budget_ms = value.parse().map_err(|_| bad())?,
+        // Bare flags on the CLI; `key = true` in a config file.
+        "reuse-addr" => args.reuse_addr = wewinthis::config::parse_bool(value).ok_or_else(bad)?,
+        "corrupt-before-crc" => {
+            args.corrupt_before_crc = wewinthis::config::parse_bool(value).ok_or_else(bad)?
+        }
+        "boot-id" => args.boot_id = wewinthis::config::parse_bool(value).ok_or_else(bad)?,
+        "mode-echo" => args.mode_echo = wewinthis::config::parse_bool(value).ok_or_else(bad)?,
+        "command-echo" => args.command_echo = wewinthis::config::parse_bool(value).ok_or_else(bad)?,
+        "tlv" => args.tlv = wewinthis::config::parse_bool(value).ok_or_else(bad)?,
+        "packed" => args.packed = wewinthis::config::parse_bool(value).ok_or_else(bad)?,
+        "batch" => args.batch = value.parse().map_err(|_| bad())?,
+        "random-start" => {
+            args.random_start = wewinthis::config::parse_bool(value).ok_or_else(bad)?
+        }
+        "timestamp-base" => args.timestamp_base_ms = Some(value.parse().map_err(|_| bad())?),
+        "met-epoch" => args.met_epoch_ms = Some(value.parse().map_err(|_| bad())?),
+        _ => return Err(format!("unknown option '{key}'")),
+    }
+    Ok(())
+}
+
+/// Parses a `DELAY_MS:JITTER_MS:LOSS` impairment triple.
+fn parse_impair(value: &str) -> Option<(u64, u64, f64)> {
+    let mut parts = value.split(':');
+    let delay = parts.next()?.parse().ok()?;
+    let jitter = parts.next()?.parse().ok()?;
+    let loss = parts.next()?.parse().ok()?;
+    parts.next().is_none().then_some((delay, jitter, loss))
+}
+
+fn parse_args() -> Args {
+    let argv: Vec<String> = std::env::args().skip(1).collect();
+    let mut args = Args::defaults();
+
+    // Apply any config file first so explicit CLI flags override its values.
+    let mut i = 0;
+    while i < argv.len() {
+        if argv[i] == "--config" {
+            let Some(path) = argv.get(i + 1) else {
+                eprintln!("missing value for --config");
+                usage()
+            };
+            let config = match wewinthis::config::Config::load(std::path::Path::new(path)) {
+                Ok(c) => c,
+                Err(e) => {
+                    eprintln!("[OCS] {e}");
+                    process::exit(e.exit_code());
+                }
+            };
+            for (key, value) in config.section("ocs") {
+                if let Err(e) = apply_option(&mut args, key, value) {
+                    eprintln!("[OCS] {path}: [ocs] {e}");
+                    process::exit(2);
+                }
+            }
+            i += 1;
+        }
+        i += 1;
+    }
+
+    let mut it = argv.iter();
+    while let Some(flag) = it.next() {
+        let mut value = |name: &str| {
+            it.next().cloned().unwrap_or_else(|| {
+                eprintln!("missing value for {name}");
+                usage()
+            })
+        };
+        match flag.as_str() {
+            "--config" => {
+                value("--config");
+            }
+            "--reuse-addr" => args.reuse_addr = true,
+            "--corrupt-before-crc" => args.corrupt_before_crc = true,
+            "--boot-id" => args.boot_id = true,
+            "--mode-echo" => args.mode_echo = true,
+            "--command-echo" => args.command_echo = true,
+            "--tlv" => args.tlv = true,
+            "--packed" => args.packed = true,
+            "--random-start" => args.random_start = true,
+            "--dry-run" => args.dry_run = true,
+            _ => {
+                let Some(key) = flag.strip_prefix("--") else { usage() };
+                let value = value(flag);
+                if let Err(e) = apply_option(&mut args, key, &value) {
+                    eprintln!("{e}");
+                    usage();
+                }
+            }
+        }
+    }
+    args
+}
+
+/// Validates the parsed configuration and prints the run plan without opening
+/// any socket or sending anything, then exits (non-zero on any problem).
+fn dry_run(args: &Args) -> ! {
+    use std::net::ToSocketAddrs;
+
+    let mut problems = Vec::new();
+
+    let resolved = match args.target.to_socket_addrs() {
+        Ok(mut addrs) => addrs.next(),
+        Err(e) => {
+            problems.push(format!("target '{}' does not resolve: {e}", args.target));
+            None
+        }
+    };
+    for spec in &args.extra_targets {
+        if let Err(e) = spec.to_socket_addrs() {
+            problems.push(format!("target '{spec}' does not resolve: {e}"));
+        }
+    }
+    if !args.extra_targets.is_empty() {
+        if args.transport_tcp {
+            problems.push("multiple targets need the udp transport".to_string());
+        }
+        if args.reconnect_every_ms > 0 {
+            problems.push(
+                "reconnect watches a single target; drop --reconnect-every or the extra targets"
+                    .to_string(),
+            );
+        }
+    }
+    if args.batch > 0 && args.tlv {
+        problems.push("batching packs fixed version-1 frames; drop --tlv".to_string());
+    }
+    if args.packed && args.tlv {
+        problems.push("--packed and --tlv select conflicting frame formats".to_string());
+    }
+    if args.packed && args.batch > 0 {
+        problems.push("batching packs fixed version-1 frames; drop --packed".to_string());
+    }
+    if args.interval_ms == 0 {
+        problems.push("interval must be at least 1 ms".to_string());
+    }
+    if !(0.0..=1.0).contains(&args.edge_ratio) {
+        problems.push(format!("edge ratio {} outside 0..=1", args.edge_ratio));
+    }
+    if !(0.0..=1.0).contains(&args.corrupt_rate) {
+        problems.push(format!("corrupt rate {} outside 0..=1", args.corrupt_rate));
+    }
+    if !(0.0..=1.0).contains(&args.chaos_level) {
+        problems.push(format!("chaos level {} outside 0..=1", args.chaos_level));
+    }
+    for (direction, impair) in [("downlink", args.downlink_impair), ("uplink", args.uplink_impair)] {
+        if let Some((_, _, loss)) = impair {
+            if !(0.0..=1.0).contains(&loss) {
+                problems.push(format!("{direction} impairment loss {loss} outside 0..=1"));
+            }
+        }
+    }
+    if args.flatline_field.is_some() && args.flatline_packets == 0 {
+        problems.push("flatline duration must be at least 1 packet".to_string());
+    }
+    if args.command_echo && !args.tlv {
+        problems.push("command echo rides the TLV format; add --tlv".to_string());
+    }
+    if args.spike_field.is_none() && (args.spike_value != 0 || args.spike_after != 0) {
+        problems.push("spike value or delay given without --spike-field".to_string());
+    }
+    if let Some((on, off)) = args.duty_cycle {
+        if on == 0 || off == 0 {
+            problems.push(format!("duty cycle {on}:{off} windows must both be positive"));
+        }
+    }
+    if let Some(spec) = &args.dscp {
+        if wewinthis::util::parse_dscp(spec).is_none() {
+            problems.push(format!(
+                "unknown DSCP '{spec}' (use EF, CS0-CS7, AF11-AF43, DF, or 0-63)"
+            ));
+        }
+    }
+    if args.max_duration == Some(std::time::Duration::ZERO) {
+        problems.push("max duration must be non-zero".to_string());
+    }
+    if args.thermal_coupling < 0.0 {
+        problems.push(format!(
+            "thermal coupling {} must not be negative",
+            args.thermal_coupling
+        ));
+    }
+    for (flag, text) in [("--temp-expr", &args.temp_expr), ("--battery-expr", &args.battery_expr)]
+    {
+        if let Some(text) = text {
+            if let Err(e) = wewinthis::expr::Expr::parse(text) {
+                problems.push(format!("{flag} '{text}' invalid: {e}"));
+            }
+        }
+    }
+    if let Some(name) = &args.campaign {
+        if wewinthis::campaign::find(name).is_none() {
+            problems.push(format!("unknown campaign '{name}'"));
+        }
+    }
+    if let Some(path) = &args.state_file {
+        let dir = path.parent().filter(|p| !p.as_os_str().is_empty());
+        if let Some(dir) = dir {
+            if !dir.is_dir() {
+                problems.push(format!(
+                    "state file directory {} does not exist",
+                    dir.display()
+                ));
+            }
+        }
+    }
+    if let (Some(clear), floor) = (args.battery_clear_mv, args.battery_floor_mv) {
+        if floor > 0 && clear < floor {
+            problems.push(format!(
+                "battery clear {clear} mV below floor {floor} mV (no hysteresis)"
+            ));
+        }
+    }
+
+    println!("[OCS] dry run: plan");
+    println!(
+        "  target        {} ({})",
+        args.target,
+        resolved.map_or_else(|| "unresolved".to_string(), |a| a.to_string())
+    );
+    for spec in &args.extra_targets {
+        println!("  also to       {spec}");
+    }
+    println!("  transport     {}", if args.transport_tcp { "tcp" } else { "udp" });
+    if args.reconnect_every_ms > 0 {
+        println!(
+            "  reconnect     every {} ms, {}",
+            args.reconnect_every_ms,
+            args.reconnect_max
+                .map_or_else(|| "unlimited attempts".to_string(), |n| format!("up to {n} attempts"))
+        );
+    }
+    println!("  interval      {} ms", args.interval_ms);
+    println!(
+        "  count         {}",
+        if args.count == 0 { "unbounded".to_string() } else { args.count.to_string() }
+    );
+    println!("  mode          {} (edge ratio {})", args.mode.name(), args.edge_ratio);
+    println!("  command port  {}", args.command_port);
+    println!("  seed          {}  warmup {} packets", args.seed, args.warmup);
+    if let Some(field) = args.corrupt_field {
+        println!(
+            "  corruption    {} ({}-CRC) on {:.0}% of packets",
+            field.name(),
+            if args.corrupt_before_crc { "pre" } else { "post" },
+            args.corrupt_rate * 100.0
+        );
+    }
+    if let Some(field) = args.flatline_field {
+        println!(
+            "  flatline      {} stuck for {} packets",
+            field.name(),
+            args.flatline_packets
+        );
+    }
+    if let Some(field) = args.spike_field {
+        println!(
+            "  spike         {} = {} for one packet, after {} packets",
+            field.name(),
+            args.spike_value,
+            args.spike_after
+        );
+    }
+    if let Some(path) = &args.send_log {
+        println!("  send log      {}", path.display());
+    }
+    if let Some((on, off)) = args.duty_cycle {
+        println!("  duty cycle    {on} ms on / {off} ms off");
+    }
+    if args.chaos_level > 0.0 {
+        println!("  chaos         level {} (seed {})", args.chaos_level, args.seed);
+    }
+    if let Some((delay, jitter, loss)) = args.downlink_impair {
+        println!("  downlink      {delay} ms +{jitter} ms jitter, {:.1}% loss", loss * 100.0);
+    }
+    if let Some((delay, jitter, loss)) = args.uplink_impair {
+        println!("  uplink        {delay} ms +{jitter} ms jitter, {:.1}% loss", loss * 100.0);
+    }
+    if let Some(spec) = &args.dscp {
+        if let Some(dscp) = wewinthis::util::parse_dscp(spec) {
+            println!("  dscp marking  {spec} (code point {dscp})");
+        }
+    }
+    if let Some(d) = args.max_duration {
+        println!("  max duration  {} ms", d.as_millis());
+    }
+    if args.battery_floor_mv > 0 {
+        println!(
+            "  battery floor {} mV (clear {} mV)",
+            args.battery_floor_mv,
+            args.battery_clear_mv
+                .unwrap_or(args.battery_floor_mv.saturating_add(500))
+        );
+    }
+    if let Some(name) = &args.campaign {
+        println!("  campaign      {name} (recovery budget {} ms)", args.recovery_budget_ms);
+    }
+    if let Some(path) = &args.state_file {
+        println!("  state file    {}", path.display());
+    }
+    if args.boot_id {
+        println!("  boot counter  enabled (version-2 frames)");
+    }
+    if args.mode_echo {
+        println!("  mode echo     enabled (version-3 frames)");
+    }
+    if args.tlv {
+        println!("  tlv framing   enabled (version-4 self-describing frames)");
+    }
+    if args.command_echo {
+        println!("  command echo  enabled (last accepted command id in telemetry)");
+    }
+    if args.packed {
+        println!("  packed frames enabled (version-6 bit-packed frames)");
+    }
+    if args.batch > 0 {
+        println!(
+            "  batching      {} samples per version-5 container (delta+RLE)",
+            args.batch
+        );
+    }
+    if args.random_start {
+        println!("  random start  seq and timestamp base from seed {}", args.seed);
+    }
+    if let Some(base) = args.timestamp_base_ms {
+        println!("  time base     {base} ms");
+    }
+    if let Some(epoch) = args.met_epoch_ms {
+        println!("  MET epoch     {epoch} ms");
+    }
+
+    if problems.is_empty() {
+        println!("[OCS] dry run: configuration OK");
+        process::exit(0);
+    }
+    for problem in &problems {
+        eprintln!("[OCS] dry run: {problem}");
+    }
+    process::exit(1);
+}
+
+fn main() {
+    let mut raw = std::env::args().skip(1).peekable();
+    if raw.peek().map(String::as_str) == Some("gen-vectors") {
+        raw.next();
+        gen_vectors(raw);
+    }
+    let args = parse_args();
+    if args.dry_run {
+        dry_run(&args);
+    }
+    let shutdown = install_shutdown_flag();
+
+    let shared = Arc::new(OcsShared::with_history_capacity(
+        args.interval_ms,
+        args.mode,
+        args.history,
+    ));
+    let mut receiver = match CommandReceiver::bind_with(args.command_port, Arc::clone(&shared), args.reuse_addr) {
+        Ok(r) => r,
+        Err(e) => {
+            eprintln!("[OCS] {e}");
+            process::exit(1);
+        }
+    };
+    if let Some((delay, jitter, loss)) = args.uplink_impair {
+        // A distinct stream from the downlink's, so the two directions roll
+        // their losses independently even under the same --seed.
+        receiver.set_impairment(LinkImpairment::new(delay, jitter, loss, args.seed.wrapping_add(1)));
+    }
+    receiver.spawn();
+
+    let mut ocs = match MockOCS::new(&args.target, Arc::clone(&shared), args.seed) {
+        Ok(o) => o,
+        Err(e) => {
+            eprintln!("[OCS] startup failed: {e}");
+            process::exit(e.exit_code());
+        }
+    };
+    if args.transport_tcp {
+        if let Err(e) = ocs.use_tcp_transport() {
+            eprintln!("[OCS] tcp transport setup failed: {e}");
+            process::exit(1);
+        }
+    }
+    if args.reconnect_every_ms > 0 {
+        // After the transport choice, so the policy aims the right downlink.
+        if let Err(e) = ocs.enable_reconnect(args.reconnect_every_ms, args.reconnect_max) {
+            eprintln!("[OCS] reconnect setup failed: {e}");
+            process::exit(1);
+        }
+        println!(
+            "[OCS] downlink reconnect armed: every {} ms, {}",
+            args.reconnect_every_ms,
+            args.reconnect_max
+                .map_or_else(|| "unlimited attempts".to_string(), |n| format!("up to {n} attempts"))
+        );
+    }
+    if !args.extra_targets.is_empty() {
+        if args.transport_tcp {
+            eprintln!("[OCS] multiple targets need the udp transport");
+            process::exit(2);
+        }
+        if args.reconnect_every_ms > 0 {
+            eprintln!("[OCS] reconnect watches a single target; drop --reconnect-every or the extra targets");
+            process::exit(2);
+        }
+        for spec in &args.extra_targets {
+            if let Err(e) = ocs.add_target(spec) {
+                eprintln!("[OCS] startup failed: {e}");
+                process::exit(e.exit_code());
+            }
+        }
+        println!(
+            "[OCS] fanning out to {} targets",
+            args.extra_targets.len() + 1
+        );
+    }
+    ocs.set_edge_ratio(args.edge_ratio);
+    if let Some(field) = args.corrupt_field {
+        ocs.set_corruption(field, args.corrupt_before_crc, args.corrupt_rate);
+        println!(
+            "[OCS] corrupting {} ({}-CRC) on {:.0}% of packets",
+            field.name(),
+            if args.corrupt_before_crc { "pre" } else { "post" },
+            args.corrupt_rate * 100.0
+        );
+    }
+    if let Some(field) = args.flatline_field {
+        ocs.set_flatline(field, args.flatline_packets);
+        println!(
+            "[OCS] flatline: {} stuck for {} packets",
+            field.name(),
+            args.flatline_packets
+        );
+    }
+    if let Some(field) = args.spike_field {
+        ocs.set_spike(field, args.spike_value, args.spike_after);
+        println!(
+            "[OCS] spike scheduled: {} = {} for one packet, after {} packets",
+            field.name(),
+            args.spike_value,
+            args.spike_after
+        );
+    }
+    if let Some(path) = &args.send_log {
+        match ocs.set_send_log(path, wewinthis::logfile::DEFAULT_LOG_QUEUE_CAPACITY) {
+            Ok(()) => println!("[OCS] logging sent packets to {}", path.display()),
+            Err(e) => {
+                eprintln!("[OCS] cannot open send log {}: {e}", path.display());
+                process::exit(1);
+            }
+        }
+    }
+    if let Some((on_ms, off_ms)) = args.duty_cycle {
+        ocs.set_duty_cycle(on_ms, off_ms);
+        println!("[OCS] duty-cycled downlink: {on_ms} ms on, {off_ms} ms off");
+    }
+    ocs.set_angle_convention(args.angle_convention);
+    if let Some((delay, jitter, loss)) = args.downlink_impair {
+        ocs.set_downlink_impairment(LinkImpairment::new(delay, jitter, loss, args.seed));
+    }
+    if args.chaos_level > 0.0 {
+        ocs.set_chaos(args.chaos_level, args.seed);
+        println!(
+            "[OCS] chaos level {} (seed {}): loss/reorder/dup/corrupt/jitter/edge",
+            args.chaos_level.clamp(0.0, 1.0),
+            args.seed
+        );
+    }
+    if let Some(spec) = &args.dscp {
+        let Some(dscp) = wewinthis::util::parse_dscp(spec) else {
+            eprintln!("[OCS] unknown DSCP '{spec}' (use EF, CS0-CS7, AF11-AF43, DF, or 0-63)");
+            process::exit(2);
+        };
+        match ocs.set_dscp(dscp) {
+            Ok(()) => println!("[OCS] downlink marked DSCP {spec} (code point {dscp})"),
+            Err(e) => eprintln!("[OCS] DSCP marking failed ({e}); sending unmarked"),
+        }
+    }
+    if let Some(d) = args.max_duration {
+        ocs.set_max_duration(d);
+        println!("[OCS] run bounded to {} ms of wall-clock time", d.as_millis());
+    }
+    ocs.set_slew_rate(args.slew_rate);
+    ocs.set_thermal_antenna_coupling(args.thermal_coupling);
+    ocs.set_warmup(args.warmup);
+    if args.battery_floor_mv > 0 {
+        let floor = args.battery_floor_mv;
+        let clear = args.battery_clear_mv.unwrap_or(floor.saturating_add(500));
+        ocs.set_battery_floor(floor, clear);
+        println!("[OCS] onboard fault protection: safe below {floor} mV, clear above {clear} mV");
+    }
+    if let Some(key) = &args.key {
+        ocs.set_key(key.clone().into_bytes());
+        println!("[OCS] telemetry authentication enabled");
+    }
+    if let Some(text) = &args.temp_expr {
+        match wewinthis::expr::Expr::parse(text) {
+            Ok(expr) => ocs.set_temp_expr(expr),
+            Err(e) => {
+                eprintln!("[OCS] invalid --temp-expr: {e}");
+                process::exit(2);
+            }
+        }
+    }
+    if let Some(text) = &args.battery_expr {
+        match wewinthis::expr::Expr::parse(text) {
+            Ok(expr) => ocs.set_battery_expr(expr),
+            Err(e) => {
+                eprintln!("[OCS] invalid --battery-expr: {e}");
+                process::exit(2);
+            }
+        }
+    }
+
+    // Randomization first: persisted state below overwrites the sequence,
+    // so a resumed run wins over the random offsets.
+    if args.random_start {
+        let (seq, base) = ocs.randomize_start(args.seed);
+        println!("[OCS] randomized start: seq {seq}, timestamp base {base} ms");
+    }
+    if let Some(base) = args.timestamp_base_ms {
+        ocs.set_timestamp_base(base);
+        println!("[OCS] timestamp base {base} ms");
+    }
+    if let Some(epoch) = args.met_epoch_ms {
+        ocs.set_met_epoch(epoch);
+        println!("[OCS] mission elapsed time epoch {epoch} ms");
+    }
+
+    if let Some(path) = &args.state_file {
+        match PersistedState::load(path) {
+            Ok(Some(persisted)) => {
+                ocs.restore_state(&persisted);
+                println!(
+                    "[OCS] resumed persisted state from {} (seq {}, battery {} mV, mode {})",
+                    path.display(),
+                    persisted.seq,
+                    persisted.battery_mv,
+                    persisted.mode.name()
+                );
+            }
+            Ok(None) => println!("[OCS] no state file at {}; starting fresh", path.display()),
+            Err(e) => println!(
+                "[OCS] state file {} unusable ({e}); starting fresh",
+                path.display()
+            ),
+        }
+    }
+
+    if args.boot_id {
+        // After state restore, so a resumed run sends the incremented counter.
+        ocs.enable_boot_tracking();
+        println!("[OCS] boot counter enabled (boot {})", ocs.boot_id());
+    }
+    if args.mode_echo {
+        ocs.enable_mode_echo();
+        println!("[OCS] mode echo enabled (version-3 frames)");
+    }
+    if args.tlv {
+        ocs.enable_tlv();
+        println!("[OCS] TLV framing enabled (version-4 self-describing frames)");
+    }
+    if args.command_echo {
+        if !args.tlv {
+            eprintln!("Error: command echo rides the TLV format; add --tlv");
+            process::exit(2);
+        }
+        ocs.enable_command_echo();
+        println!("[OCS] command echo enabled (in-band acks in telemetry)");
+    }
+    if args.packed {
+        if args.tlv {
+            eprintln!("Error: --packed and --tlv select conflicting frame formats");
+            process::exit(2);
+        }
+        ocs.enable_packed();
+        println!("[OCS] bit-packed framing enabled (version-6, 17-byte frames)");
+    }
+    if args.batch > 0 {
+        if args.tlv || args.packed {
+            eprintln!("Error: batching packs fixed version-1 frames; drop --tlv/--packed");
+            process::exit(2);
+        }
+        ocs.enable_batching(args.batch);
+        println!(
+            "[OCS] batching {} samples per version-5 container (delta+RLE)",
+            args.batch
+        );
+    }
+
+    if let Some(name) = &args.campaign {
+        let Some(campaign) = wewinthis::campaign::find(name) else {
+            eprintln!(
+                "[OCS] unknown campaign '{name}'; available: {}",
+                wewinthis::campaign::CAMPAIGNS
+                    .iter()
+                    .map(|c| c.name)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+            process::exit(2);
+        };
+        let campaign_shared = Arc::clone(&shared);
+        let budget = args.recovery_budget_ms;
+        std::thread::spawn(move || {
+            wewinthis::campaign::run(campaign, &campaign_shared, budget);
+        });
+    }
+
+    println!(
+        "[OCS] downlink to {} every {} ms (mode {}, command port {})",
+        args.target,
+        args.interval_ms,
+        args.mode.name(),
+        args.command_port
+    );
+    ocs.run(args.count, shutdown);
+
+    if let Some(path) = &args.state_file {
+        match ocs.capture_state().save(path) {
+            Ok(()) => println!("[OCS] state persisted to {}", path.display()),
+            Err(e) => eprintln!("[OCS] failed to persist state to {}: {e}", path.display()),
+        }
+    }
+}
@@ -0,0 +1,61 @@
+//! Scenario runner binary: interprets a scenario script against a live OCS.
+
+use std::process;
+
+use wewinthis::scenario::{parse_script, ScenarioRunner};
+
+fn usage() -> ! {
+    eprintln!("usage: scenario --script FILE [--ocs-command HOST:PORT] [--listen PORT]");
+    process::exit(2);
+}
+
+fn main() {
+    let mut script_path: Option<String> = None;
+    let mut ocs_command = "127.0.0.1:9000".to_string();
+    let mut listen_port: u16 = 8080;
+
+    let mut it = std::env::args().skip(1);
+    while let Some(flag) = it.next() {
+        let mut value = |name: &str| it.next().unwrap_or_else(|| {
+            eprintln!("missing value for {name}");
+            usage()
+        });
+        match flag.as_str() {
+            "--script" => script_path = Some(value("--script")),
+            "--ocs-command" => ocs_command = value("--ocs-command"),
+            "--listen" => listen_port = value("--listen").parse().unwrap_or_else(|_| usage()),
+            _ => usage(),
+        }
+    }
+    let Some(script_path) = script_path else { usage() };
+
+    let text = match std::fs::read_to_string(&script_path) {
+        Ok(t) => t,
+        Err(e) => {
+            eprintln!("[SCENARIO] cannot read {script_path}: {e}");
+            process::exit(1);
+        }
+    };
+    let steps = match parse_script(&text) {
+        Ok(s) => s,
+        Err((lineno, msg)) => {
+            eprintln!("[SCENARIO] {script_path}:{lineno}: {msg}");
+            process::exit(1);
+        }
+    };
+
+    let mut runner = match ScenarioRunner::new(&ocs_command, listen_port) {
+        Ok(r) => r,
+        Err(e) => {
+            eprintln!("[SCENARIO] startup failed: {e}");
+            process::exit(1);
+        }
+    };
+    match runner.run(&steps) {
+        Ok(()) => println!("[SCENARIO] PASS ({} steps)", steps.len()),
+        Err((lineno, msg)) => {
+            eprintln!("[SCENARIO] FAIL at {script_path}:{lineno}: {msg}");
+            process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,228 @@
+//! Named fault campaigns: predefined injection sequences with scoring.
+//!
+//! A campaign packages a repeatable stress scenario — e.g. repeated hot/cold
+//! cycling — as a table of `(offset, fault case, duration)` steps applied to
+//! the running OCS through the same shared state `INJECT_FAULT` uses. When
+//! the sequence completes, the run is scored: faults injected, average
+//! modeled recovery time (from the end of each injection until generation is
+//! back inside limits), and whether the watchdog had to force-clear a stuck
+//! injection. The score is judged pass/fail against a configurable recovery
+//! budget.
+
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+
+use crate::gcs::{classify_faults, Limits};
+use crate::mock_ocs::command::OcsShared;
+use crate::reservoir::{Reservoir, DEFAULT_RESERVOIR_CAPACITY};
+
+/// One campaign step: at `offset_ms` from campaign start, inject edge case
+/// `case` for `duration_ms`.
+pub struct CampaignStep {
+    pub offset_ms: u64,
+    pub case: u8,
+    pub duration_ms: u64,
+}
+
+/// A named, code-defined fault sequence.
+pub struct Campaign {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub steps: &'static [CampaignStep],
+}
+
+/// If recovery takes longer than this multiple of the budget, the watchdog
+/// force-clears the injection and the fire is counted against the score.
+const WATCHDOG_BUDGET_MULTIPLE: u64 = 10;
+
+/// The built-in campaign table. Cases match
+/// [`crate::mock_ocs::generator::TelemetryGenerator::generate_edge_case`].
+pub const CAMPAIGNS: &[Campaign] = &[
+    Campaign {
+        name: "thermal-stress",
+        description: "repeated hot/cold cycling",
+        steps: &[
+            CampaignStep { offset_ms: 0, case: 0, duration_ms: 2_000 },
+            CampaignStep { offset_ms: 4_000, case: 1, duration_ms: 2_000 },
+            CampaignStep { offset_ms: 8_000, case: 0, duration_ms: 2_000 },
+            CampaignStep { offset_ms: 12_000, case: 1, duration_ms: 2_000 },
+        ],
+    },
+    Campaign {
+        name: "power-sag",
+        description: "battery collapse and overvolt excursions",
+        steps: &[
+            CampaignStep { offset_ms: 0, case: 2, duration_ms: 3_000 },
+            CampaignStep { offset_ms: 6_000, case: 3, duration_ms: 3_000 },
+            CampaignStep { offset_ms: 12_000, case: 2, duration_ms: 3_000 },
+        ],
+    },
+    Campaign {
+        name: "antenna-wobble",
+        description: "alternating gross misalignment",
+        steps: &[
+            CampaignStep { offset_ms: 0, case: 4, duration_ms: 1_500 },
+            CampaignStep { offset_ms: 3_000, case: 5, duration_ms: 1_500 },
+            CampaignStep { offset_ms: 6_000, case: 4, duration_ms: 1_500 },
+        ],
+    },
+];
+
+/// Looks up a campaign by name.
+pub fn find(name: &str) -> Option<&'static Campaign> {
+    CAMPAIGNS.iter().find(|c| c.name == name)
+}
+
+/// Outcome of a campaign run.
+pub struct CampaignScore {
+    pub name: &'static str,
+    pub faults_injected: u64,
+    /// Uniform sample of recovery times; bounded for long campaigns.
+    pub recovery_times_ms: Reservoir<f64>,
+    pub watchdog_fires: u64,
+    pub budget_ms: u64,
+}
+
+impl CampaignScore {
+    /// Mean recovery time, or 0 with no samples.
+    pub fn avg_recovery_ms(&self) -> f64 {
+        if self.recovery_times_ms.is_empty() {
+            return 0.0;
+        }
+        self.recovery_times_ms.iter().sum::<f64>() / self.recovery_times_ms.len() as f64
+    }
+
+    /// Passes when recovery stayed within budget and no watchdog fired.
+    pub fn passed(&self) -> bool {
+        self.watchdog_fires == 0 && self.avg_recovery_ms() <= self.budget_ms as f64
+    }
+
+    /// Prints the end-of-campaign scorecard.
+    pub fn report(&self) {
+        println!("===== Campaign Report: {} =====", self.name);
+        println!("Faults injected:    {}", self.faults_injected);
+        println!(
+            "Avg recovery:       {:.0} ms (budget {} ms)",
+            self.avg_recovery_ms(),
+            self.budget_ms
+        );
+        println!("Watchdog fires:     {}", self.watchdog_fires);
+        println!(
+            "Result:             {}",
+            if self.passed() { "PASS" } else { "FAIL" }
+        );
+        println!("==================================");
+    }
+}
+
+/// Runs a campaign against a live OCS, blocking until the sequence completes
+/// and returning the score. Intended to run on its own thread alongside the
+/// send loop.
+pub fn run(campaign: &Campaign, shared: &OcsShared, budget_ms: u64) -> CampaignScore {
+    let start = Instant::now();
+    let limits = Limits::default();
+    let mut score = CampaignScore {
+        name: campaign.name,
+        faults_injected: 0,
+        recovery_times_ms: Reservoir::new(DEFAULT_RESERVOIR_CAPACITY, 4),
+        watchdog_fires: 0,
+        budget_ms,
+    };
+    println!(
+        "[CAMPAIGN] {} ({}): {} steps",
+        campaign.name,
+        campaign.description,
+        campaign.steps.len()
+    );
+
+    for step in campaign.steps {
+        let at = start + Duration::from_millis(step.offset_ms);
+        let now = Instant::now();
+        if at > now {
+            std::thread::sleep(at - now);
+        }
+
+        let interval = shared.interval_ms.load(Ordering::SeqCst).max(1);
+        let packets = (step.duration_ms / interval).max(1);
+        shared.inject_case.store(step.case + 1, Ordering::SeqCst);
+        shared.inject_packets.store(packets, Ordering::SeqCst);
+        score.faults_injected += 1;
+        println!(
+            "[CAMPAIGN] step at +{} ms: case {} for {} packets",
+            step.offset_ms, step.case, packets
+        );
+
+        // Wait out the injection, then time how long generation takes to get
+        // back inside limits. The watchdog force-clears a stuck injection.
+        std::thread::sleep(Duration::from_millis(step.duration_ms));
+        let fault_end = Instant::now();
+        let deadline = fault_end + Duration::from_millis(budget_ms * WATCHDOG_BUDGET_MULTIPLE);
+        loop {
+            let recovered = shared
+                .recent_history(1)
+                .first()
+                .is_some_and(|t| classify_faults(t, &limits).is_empty());
+            if recovered {
+                score
+                    .recovery_times_ms
+                    .push(fault_end.elapsed().as_secs_f64() * 1000.0);
+                break;
+            }
+            if Instant::now() > deadline {
+                shared.inject_case.store(0, Ordering::SeqCst);
+                shared.inject_packets.store(0, Ordering::SeqCst);
+                score.watchdog_fires += 1;
+                println!("[CAMPAIGN] watchdog cleared stuck injection (case {})", step.case);
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(interval.min(50)));
+        }
+    }
+
+    score.report();
+    score
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn campaigns_are_found_by_name() {
+        assert!(find("thermal-stress").is_some());
+        assert!(find("power-sag").is_some());
+        assert!(find("no-such-campaign").is_none());
+    }
+
+    #[test]
+    fn step_offsets_are_monotonic() {
+        for campaign in CAMPAIGNS {
+            let mut last = 0;
+            for step in campaign.steps {
+                assert!(step.offset_ms >= last, "{}: offsets out of order", campaign.name);
+                last = step.offset_ms;
+            }
+        }
+    }
+
+    #[test]
+    fn score_judges_budget_and_watchdog() {
+        let mut score = CampaignScore {
+            name: "test",
+            faults_injected: 2,
+            recovery_times_ms: Reservoir::new(DEFAULT_RESERVOIR_CAPACITY, 1),
+            watchdog_fires: 0,
+            budget_ms: 500,
+        };
+        score.recovery_times_ms.push(100.0);
+        score.recovery_times_ms.push(300.0);
+        assert_eq!(score.avg_recovery_ms(), 200.0);
+        assert!(score.passed());
+        score.watchdog_fires = 1;
+        assert!(!score.passed());
+        score.watchdog_fires = 0;
+        score.recovery_times_ms = Reservoir::new(DEFAULT_RESERVOIR_CAPACITY, 1);
+        score.recovery_times_ms.push(900.0);
+        assert!(!score.passed());
+    }
+}
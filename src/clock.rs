@@ -0,0 +1,108 @@
+//! Clock abstraction used by both ends of the link.
+//!
+//! Telemetry timestamps are milliseconds since mission start rather than wall
+//! clock. Indirecting through a trait lets tests drive time manually instead
+//! of sleeping.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Source of mission-relative time in milliseconds.
+pub trait Clock: Send + Sync {
+    /// Milliseconds elapsed since the clock's epoch.
+    fn now_ms(&self) -> u64;
+}
+
+/// Real clock anchored at construction time.
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> Self {
+        SystemClock {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now_ms(&self) -> u64 {
+        self.start.elapsed().as_millis() as u64
+    }
+}
+
+/// Mission Elapsed Time: an inner clock shifted by a fixed epoch offset, so
+/// timestamps line up with an external mission timeline instead of process
+/// start. At one count per millisecond a `u64` lasts about 584 million
+/// years, so rollover is academic; the addition still wraps rather than
+/// panics for pathological epochs.
+pub struct MissionClock {
+    inner: Arc<dyn Clock>,
+    epoch_ms: u64,
+}
+
+impl MissionClock {
+    pub fn new(inner: Arc<dyn Clock>, epoch_ms: u64) -> Self {
+        MissionClock { inner, epoch_ms }
+    }
+}
+
+impl Clock for MissionClock {
+    fn now_ms(&self) -> u64 {
+        self.epoch_ms.wrapping_add(self.inner.now_ms())
+    }
+}
+
+/// Manually advanced clock for deterministic tests.
+pub struct ManualClock {
+    now_ms: AtomicU64,
+}
+
+impl ManualClock {
+    pub fn new(start_ms: u64) -> Self {
+        ManualClock {
+            now_ms: AtomicU64::new(start_ms),
+        }
+    }
+
+    /// Advances the clock by `ms` milliseconds.
+    pub fn advance(&self, ms: u64) {
+        self.now_ms.fetch_add(ms, Ordering::SeqCst);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now_ms(&self) -> u64 {
+        self.now_ms.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manual_clock_advances() {
+        let clock = ManualClock::new(100);
+        assert_eq!(clock.now_ms(), 100);
+        clock.advance(250);
+        assert_eq!(clock.now_ms(), 350);
+    }
+
+    #[test]
+    fn mission_clock_offsets_by_its_epoch() {
+        let inner = Arc::new(ManualClock::new(0));
+        let met = MissionClock::new(Arc::clone(&inner) as Arc<dyn Clock>, 1_000_000);
+        assert_eq!(met.now_ms(), 1_000_000);
+        inner.advance(42);
+        assert_eq!(met.now_ms(), 1_000_042);
+    }
+}
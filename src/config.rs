@@ -0,0 +1,156 @@
+//! Minimal INI/TOML-subset configuration loader.
+//!
+//! Supports exactly what the binaries need to move their growing option sets
+//! out of CLI flags: `[section]` headers, `key = value` pairs, blank lines
+//! and full-line `#`/`;` comments. Values are kept as strings (surrounding
+//! quotes stripped) and parsed by the binaries through the same code paths as
+//! the corresponding CLI flags, which always override the file. Parse errors
+//! carry the offending line number.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+/// A parsed configuration file: `section -> ordered key/value pairs`.
+#[derive(Debug)]
+pub struct Config {
+    sections: HashMap<String, Vec<(String, String)>>,
+}
+
+impl Config {
+    /// Parses configuration text; errors name the offending line.
+    pub fn parse(text: &str) -> Result<Config, String> {
+        let mut sections: HashMap<String, Vec<(String, String)>> = HashMap::new();
+        let mut current: Option<String> = None;
+        for (index, raw) in text.lines().enumerate() {
+            let line_no = index + 1;
+            let line = raw.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+            if let Some(rest) = line.strip_prefix('[') {
+                let Some(name) = rest.strip_suffix(']') else {
+                    return Err(format!("line {line_no}: unterminated section header"));
+                };
+                let name = name.trim();
+                if name.is_empty() {
+                    return Err(format!("line {line_no}: empty section name"));
+                }
+                sections.entry(name.to_string()).or_default();
+                current = Some(name.to_string());
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(format!("line {line_no}: expected 'key = value' or '[section]'"));
+            };
+            let Some(section) = &current else {
+                return Err(format!("line {line_no}: '{}' outside any [section]", key.trim()));
+            };
+            let key = key.trim();
+            if key.is_empty() {
+                return Err(format!("line {line_no}: empty key"));
+            }
+            let mut value = value.trim();
+            // Strip one matching pair of surrounding quotes, TOML-style.
+            for quote in ['"', '\''] {
+                if value.len() >= 2 && value.starts_with(quote) && value.ends_with(quote) {
+                    value = &value[1..value.len() - 1];
+                    break;
+                }
+            }
+            sections
+                .get_mut(section)
+                .expect("current section exists")
+                .push((key.to_string(), value.to_string()));
+        }
+        Ok(Config { sections })
+    }
+
+    /// Loads and parses a configuration file; errors are prefixed with the
+    /// path so the binary can print them as-is.
+    pub fn load(path: &Path) -> crate::Result<Config> {
+        let text = std::fs::read_to_string(path).map_err(|e| {
+            crate::Error::Config(format!("{}: {e}", path.display()))
+        })?;
+        Self::parse(&text).map_err(|e| crate::Error::Config(format!("{}: {e}", path.display())))
+    }
+
+    /// The key/value pairs of one section in file order (empty if absent).
+    pub fn section(&self, name: &str) -> impl Iterator<Item = (&str, &str)> {
+        self.sections
+            .get(name)
+            .into_iter()
+            .flatten()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Last value set for `section.key`, if any.
+    pub fn get(&self, section: &str, key: &str) -> Option<&str> {
+        self.sections
+            .get(section)?
+            .iter()
+            .rev()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Parses a configuration boolean: `true`/`false`, `yes`/`no`, `1`/`0`.
+pub fn parse_bool(value: &str) -> Option<bool> {
+    match value {
+        "true" | "yes" | "1" => Some(true),
+        "false" | "no" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sections_keys_comments_and_quotes_parse() {
+        let config = Config::parse(
+            "# run profile\n\
+             [ocs]\n\
+             interval = 250\n\
+             target = \"127.0.0.1:8080\"\n\
+             ; semicolon comments too\n\
+             \n\
+             [gcs]\n\
+             port = 8080\n\
+             interval = 999\n",
+        )
+        .unwrap();
+        assert_eq!(config.get("ocs", "interval"), Some("250"));
+        assert_eq!(config.get("ocs", "target"), Some("127.0.0.1:8080"));
+        assert_eq!(config.get("gcs", "port"), Some("8080"));
+        assert_eq!(config.get("gcs", "interval"), Some("999"));
+        assert_eq!(config.get("ocs", "port"), None);
+        assert_eq!(config.section("missing").count(), 0);
+    }
+
+    #[test]
+    fn duplicate_keys_keep_the_last_value_in_order() {
+        let config = Config::parse("[ocs]\nseed = 1\nseed = 2\n").unwrap();
+        assert_eq!(config.get("ocs", "seed"), Some("2"));
+        let pairs: Vec<_> = config.section("ocs").collect();
+        assert_eq!(pairs, vec![("seed", "1"), ("seed", "2")]);
+    }
+
+    #[test]
+    fn parse_errors_carry_line_numbers() {
+        let err = Config::parse("[ocs]\nnot a pair\n").unwrap_err();
+        assert!(err.contains("line 2"), "{err}");
+        let err = Config::parse("interval = 250\n").unwrap_err();
+        assert!(err.contains("line 1") && err.contains("outside any"), "{err}");
+        let err = Config::parse("\n[ocs\n").unwrap_err();
+        assert!(err.contains("line 2") && err.contains("unterminated"), "{err}");
+    }
+
+    #[test]
+    fn booleans_accept_the_usual_spellings() {
+        assert_eq!(parse_bool("true"), Some(true));
+        assert_eq!(parse_bool("no"), Some(false));
+        assert_eq!(parse_bool("maybe"), None);
+    }
+}
@@ -0,0 +1,105 @@
+//! Crate-level error type.
+//!
+//! Fallible constructors used to return `io::Result` with every cause folded
+//! into `ErrorKind::InvalidInput` strings; embedding code could only parse
+//! messages. [`Error`] keeps the cause structured so callers can match on it,
+//! while the binaries map each variant to a friendly message and exit code.
+
+use std::fmt;
+use std::io;
+
+use crate::telemetry::DecodeError;
+
+/// What went wrong, structured by cause.
+#[derive(Debug)]
+pub enum Error {
+    /// A socket could not be bound (port in use, permissions, ...).
+    Bind(io::Error),
+    /// A host:port string did not resolve to a usable address.
+    Resolve(String),
+    /// A telemetry frame failed to decode.
+    Decode(DecodeError),
+    /// A configuration value was missing, inconsistent or out of range.
+    Config(String),
+    /// Any other I/O failure.
+    Io(io::Error),
+}
+
+/// Crate-wide result alias.
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Bind(e) => write!(f, "bind failed: {e}"),
+            Error::Resolve(addr) => write!(f, "address '{addr}' does not resolve"),
+            Error::Decode(DecodeError::Empty) => write!(f, "decode failed: empty frame"),
+            Error::Decode(DecodeError::UnknownVersion(v)) => {
+                write!(f, "decode failed: unknown version {v}")
+            }
+            Error::Decode(DecodeError::Malformed) => {
+                write!(f, "decode failed: malformed frame (length or checksum)")
+            }
+            Error::Config(detail) => write!(f, "configuration error: {detail}"),
+            Error::Io(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Bind(e) | Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<DecodeError> for Error {
+    fn from(e: DecodeError) -> Self {
+        Error::Decode(e)
+    }
+}
+
+impl Error {
+    /// Process exit code for this failure: configuration mistakes exit like a
+    /// usage error (2), everything else as a startup failure (1).
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::Config(_) => 2,
+            _ => 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn variants_stay_matchable_through_from_impls() {
+        let e: Error = io::Error::new(io::ErrorKind::AddrInUse, "busy").into();
+        assert!(matches!(e, Error::Io(_)));
+        let e: Error = DecodeError::UnknownVersion(9).into();
+        assert!(matches!(e, Error::Decode(DecodeError::UnknownVersion(9))));
+    }
+
+    #[test]
+    fn display_names_the_cause() {
+        assert!(Error::Resolve("nowhere:1".into()).to_string().contains("nowhere:1"));
+        assert!(Error::Config("bad interval".into()).to_string().contains("bad interval"));
+        assert!(Error::Decode(DecodeError::Malformed).to_string().contains("malformed"));
+    }
+
+    #[test]
+    fn exit_codes_distinguish_config_from_startup_failures() {
+        assert_eq!(Error::Config("x".into()).exit_code(), 2);
+        assert_eq!(Error::Resolve("x".into()).exit_code(), 1);
+    }
+}
@@ -0,0 +1,270 @@
+//! Tiny arithmetic expression evaluator for custom telemetry waveforms.
+//!
+//! Supports `+ - * /`, unary minus, parentheses, the functions `sin` and
+//! `cos`, numeric literals, and the variables `t` (elapsed seconds) and `i`
+//! (packet index). Expressions are parsed once at startup — an invalid
+//! expression is a configuration error, not a runtime one — and evaluated
+//! per packet against the current `t` and `i`.
+//!
+//! Grammar (recursive descent, usual precedence):
+//!
+//! ```text
+//! expr   := term (('+' | '-') term)*
+//! term   := factor (('*' | '/') factor)*
+//! factor := NUMBER | 't' | 'i' | ('sin' | 'cos') '(' expr ')'
+//!         | '(' expr ')' | '-' factor
+//! ```
+
+/// A parsed expression, ready for repeated evaluation.
+#[derive(Debug, Clone)]
+pub enum Expr {
+    Num(f64),
+    VarT,
+    VarI,
+    Neg(Box<Expr>),
+    Add(Box<Expr>, Box<Expr>),
+    Sub(Box<Expr>, Box<Expr>),
+    Mul(Box<Expr>, Box<Expr>),
+    Div(Box<Expr>, Box<Expr>),
+    Sin(Box<Expr>),
+    Cos(Box<Expr>),
+}
+
+impl Expr {
+    /// Parses `input`, rejecting anything outside the supported grammar with
+    /// a message naming the offending position.
+    pub fn parse(input: &str) -> Result<Expr, String> {
+        let tokens = tokenize(input)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let expr = parser.expr()?;
+        if parser.pos != parser.tokens.len() {
+            return Err(format!(
+                "unexpected trailing input at token {}",
+                parser.pos + 1
+            ));
+        }
+        Ok(expr)
+    }
+
+    /// Evaluates with `t` in seconds and `i` the packet index.
+    pub fn eval(&self, t: f64, i: f64) -> f64 {
+        match self {
+            Expr::Num(n) => *n,
+            Expr::VarT => t,
+            Expr::VarI => i,
+            Expr::Neg(e) => -e.eval(t, i),
+            Expr::Add(a, b) => a.eval(t, i) + b.eval(t, i),
+            Expr::Sub(a, b) => a.eval(t, i) - b.eval(t, i),
+            Expr::Mul(a, b) => a.eval(t, i) * b.eval(t, i),
+            Expr::Div(a, b) => a.eval(t, i) / b.eval(t, i),
+            Expr::Sin(e) => e.eval(t, i).sin(),
+            Expr::Cos(e) => e.eval(t, i).cos(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Num(f64),
+    Ident(String),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Open,
+    Close,
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let chars: Vec<char> = input.chars().collect();
+    let mut pos = 0;
+    while pos < chars.len() {
+        let c = chars[pos];
+        match c {
+            ' ' | '\t' => pos += 1,
+            '+' => {
+                tokens.push(Token::Plus);
+                pos += 1;
+            }
+            '-' => {
+                tokens.push(Token::Minus);
+                pos += 1;
+            }
+            '*' => {
+                tokens.push(Token::Star);
+                pos += 1;
+            }
+            '/' => {
+                tokens.push(Token::Slash);
+                pos += 1;
+            }
+            '(' => {
+                tokens.push(Token::Open);
+                pos += 1;
+            }
+            ')' => {
+                tokens.push(Token::Close);
+                pos += 1;
+            }
+            '0'..='9' | '.' => {
+                let start = pos;
+                while pos < chars.len() && (chars[pos].is_ascii_digit() || chars[pos] == '.') {
+                    pos += 1;
+                }
+                let text: String = chars[start..pos].iter().collect();
+                let n = text
+                    .parse()
+                    .map_err(|_| format!("bad number '{text}' at char {}", start + 1))?;
+                tokens.push(Token::Num(n));
+            }
+            'a'..='z' | 'A'..='Z' => {
+                let start = pos;
+                while pos < chars.len() && chars[pos].is_ascii_alphabetic() {
+                    pos += 1;
+                }
+                tokens.push(Token::Ident(chars[start..pos].iter().collect()));
+            }
+            _ => return Err(format!("unexpected character '{c}' at char {}", pos + 1)),
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let t = self.tokens.get(self.pos).cloned();
+        if t.is_some() {
+            self.pos += 1;
+        }
+        t
+    }
+
+    fn expect_close(&mut self) -> Result<(), String> {
+        match self.next() {
+            Some(Token::Close) => Ok(()),
+            _ => Err("expected ')'".to_string()),
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr, String> {
+        let mut left = self.term()?;
+        while let Some(op) = self.peek() {
+            let op = match op {
+                Token::Plus => Token::Plus,
+                Token::Minus => Token::Minus,
+                _ => break,
+            };
+            self.pos += 1;
+            let right = self.term()?;
+            left = match op {
+                Token::Plus => Expr::Add(Box::new(left), Box::new(right)),
+                _ => Expr::Sub(Box::new(left), Box::new(right)),
+            };
+        }
+        Ok(left)
+    }
+
+    fn term(&mut self) -> Result<Expr, String> {
+        let mut left = self.factor()?;
+        while let Some(op) = self.peek() {
+            let op = match op {
+                Token::Star => Token::Star,
+                Token::Slash => Token::Slash,
+                _ => break,
+            };
+            self.pos += 1;
+            let right = self.factor()?;
+            left = match op {
+                Token::Star => Expr::Mul(Box::new(left), Box::new(right)),
+                _ => Expr::Div(Box::new(left), Box::new(right)),
+            };
+        }
+        Ok(left)
+    }
+
+    fn factor(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::Num(n)) => Ok(Expr::Num(n)),
+            Some(Token::Minus) => Ok(Expr::Neg(Box::new(self.factor()?))),
+            Some(Token::Open) => {
+                let inner = self.expr()?;
+                self.expect_close()?;
+                Ok(inner)
+            }
+            Some(Token::Ident(name)) => match name.as_str() {
+                "t" => Ok(Expr::VarT),
+                "i" => Ok(Expr::VarI),
+                "sin" | "cos" => {
+                    match self.next() {
+                        Some(Token::Open) => {}
+                        _ => return Err(format!("expected '(' after {name}")),
+                    }
+                    let inner = Box::new(self.expr()?);
+                    self.expect_close()?;
+                    Ok(if name == "sin" {
+                        Expr::Sin(inner)
+                    } else {
+                        Expr::Cos(inner)
+                    })
+                }
+                _ => Err(format!(
+                    "unknown identifier '{name}' (expected t, i, sin or cos)"
+                )),
+            },
+            Some(other) => Err(format!("unexpected token {other:?}")),
+            None => Err("unexpected end of expression".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evaluates_with_usual_precedence() {
+        let e = Expr::parse("2 + 3 * 4").unwrap();
+        assert_eq!(e.eval(0.0, 0.0), 14.0);
+        let e = Expr::parse("(2 + 3) * 4").unwrap();
+        assert_eq!(e.eval(0.0, 0.0), 20.0);
+        let e = Expr::parse("10 - 4 / 2").unwrap();
+        assert_eq!(e.eval(0.0, 0.0), 8.0);
+    }
+
+    #[test]
+    fn variables_and_functions() {
+        let e = Expr::parse("20 + 30*sin(t/10)").unwrap();
+        assert!((e.eval(0.0, 0.0) - 20.0).abs() < 1e-9);
+        let expected = 20.0 + 30.0 * (5.0_f64 / 10.0).sin();
+        assert!((e.eval(5.0, 0.0) - expected).abs() < 1e-9);
+        let e = Expr::parse("cos(0) + i").unwrap();
+        assert_eq!(e.eval(0.0, 3.0), 4.0);
+    }
+
+    #[test]
+    fn unary_minus() {
+        let e = Expr::parse("-t + 1").unwrap();
+        assert_eq!(e.eval(2.0, 0.0), -1.0);
+    }
+
+    #[test]
+    fn invalid_expressions_are_rejected() {
+        assert!(Expr::parse("").is_err());
+        assert!(Expr::parse("2 +").is_err());
+        assert!(Expr::parse("sin 3").is_err());
+        assert!(Expr::parse("(1 + 2").is_err());
+        assert!(Expr::parse("foo(3)").is_err());
+        assert!(Expr::parse("1 ^ 2").is_err());
+        assert!(Expr::parse("1 2").is_err());
+    }
+}
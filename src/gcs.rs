@@ -0,0 +1,5410 @@
+//! Ground control station (GCS).
+//!
+//! Receives telemetry datagrams, decodes and validates them against the
+//! mission limits, tracks link health (loss, duplicates, reordering, jitter)
+//! and enforces the real-time constraints the system advertises: decode
+//! latency under [`DECODE_LATENCY_THRESHOLD_US`] and fault response under
+//! [`FAULT_RESPONSE_THRESHOLD_MS`].
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io;
+use std::net::{ToSocketAddrs, UdpSocket};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
+
+use crate::reservoir::{Reservoir, DEFAULT_RESERVOIR_CAPACITY};
+use crate::status_stream::StatusStream;
+use crate::telemetry::{DecodeError, DecoderRegistry, Telemetry, TELEMETRY_WIRE_SIZE};
+use crate::uplink::CommandSender;
+
+/// Maximum acceptable decode latency for one packet (3 ms).
+pub const DECODE_LATENCY_THRESHOLD_US: u128 = 3_000;
+/// Maximum acceptable time from fault detection to completed response.
+pub const FAULT_RESPONSE_THRESHOLD_MS: u64 = 100;
+/// How long a commanded mode change may go unreflected in mode-echoing
+/// telemetry before `[GCS-CMD-UNCONFIRMED]` is raised.
+pub const MODE_CONFIRM_TIMEOUT_MS: u64 = 5_000;
+/// Equal-width buckets used when exporting histograms as CSV.
+pub const HISTOGRAM_EXPORT_BUCKETS: usize = 20;
+/// Silence on the downlink longer than this declares loss of contact.
+pub const LOSS_OF_CONTACT_TIMEOUT_MS: u64 = 5_000;
+/// Width of the sliding window used for the received-rate gauge.
+const RATE_WINDOW: Duration = Duration::from_secs(5);
+/// Default fraction of the expected rate below which a rate anomaly is
+/// flagged, and how long the shortfall must persist first.
+pub const DEFAULT_RATE_ANOMALY_FRACTION: f64 = 0.5;
+const RATE_ANOMALY_SUSTAIN: Duration = Duration::from_secs(2);
+/// Default period of the full in-flight performance report.
+pub const DEFAULT_REPORT_EVERY_SECS: u64 = 30;
+/// Default period of the command-link round-trip probe.
+pub const DEFAULT_PING_INTERVAL_MS: u64 = 5_000;
+/// Default jitter tolerance band; excursions beyond it raise `[GCS-JITTER]`.
+pub const DEFAULT_JITTER_TOLERANCE_MS: u64 = 100;
+/// Consecutive edge-case packets beyond this raise `[GCS-SUSTAINED-EDGE]`.
+pub const DEFAULT_EDGE_STREAK_LIMIT: u64 = 5;
+/// Consecutive identical readings of one field beyond this raise
+/// `[GCS-STUCK]`: a healthy sensor has noise, so a long flat run suggests it
+/// stopped updating.
+pub const DEFAULT_STUCK_PACKET_LIMIT: u64 = 100;
+/// Valid packets excluded from decode-latency metrics at startup, unless
+/// overridden; mirrors the OCS send-side warm-up.
+pub const DEFAULT_WARMUP_PACKETS: u64 = 10;
+/// Receive buffer size, deliberately larger than any current frame so the
+/// true length of an oversized datagram is visible rather than truncated.
+const MAX_DATAGRAM: usize = 512;
+
+/// Frames queued toward the forwarding worker before new ones are shed.
+const FORWARD_QUEUE_CAPACITY: usize = 256;
+
+/// Consecutive over-budget packets before the degradation ladder sheds one
+/// more level of optional work.
+const DEGRADE_AFTER_OVERRUNS: u32 = 8;
+/// Consecutive packets under half the budget before one level is restored;
+/// much deeper than the trip streak, so a load hovering at the budget does
+/// not oscillate the ladder.
+const DEGRADE_RESTORE_AFTER: u32 = 64;
+/// Ladder levels, in shedding order. Level 1 drops the anomaly analysis
+/// (alerts, rate-of-change, stuck, streak and jitter tracking); level 2
+/// additionally drops per-packet logging and printing, leaving decode,
+/// integrity and critical-fault detection.
+const DEGRADE_LEVEL_NAMES: [&str; 3] = ["full service", "reduced analysis", "essential only"];
+
+/// Spacing between drift-rate samples of the estimated clock offset; a pair
+/// this far apart averages out per-packet arrival noise.
+const CLOCK_DRIFT_WINDOW: Duration = Duration::from_secs(5);
+
+/// Sliding window over which the receive-loop busy fraction is judged; long
+/// enough that one expensive packet cannot trip the alarm on its own.
+const BUSY_WINDOW: Duration = Duration::from_secs(5);
+
+/// Default bound on how fast the estimated clock offset may move, in ms of
+/// offset per second of local time, before `[GCS-CLOCK-SKEW]` fires.
+pub const DEFAULT_MAX_CLOCK_DRIFT_MS_PER_S: f64 = 5.0;
+
+/// Backward sequence jump at least this large is treated as a sender reset
+/// (reboot restarting at 0) rather than reordering or loss.
+const RESET_BACKWARD_JUMP: i32 = 1_000;
+
+/// Exit-code bits encoding what went wrong during a soak run; see
+/// [`GCSPerformanceMetrics::exit_code`]. Values start at 8 so the low codes
+/// stay reserved for usage (2) and startup (1) errors.
+pub const EXIT_LATENCY_VIOLATION: i32 = 8;
+pub const EXIT_LOSS_OF_CONTACT: i32 = 16;
+pub const EXIT_FAULT_RESPONSE_OVERRUN: i32 = 32;
+pub const EXIT_GOLDEN_REGRESSION: i32 = 64;
+
+/// Default allowed worsening of each compared metric before the golden-run
+/// comparison reports a regression.
+pub const DEFAULT_GOLDEN_TOLERANCE_PCT: f64 = 20.0;
+
+/// Mission limits a telemetry sample is validated against.
+#[derive(Debug, Clone, Copy)]
+pub struct Limits {
+    pub temp_high: i16,
+    pub temp_low: i16,
+    pub battery_low_mv: u16,
+    pub battery_high_mv: u16,
+    pub antenna_misalign_deg: i16,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            temp_high: 100,
+            temp_low: -50,
+            battery_low_mv: 10_000,
+            battery_high_mv: 15_000,
+            antenna_misalign_deg: 45,
+        }
+    }
+}
+
+impl Limits {
+    /// Adjusts one named threshold at runtime (`SET_THRESHOLD` on the
+    /// control port). Only the field names listed below are adjustable, and
+    /// cross-field ordering (high above low) is enforced so a live tweak
+    /// cannot leave the limits self-contradictory.
+    pub fn set_threshold(&mut self, name: &str, value: &str) -> Result<(), String> {
+        let bad = || format!("invalid value '{value}' for {name}");
+        match name {
+            "temp_high" => {
+                let v: i16 = value.parse().map_err(|_| bad())?;
+                if v <= self.temp_low {
+                    return Err(format!(
+                        "temp_high {v} must be above temp_low {}",
+                        self.temp_low
+                    ));
+                }
+                self.temp_high = v;
+            }
+            "temp_low" => {
+                let v: i16 = value.parse().map_err(|_| bad())?;
+                if v >= self.temp_high {
+                    return Err(format!(
+                        "temp_low {v} must be below temp_high {}",
+                        self.temp_high
+                    ));
+                }
+                self.temp_low = v;
+            }
+            "battery_low_mv" => {
+                let v: u16 = value.parse().map_err(|_| bad())?;
+                if v >= self.battery_high_mv {
+                    return Err(format!(
+                        "battery_low_mv {v} must be below battery_high_mv {}",
+                        self.battery_high_mv
+                    ));
+                }
+                self.battery_low_mv = v;
+            }
+            "battery_high_mv" => {
+                let v: u16 = value.parse().map_err(|_| bad())?;
+                if v <= self.battery_low_mv {
+                    return Err(format!(
+                        "battery_high_mv {v} must be above battery_low_mv {}",
+                        self.battery_low_mv
+                    ));
+                }
+                self.battery_high_mv = v;
+            }
+            "antenna_misalign_deg" => {
+                let v: i16 = value.parse().map_err(|_| bad())?;
+                if !(1..=180).contains(&v) {
+                    return Err(format!("antenna_misalign_deg {v} outside 1..=180"));
+                }
+                self.antenna_misalign_deg = v;
+            }
+            _ => {
+                return Err(format!(
+                    "unknown threshold '{name}' (adjustable: temp_high, temp_low, \
+                     battery_low_mv, battery_high_mv, antenna_misalign_deg)"
+                ))
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Relative importance of each field in the composite health score.
+#[derive(Debug, Clone, Copy)]
+pub struct HealthWeights {
+    pub temperature: f64,
+    pub battery: f64,
+    pub antenna: f64,
+}
+
+impl Default for HealthWeights {
+    fn default() -> Self {
+        HealthWeights {
+            temperature: 1.0,
+            battery: 1.0,
+            antenna: 1.0,
+        }
+    }
+}
+
+/// Composite 0-100 health score: the weighted mean of each field's normalized
+/// distance from the center of its allowed band. A field sitting at the center
+/// contributes 0; exactly at a limit contributes 100; beyond a limit is capped
+/// at 100 so one runaway field cannot mask the others in the average.
+pub fn health_score(t: &Telemetry, limits: &Limits, weights: &HealthWeights) -> f64 {
+    fn field(value: f64, center: f64, half_band: f64) -> f64 {
+        if half_band <= 0.0 {
+            return 0.0;
+        }
+        (100.0 * (value - center).abs() / half_band).min(100.0)
+    }
+    let temp_center = (limits.temp_high as f64 + limits.temp_low as f64) / 2.0;
+    let temp_half = (limits.temp_high as f64 - limits.temp_low as f64) / 2.0;
+    let batt_center = (limits.battery_high_mv as f64 + limits.battery_low_mv as f64) / 2.0;
+    let batt_half = (limits.battery_high_mv as f64 - limits.battery_low_mv as f64) / 2.0;
+    let scores = [
+        (weights.temperature, field(t.temperature as f64, temp_center, temp_half)),
+        (weights.battery, field(t.battery_mv as f64, batt_center, batt_half)),
+        (
+            weights.antenna,
+            field(
+                crate::angle::angular_distance(t.antenna_angle as f64, 0.0),
+                0.0,
+                limits.antenna_misalign_deg as f64,
+            ),
+        ),
+    ];
+    let total_weight: f64 = scores.iter().map(|(w, _)| w.max(0.0)).sum();
+    if total_weight <= 0.0 {
+        return 0.0;
+    }
+    scores.iter().map(|(w, s)| w.max(0.0) * s).sum::<f64>() / total_weight
+}
+
+/// Fault classes the GCS can detect in a telemetry sample.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Fault {
+    HighTemperature,
+    LowTemperature,
+    LowBattery,
+    HighBattery,
+    AntennaMisalignment,
+    LossOfContact,
+}
+
+impl Fault {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Fault::HighTemperature => "HighTemperature",
+            Fault::LowTemperature => "LowTemperature",
+            Fault::LowBattery => "LowBattery",
+            Fault::HighBattery => "HighBattery",
+            Fault::AntennaMisalignment => "AntennaMisalignment",
+            Fault::LossOfContact => "LossOfContact",
+        }
+    }
+}
+
+/// Appends `name,bucket_lower,bucket_upper,count` rows for one sample set,
+/// bucketed into [`HISTOGRAM_EXPORT_BUCKETS`] equal-width bins spanning the
+/// observed range. All bins are written, zeros included, so a plotted curve
+/// shows the gaps in the distribution.
+fn append_histogram(out: &mut String, name: &str, samples: &[f64]) {
+    if samples.is_empty() {
+        return;
+    }
+    let min = samples.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = samples.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let width = ((max - min) / HISTOGRAM_EXPORT_BUCKETS as f64).max(1.0);
+    let mut counts = [0u64; HISTOGRAM_EXPORT_BUCKETS];
+    for &sample in samples {
+        let bucket = (((sample - min) / width) as usize).min(HISTOGRAM_EXPORT_BUCKETS - 1);
+        counts[bucket] += 1;
+    }
+    for (bucket, &count) in counts.iter().enumerate() {
+        let lower = min + bucket as f64 * width;
+        out.push_str(&format!("{name},{lower:.0},{:.0},{count}\n", lower + width));
+    }
+}
+
+/// Human-readable label for a wire mode byte; out-of-encoding values are
+/// shown as-is instead of being mistaken for a known mode.
+fn mode_label(byte: u8) -> String {
+    match crate::mock_ocs::command::Mode::try_from_u8(byte) {
+        Some(mode) => mode.name().to_string(),
+        None => format!("unknown({byte})"),
+    }
+}
+
+/// Renders mission-elapsed milliseconds as `days+hh:mm:ss`, the usual MET
+/// notation on mission timelines.
+fn format_met(ms: u64) -> String {
+    let secs = ms / 1000;
+    format!(
+        "{}+{:02}:{:02}:{:02}",
+        secs / 86_400,
+        secs / 3_600 % 24,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
+
+/// Current UTC time of day as `hh:mm:ss`, for correlating MET with wall
+/// clock without pulling in a timezone database.
+fn wall_clock_hms() -> String {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+        % 86_400;
+    format!("{:02}:{:02}:{:02}", secs / 3_600, secs / 60 % 60, secs % 60)
+}
+
+/// Classifies a sample against the limits, returning every fault present.
+pub fn classify_faults(t: &Telemetry, limits: &Limits) -> Vec<Fault> {
+    let mut faults = Vec::new();
+    if t.temperature > limits.temp_high {
+        faults.push(Fault::HighTemperature);
+    }
+    if t.temperature < limits.temp_low {
+        faults.push(Fault::LowTemperature);
+    }
+    if t.battery_mv < limits.battery_low_mv {
+        faults.push(Fault::LowBattery);
+    }
+    if t.battery_mv > limits.battery_high_mv {
+        faults.push(Fault::HighBattery);
+    }
+    // Misalignment is the shortest angular distance from boresight, so a
+    // wrapped reading like 350 means 10 degrees off, not a gross excursion.
+    if crate::angle::angular_distance(t.antenna_angle as f64, 0.0)
+        > limits.antenna_misalign_deg as f64
+    {
+        faults.push(Fault::AntennaMisalignment);
+    }
+    faults
+}
+
+/// Two-stage alert thresholds for one monitored field direction: crossing
+/// `warn` raises `[GCS-WARN]`, crossing `alarm` raises `[GCS-ALARM]`, and
+/// either tier retires only once the value retreats past `clear` (hysteresis).
+/// For fields alerting on a falling value the ordering is mirrored.
+#[derive(Debug, Clone, Copy)]
+pub struct FieldThreshold {
+    pub warn: f64,
+    pub alarm: f64,
+    pub clear: f64,
+}
+
+impl FieldThreshold {
+    /// True when the levels are ordered consistently with the field's
+    /// direction: `clear <= warn <= alarm` rising, mirrored falling.
+    pub fn ordered_for(&self, field: AlertField) -> bool {
+        if field.rising() {
+            self.clear <= self.warn && self.warn <= self.alarm
+        } else {
+            self.clear >= self.warn && self.warn >= self.alarm
+        }
+    }
+}
+
+/// Monitored field directions covered by the two-stage alert scheme. Each
+/// direction is its own monitor so, say, a hot alert can carry different
+/// hysteresis than a cold one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AlertField {
+    TemperatureHigh,
+    TemperatureLow,
+    BatteryLow,
+    BatteryHigh,
+    AntennaMisalignment,
+}
+
+impl AlertField {
+    pub const ALL: [AlertField; 5] = [
+        AlertField::TemperatureHigh,
+        AlertField::TemperatureLow,
+        AlertField::BatteryLow,
+        AlertField::BatteryHigh,
+        AlertField::AntennaMisalignment,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            AlertField::TemperatureHigh => "temperature-high",
+            AlertField::TemperatureLow => "temperature-low",
+            AlertField::BatteryLow => "battery-low",
+            AlertField::BatteryHigh => "battery-high",
+            AlertField::AntennaMisalignment => "antenna-misalign",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<AlertField> {
+        Self::ALL.iter().copied().find(|f| f.name() == name)
+    }
+
+    /// True when the field alerts on a rising value, false on a falling one.
+    fn rising(&self) -> bool {
+        !matches!(self, AlertField::TemperatureLow | AlertField::BatteryLow)
+    }
+
+    /// The monitored value this field watches in one sample.
+    fn sample(&self, t: &Telemetry) -> f64 {
+        match self {
+            AlertField::TemperatureHigh | AlertField::TemperatureLow => t.temperature as f64,
+            AlertField::BatteryLow | AlertField::BatteryHigh => t.battery_mv as f64,
+            AlertField::AntennaMisalignment => {
+                crate::angle::angular_distance(t.antenna_angle as f64, 0.0)
+            }
+        }
+    }
+
+    /// Default thresholds collapsing both tiers (and the clear level) onto
+    /// today's single limit, so an unconfigured field alerts exactly where
+    /// the fault model already does.
+    pub fn default_threshold(&self, limits: &Limits) -> FieldThreshold {
+        let limit = match self {
+            AlertField::TemperatureHigh => limits.temp_high as f64,
+            AlertField::TemperatureLow => limits.temp_low as f64,
+            AlertField::BatteryLow => limits.battery_low_mv as f64,
+            AlertField::BatteryHigh => limits.battery_high_mv as f64,
+            AlertField::AntennaMisalignment => limits.antenna_misalign_deg as f64,
+        };
+        FieldThreshold {
+            warn: limit,
+            alarm: limit,
+            clear: limit,
+        }
+    }
+}
+
+/// Alert tier a monitored field currently sits in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AlertTier {
+    Normal,
+    Warn,
+    Alarm,
+}
+
+/// What one sample did to a field's alert state, for logging and counting.
+enum AlertOutcome {
+    Warned,
+    Alarmed,
+    Cleared,
+}
+
+/// Flat-run tracker for one field, behind the stuck-sensor check: how long
+/// the field has reported exactly the same value, and whether that run has
+/// already been flagged.
+struct StuckTrack {
+    field: &'static str,
+    last: Option<i64>,
+    run: u64,
+    alarmed: bool,
+}
+
+impl StuckTrack {
+    fn new(field: &'static str) -> Self {
+        StuckTrack {
+            field,
+            last: None,
+            run: 0,
+            alarmed: false,
+        }
+    }
+}
+
+/// Per-field two-stage alert state machine; see [`FieldThreshold`].
+struct FieldAlert {
+    field: AlertField,
+    threshold: FieldThreshold,
+    tier: AlertTier,
+}
+
+impl FieldAlert {
+    /// Advances the state machine with one sample, returning the transition
+    /// (if any). Escalation straight to alarm counts only an alarm episode;
+    /// either tier clears only once the value retreats past `clear`.
+    fn observe(&mut self, value: f64) -> Option<AlertOutcome> {
+        let rising = self.field.rising();
+        let breach = |level: f64| if rising { value > level } else { value < level };
+        match self.tier {
+            AlertTier::Normal | AlertTier::Warn if breach(self.threshold.alarm) => {
+                self.tier = AlertTier::Alarm;
+                Some(AlertOutcome::Alarmed)
+            }
+            AlertTier::Normal if breach(self.threshold.warn) => {
+                self.tier = AlertTier::Warn;
+                Some(AlertOutcome::Warned)
+            }
+            AlertTier::Warn | AlertTier::Alarm if !breach(self.threshold.clear) => {
+                self.tier = AlertTier::Normal;
+                Some(AlertOutcome::Cleared)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Per-field maximum plausible change between consecutive samples
+/// (temperature in degrees, battery in mV, antenna in degrees — all per
+/// packet). Changes beyond a limit are physically implausible even when both
+/// values sit inside the absolute limits, pointing at a sensor glitch or
+/// corruption that passed the CRC.
+#[derive(Debug, Clone, Copy)]
+pub struct RateOfChangeLimits {
+    pub temperature: f64,
+    pub battery: f64,
+    pub antenna: f64,
+}
+
+/// How the receive-side rate cap chooses which datagrams to shed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShedPolicy {
+    /// Process the first `cap` datagrams of each second, shed the rest.
+    TailDrop,
+    /// Spread the budget across the whole second by keeping every k-th
+    /// datagram, with k estimated from the previous second's arrival count.
+    Sample,
+}
+
+/// Live state and history of one alarm type, as kept by [`AlarmRegistry`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AlarmState {
+    /// Whether the condition holds right now.
+    pub active: bool,
+    /// Every individual raise, however long the condition then held.
+    pub occurrences: u64,
+    /// Distinct inactive-to-active transitions.
+    pub episodes: u64,
+    /// When the alarm last fired or re-fired.
+    pub last_active: Option<Instant>,
+}
+
+/// Central ledger of every alarm the GCS raises, keyed by alarm name.
+///
+/// The individual watchdogs keep their own booleans and counters for their
+/// own logic; this registry mirrors their transitions so the final report
+/// can show which alarms are live right now next to how often each has
+/// fired, instead of an operator piecing that together from scattered log
+/// lines.
+pub struct AlarmRegistry {
+    alarms: HashMap<&'static str, AlarmState>,
+}
+
+impl AlarmRegistry {
+    pub fn new() -> Self {
+        AlarmRegistry {
+            alarms: HashMap::new(),
+        }
+    }
+
+    /// Marks `name` active, counting the occurrence and — on the
+    /// inactive-to-active transition — an episode.
+    pub fn raise(&mut self, name: &'static str) {
+        let state = self.alarms.entry(name).or_default();
+        state.occurrences += 1;
+        if !state.active {
+            state.active = true;
+            state.episodes += 1;
+        }
+        state.last_active = Some(Instant::now());
+    }
+
+    /// Marks `name` inactive; its history stays on the books. Clearing an
+    /// alarm that never fired records nothing.
+    pub fn clear(&mut self, name: &'static str) {
+        if let Some(state) = self.alarms.get_mut(name) {
+            state.active = false;
+        }
+    }
+
+    /// Counts a momentary alarm that has no duration to stay active for:
+    /// one occurrence, one episode, never left active.
+    pub fn pulse(&mut self, name: &'static str) {
+        let state = self.alarms.entry(name).or_default();
+        state.occurrences += 1;
+        state.episodes += 1;
+        state.active = false;
+        state.last_active = Some(Instant::now());
+    }
+
+    /// State of one alarm, if it has ever fired.
+    pub fn get(&self, name: &str) -> Option<&AlarmState> {
+        self.alarms.get(name)
+    }
+
+    /// Number of alarms active right now.
+    pub fn active_count(&self) -> usize {
+        self.alarms.values().filter(|s| s.active).count()
+    }
+
+    /// Names of the alarms active right now, sorted for stable output.
+    pub fn active_names(&self) -> Vec<&'static str> {
+        let mut names: Vec<_> = self
+            .alarms
+            .iter()
+            .filter(|(_, state)| state.active)
+            .map(|(&name, _)| name)
+            .collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Prints the alarm table, with last-active times relative to `start`.
+    /// Alarms that never fired have no row; no alarms, no table.
+    pub fn report(&self, start: Instant) {
+        if self.alarms.is_empty() {
+            return;
+        }
+        let mut entries: Vec<_> = self.alarms.iter().collect();
+        entries.sort_by_key(|(name, _)| *name);
+        println!("Alarm summary:");
+        println!("  {:<18} {:<7} {:>8} {:>12} {:>10}", "alarm", "state", "episodes", "occurrences", "last");
+        for (name, state) in entries {
+            let last = state
+                .last_active
+                .map_or_else(String::new, |at| {
+                    format!("{:.1}s", at.saturating_duration_since(start).as_secs_f64())
+                });
+            println!(
+                "  {:<18} {:<7} {:>8} {:>12} {:>10}",
+                name,
+                if state.active { "ACTIVE" } else { "clear" },
+                state.episodes,
+                state.occurrences,
+                last
+            );
+        }
+    }
+}
+
+impl Default for AlarmRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Receive-side performance and link-health counters.
+pub struct GCSPerformanceMetrics {
+    packets_received: u64,
+    valid_packets: u64,
+    invalid_packets: u64,
+    packets_lost: u64,
+    unknown_version_packets: u64,
+    spoofed_packets: u64,
+    duplicate_packets: u64,
+    out_of_order_packets: u64,
+    edge_cases_detected: u64,
+    sustained_edge_episodes: u64,
+    max_edge_streak: u64,
+    decode_latencies_us: Vec<u128>,
+    latency_violations: u64,
+    /// Uniform sample of per-packet jitter; bounded for long runs.
+    jitter_us: Reservoir<i64>,
+    jitter_violations: u64,
+    faults_detected: HashMap<Fault, u64>,
+    /// Inactive-to-active transitions per fault type: distinct episodes, not
+    /// per-packet occurrences.
+    fault_episodes: HashMap<Fault, u64>,
+    /// Uniform sample of fault-response times; bounded for long runs.
+    fault_response_times_ms: Reservoir<f64>,
+    fault_response_by_type: HashMap<Fault, Vec<f64>>,
+    fault_response_violations: u64,
+    auto_commands: u64,
+    /// Lowest and highest sequence seen (wrap-aware), for expected-count math.
+    seq_span: Option<(u32, u32)>,
+    rate_anomaly_events: u64,
+    rate_anomaly_total_ms: f64,
+    /// Loss/expected marks at the previous report, for the window loss rate.
+    window_lost_mark: u64,
+    window_expected_mark: u64,
+    /// Cleared for reliable transports, where loss stats are not meaningful.
+    loss_stats: bool,
+    /// Sender restarts detected via a large backward sequence jump.
+    sender_resets: u64,
+    /// Gap-size distribution per source: `source -> gap size -> count`.
+    /// Distinguishes random loss (many size-1 gaps) from bursts (few large).
+    seq_gaps: HashMap<String, HashMap<u32, u64>>,
+    /// Datagram-length distribution, for the format-change diagnostic.
+    frame_lengths: HashMap<usize, u64>,
+    format_changes: u64,
+    /// Session extremes and running sum of the composite health score.
+    health_min: f64,
+    health_max: f64,
+    health_sum: f64,
+    health_samples: u64,
+    /// Downstream tap counters, copied in from the forwarding worker.
+    forwarded_packets: u64,
+    forward_errors: u64,
+    forward_queue_drops: u64,
+    /// Log records shed because the async logging queue was full.
+    log_queue_drops: u64,
+    /// Command-link round-trip samples from the ping probe.
+    ping_rtts_ms: Vec<f64>,
+    /// Ping probes that drew no echo within their deadline.
+    ping_failures: u64,
+    /// Two-stage alert episodes per field, counted separately per tier.
+    warn_episodes: HashMap<&'static str, u64>,
+    alarm_episodes: HashMap<&'static str, u64>,
+    /// Implausible consecutive-sample jumps per field (`[GCS-RATE-SPIKE]`).
+    rate_spikes: HashMap<&'static str, u64>,
+    /// OCS restarts observed via the v2 boot counter.
+    ocs_restarts: u64,
+    /// Datagrams deliberately shed by the receive-side rate cap.
+    packets_shed: u64,
+    /// Datagrams dropped because their source was not on the allowlist.
+    untrusted_packets: u64,
+    /// Commanded mode changes never confirmed by mode-echoing telemetry.
+    unconfirmed_commands: u64,
+    /// Commands confirmed by the in-band telemetry echo, with their
+    /// command-to-confirmation latencies.
+    confirmed_commands: u64,
+    confirmation_latencies_ms: Vec<f64>,
+    /// Echoed command ids this GCS never sent.
+    unexpected_echoes: u64,
+    /// Clock-skew alarm episodes and the largest absolute deviation of the
+    /// estimated clock offset from its baseline (ms).
+    clock_skew_alarms: u64,
+    max_clock_skew_ms: u64,
+    /// Whether the timestamp-dependent figures are currently uncalibrated
+    /// because a clock-skew alarm is active.
+    clock_uncalibrated: bool,
+    /// Busy-fraction alarm episodes, plus the latest and peak busy share of
+    /// the receive loop (0..=1).
+    busy_alarms: u64,
+    busy_fraction: f64,
+    max_busy_fraction: f64,
+    /// Dropped datagrams broken down by rejection reason.
+    rejections_by_reason: HashMap<&'static str, u64>,
+    /// Stuck-sensor episodes (`[GCS-STUCK]`) per field.
+    stuck_episodes: HashMap<&'static str, u64>,
+    /// Batch containers unpacked, and their raw-vs-wire byte totals.
+    batches_received: u64,
+    batch_raw_bytes: u64,
+    batch_wire_bytes: u64,
+}
+
+/// Nearest-rank percentile of `samples` (`p` in `0.0..=100.0`), or `None`
+/// with no samples. Sorts a copy, so callers pay O(n log n) per query —
+/// fine at report cadence.
+fn percentile_of<T: Ord + Copy>(samples: &[T], p: f64) -> Option<T> {
+    if samples.is_empty() {
+        return None;
+    }
+    let mut sorted = samples.to_vec();
+    sorted.sort_unstable();
+    let rank = (sorted.len() as f64 * p / 100.0).ceil() as usize;
+    Some(sorted[rank.saturating_sub(1).min(sorted.len() - 1)])
+}
+
+impl GCSPerformanceMetrics {
+    pub fn new() -> Self {
+        GCSPerformanceMetrics {
+            packets_received: 0,
+            valid_packets: 0,
+            invalid_packets: 0,
+            packets_lost: 0,
+            unknown_version_packets: 0,
+            spoofed_packets: 0,
+            duplicate_packets: 0,
+            out_of_order_packets: 0,
+            edge_cases_detected: 0,
+            sustained_edge_episodes: 0,
+            max_edge_streak: 0,
+            decode_latencies_us: Vec::new(),
+            latency_violations: 0,
+            jitter_us: Reservoir::new(DEFAULT_RESERVOIR_CAPACITY, 1),
+            jitter_violations: 0,
+            faults_detected: HashMap::new(),
+            fault_episodes: HashMap::new(),
+            fault_response_times_ms: Reservoir::new(DEFAULT_RESERVOIR_CAPACITY, 2),
+            fault_response_by_type: HashMap::new(),
+            fault_response_violations: 0,
+            auto_commands: 0,
+            seq_span: None,
+            rate_anomaly_events: 0,
+            rate_anomaly_total_ms: 0.0,
+            window_lost_mark: 0,
+            window_expected_mark: 0,
+            loss_stats: true,
+            sender_resets: 0,
+            seq_gaps: HashMap::new(),
+            frame_lengths: HashMap::new(),
+            format_changes: 0,
+            health_min: f64::INFINITY,
+            health_max: 0.0,
+            health_sum: 0.0,
+            health_samples: 0,
+            forwarded_packets: 0,
+            forward_errors: 0,
+            forward_queue_drops: 0,
+            log_queue_drops: 0,
+            ping_rtts_ms: Vec::new(),
+            ping_failures: 0,
+            warn_episodes: HashMap::new(),
+            alarm_episodes: HashMap::new(),
+            rate_spikes: HashMap::new(),
+            ocs_restarts: 0,
+            packets_shed: 0,
+            untrusted_packets: 0,
+            unconfirmed_commands: 0,
+            confirmed_commands: 0,
+            confirmation_latencies_ms: Vec::new(),
+            unexpected_echoes: 0,
+            clock_skew_alarms: 0,
+            max_clock_skew_ms: 0,
+            clock_uncalibrated: false,
+            busy_alarms: 0,
+            busy_fraction: 0.0,
+            max_busy_fraction: 0.0,
+            rejections_by_reason: HashMap::new(),
+            stuck_episodes: HashMap::new(),
+            batches_received: 0,
+            batch_raw_bytes: 0,
+            batch_wire_bytes: 0,
+        }
+    }
+
+    /// Counts one inactive-to-warn transition for a monitored field.
+    pub fn record_warn_episode(&mut self, field: &'static str) {
+        *self.warn_episodes.entry(field).or_insert(0) += 1;
+    }
+
+    /// Counts one escalation to the alarm tier for a monitored field.
+    pub fn record_alarm_episode(&mut self, field: &'static str) {
+        *self.alarm_episodes.entry(field).or_insert(0) += 1;
+    }
+
+    /// Counts one implausible consecutive-sample jump for a field.
+    pub fn record_rate_spike(&mut self, field: &'static str) {
+        *self.rate_spikes.entry(field).or_insert(0) += 1;
+    }
+
+    /// Counts one stuck-sensor episode for a field.
+    pub fn record_stuck_episode(&mut self, field: &'static str) {
+        *self.stuck_episodes.entry(field).or_insert(0) += 1;
+    }
+
+    /// Counts one boot-counter change, i.e. an observed OCS restart.
+    pub fn record_ocs_restart(&mut self) {
+        self.ocs_restarts += 1;
+    }
+
+    /// Counts one datagram shed by the receive-side rate cap.
+    pub fn record_shed(&mut self) {
+        self.packets_shed += 1;
+    }
+
+    /// Counts one datagram dropped for coming from an unlisted source.
+    pub fn record_untrusted(&mut self) {
+        self.untrusted_packets += 1;
+    }
+
+    /// Books one unpacked batch container: the raw sample bytes it carried
+    /// and its size on the wire.
+    pub fn record_batch(&mut self, raw: usize, wire: usize) {
+        self.batches_received += 1;
+        self.batch_raw_bytes += raw as u64;
+        self.batch_wire_bytes += wire as u64;
+    }
+
+    /// Counts one commanded mode change that telemetry never confirmed.
+    pub fn record_unconfirmed_command(&mut self) {
+        self.unconfirmed_commands += 1;
+    }
+
+    /// Books one command confirmed by the telemetry echo.
+    pub fn record_command_confirmation(&mut self, latency_ms: f64) {
+        self.confirmed_commands += 1;
+        self.confirmation_latencies_ms.push(latency_ms);
+    }
+
+    /// Books an echoed command id with no matching outstanding command.
+    pub fn record_unexpected_echo(&mut self) {
+        self.unexpected_echoes += 1;
+    }
+
+    /// Tracks the largest absolute deviation of the estimated clock offset.
+    pub fn note_clock_offset(&mut self, abs_deviation_ms: u64) {
+        self.max_clock_skew_ms = self.max_clock_skew_ms.max(abs_deviation_ms);
+    }
+
+    /// Books one clock-skew alarm episode.
+    pub fn record_clock_skew_alarm(&mut self) {
+        self.clock_skew_alarms += 1;
+    }
+
+    /// Marks the timestamp-dependent figures trusted or not; the report
+    /// carries the flag, so a reader knows which state the run ended in.
+    pub fn set_clock_uncalibrated(&mut self, uncalibrated: bool) {
+        self.clock_uncalibrated = uncalibrated;
+    }
+
+    /// Records the receive loop's current windowed busy share.
+    pub fn note_busy_fraction(&mut self, fraction: f64) {
+        self.busy_fraction = fraction;
+        self.max_busy_fraction = self.max_busy_fraction.max(fraction);
+    }
+
+    /// Books one busy-fraction alarm episode.
+    pub fn record_busy_alarm(&mut self) {
+        self.busy_alarms += 1;
+    }
+
+    /// Overwrites the forwarding counters with the worker thread's totals.
+    /// Absolute rather than incremental so repeated syncs are idempotent.
+    pub fn set_forward_stats(&mut self, forwarded: u64, errors: u64, queue_drops: u64) {
+        self.forwarded_packets = forwarded;
+        self.forward_errors = errors;
+        self.forward_queue_drops = queue_drops;
+    }
+
+    /// Overwrites the async-logging drop counter with the logger's total.
+    pub fn set_log_queue_drops(&mut self, drops: u64) {
+        self.log_queue_drops = drops;
+    }
+
+    /// Overwrites the ping-probe figures with the worker's totals.
+    pub fn set_ping_stats(&mut self, rtts_ms: Vec<f64>, failures: u64) {
+        self.ping_rtts_ms = rtts_ms;
+        self.ping_failures = failures;
+    }
+
+    /// Marks the session as running over a reliable transport: the report
+    /// notes the transport and omits the loss/reorder statistics.
+    pub fn suppress_loss_stats(&mut self) {
+        self.loss_stats = false;
+    }
+
+    pub fn record_packet_received(&mut self) {
+        self.packets_received += 1;
+    }
+
+    /// Counts one datagram of the given wire length.
+    pub fn record_frame_length(&mut self, len: usize) {
+        *self.frame_lengths.entry(len).or_insert(0) += 1;
+    }
+
+    /// The most frequently seen datagram length, with its count. Ties keep
+    /// the smaller length so the modal value is deterministic.
+    pub fn modal_frame_length(&self) -> Option<(usize, u64)> {
+        self.frame_lengths
+            .iter()
+            .map(|(&len, &count)| (len, count))
+            .max_by_key(|&(len, count)| (count, std::cmp::Reverse(len)))
+    }
+
+    /// Counts one modal-length shift (`[GCS-FORMAT-CHANGE]`).
+    pub fn record_format_change(&mut self) {
+        self.format_changes += 1;
+    }
+
+    pub fn record_valid_packet(&mut self) {
+        self.valid_packets += 1;
+    }
+
+    pub fn record_invalid_packet(&mut self) {
+        self.invalid_packets += 1;
+    }
+
+    /// Widens the observed sequence span with a newly received sequence
+    /// number. Comparison is wrap-aware so a session crossing `u32::MAX`
+    /// keeps a sane expected count.
+    pub fn note_seq(&mut self, seq: u32) {
+        match &mut self.seq_span {
+            None => self.seq_span = Some((seq, seq)),
+            Some((lowest, highest)) => {
+                if (seq.wrapping_sub(*highest) as i32) > 0 {
+                    *highest = seq;
+                } else if ((*lowest).wrapping_sub(seq) as i32) > 0 {
+                    *lowest = seq;
+                }
+            }
+        }
+    }
+
+    /// Packets the sender should have produced over the observed span.
+    pub fn expected_packets(&self) -> u64 {
+        match self.seq_span {
+            None => 0,
+            Some((lowest, highest)) => highest.wrapping_sub(lowest) as u64 + 1,
+        }
+    }
+
+    /// Counts one sustained rate-anomaly episode and its duration.
+    pub fn record_rate_anomaly(&mut self, duration_ms: f64) {
+        self.rate_anomaly_events += 1;
+        self.rate_anomaly_total_ms += duration_ms;
+    }
+
+    /// Counts an automatic mitigation command sent without operator action.
+    pub fn record_auto_command(&mut self) {
+        self.auto_commands += 1;
+    }
+
+    /// Counts a frame whose version byte has no registered decoder.
+    pub fn record_unknown_version(&mut self) {
+        self.unknown_version_packets += 1;
+    }
+
+    /// Counts a frame rejected because its authentication tag did not verify.
+    pub fn record_spoofed(&mut self) {
+        self.spoofed_packets += 1;
+    }
+
+    /// Counts one dropped datagram under its rejection reason, for the
+    /// per-reason breakdown in the report.
+    pub fn record_rejection(&mut self, reason: &'static str) {
+        *self.rejections_by_reason.entry(reason).or_insert(0) += 1;
+    }
+
+    pub fn record_packet_lost(&mut self) {
+        self.packets_lost += 1;
+    }
+
+    /// Counts one detected sender reset and restarts the sequence span so the
+    /// backward jump is not booked as loss.
+    pub fn record_sender_reset(&mut self, seq: u32) {
+        self.sender_resets += 1;
+        self.seq_span = Some((seq, seq));
+    }
+
+    /// Counts one sequence gap of the given size (packets missing in a row)
+    /// from the named source.
+    pub fn record_seq_gap(&mut self, source: &str, gap: u32) {
+        *self
+            .seq_gaps
+            .entry(source.to_string())
+            .or_default()
+            .entry(gap)
+            .or_insert(0) += 1;
+    }
+
+    pub fn record_duplicate(&mut self) {
+        self.duplicate_packets += 1;
+    }
+
+    pub fn record_out_of_order(&mut self) {
+        self.out_of_order_packets += 1;
+    }
+
+    pub fn record_edge_case(&mut self) {
+        self.edge_cases_detected += 1;
+    }
+
+    /// Counts one sustained-edge episode (streak exceeding the limit).
+    pub fn record_sustained_edge(&mut self) {
+        self.sustained_edge_episodes += 1;
+    }
+
+    /// Widens the longest observed run of consecutive edge-case packets.
+    pub fn note_edge_streak(&mut self, streak: u64) {
+        self.max_edge_streak = self.max_edge_streak.max(streak);
+    }
+
+    pub fn record_decode_latency(&mut self, latency_us: u128) {
+        if latency_us > DECODE_LATENCY_THRESHOLD_US {
+            self.latency_violations += 1;
+        }
+        self.decode_latencies_us.push(latency_us);
+    }
+
+    pub fn record_jitter(&mut self, jitter_us: i64) {
+        self.jitter_us.push(jitter_us);
+    }
+
+    /// Counts a packet whose jitter fell outside the tolerance band.
+    pub fn record_jitter_violation(&mut self) {
+        self.jitter_violations += 1;
+    }
+
+    /// Largest retained jitter excursion by magnitude, or 0 with no samples.
+    pub fn worst_jitter_us(&self) -> i64 {
+        self.jitter_us.iter().copied().max_by_key(|j| j.abs()).unwrap_or(0)
+    }
+
+    /// Folds one packet's composite health score into the session extremes.
+    pub fn record_health(&mut self, score: f64) {
+        self.health_min = self.health_min.min(score);
+        self.health_max = self.health_max.max(score);
+        self.health_sum += score;
+        self.health_samples += 1;
+    }
+
+    pub fn record_fault(&mut self, fault: Fault) {
+        *self.faults_detected.entry(fault).or_insert(0) += 1;
+    }
+
+    /// Counts one inactive-to-active transition of a fault: "how many times
+    /// did the battery go low", as opposed to how many packets reported it.
+    pub fn record_fault_episode(&mut self, fault: Fault) {
+        *self.fault_episodes.entry(fault).or_insert(0) += 1;
+    }
+
+    /// Records the measured time from fault detection to completed response,
+    /// counting a violation if it exceeds [`FAULT_RESPONSE_THRESHOLD_MS`].
+    /// The sample is kept both in the flat aggregate and bucketed per fault
+    /// type, so a slow fault class can be pinpointed.
+    pub fn record_fault_response(&mut self, faults: &[Fault], response_ms: f64) {
+        if response_ms > FAULT_RESPONSE_THRESHOLD_MS as f64 {
+            self.fault_response_violations += 1;
+        }
+        self.fault_response_times_ms.push(response_ms);
+        for &fault in faults {
+            self.fault_response_by_type
+                .entry(fault)
+                .or_default()
+                .push(response_ms);
+        }
+    }
+
+    pub fn packets_received(&self) -> u64 {
+        self.packets_received
+    }
+
+    pub fn packets_lost(&self) -> u64 {
+        self.packets_lost
+    }
+
+    /// Total fault occurrences across all fault types.
+    pub fn total_faults(&self) -> u64 {
+        self.faults_detected.values().sum()
+    }
+
+    /// Nearest-rank percentile of the decode latencies (`p` in `0..=100`),
+    /// or 0 with no samples; small sample counts clamp to the extremes
+    /// rather than interpolating.
+    pub fn decode_latency_percentile_us(&self, p: f64) -> u128 {
+        percentile_of(&self.decode_latencies_us, p).unwrap_or(0)
+    }
+
+    /// Nearest-rank percentile of the retained jitter magnitudes, or 0 with
+    /// no samples. Magnitudes rather than signed values, since the tail of
+    /// interest is "how far off the expected interval", whichever side.
+    pub fn abs_jitter_percentile_us(&self, p: f64) -> i64 {
+        let magnitudes: Vec<i64> = self.jitter_us.iter().map(|j| j.abs()).collect();
+        percentile_of(&magnitudes, p).unwrap_or(0)
+    }
+
+    /// 95th-percentile decode latency, or 0 with no samples.
+    pub(crate) fn decode_p95_us(&self) -> u128 {
+        self.decode_latency_percentile_us(95.0)
+    }
+
+    /// Captures the run's comparable performance figures for the golden-run
+    /// regression guard. Rates and averages rather than raw counts, so runs
+    /// of different lengths compare meaningfully; nothing time- or
+    /// session-specific (timestamps, addresses) is included.
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        let expected = self.expected_packets();
+        let loss_pct = if expected == 0 {
+            0.0
+        } else {
+            100.0 * self.packets_lost as f64 / expected as f64
+        };
+        let invalid_pct = if self.packets_received == 0 {
+            0.0
+        } else {
+            100.0 * self.invalid_packets as f64 / self.packets_received as f64
+        };
+        let avg_decode_us = if self.decode_latencies_us.is_empty() {
+            0.0
+        } else {
+            self.decode_latencies_us.iter().sum::<u128>() as f64
+                / self.decode_latencies_us.len() as f64
+        };
+        let jitter_samples = self.jitter_us.iter().count();
+        let avg_abs_jitter_us = if jitter_samples == 0 {
+            0.0
+        } else {
+            self.jitter_us.iter().map(|&j| j.unsigned_abs() as f64).sum::<f64>()
+                / jitter_samples as f64
+        };
+        MetricsSnapshot {
+            packets_received: self.packets_received,
+            loss_pct,
+            invalid_pct,
+            avg_decode_us,
+            p95_decode_us: self.decode_p95_us() as f64,
+            avg_abs_jitter_us,
+        }
+    }
+
+    /// Copies the raw tallies into a [`GCSMetricsSnapshot`]; see there for
+    /// how this differs from [`GCSPerformanceMetrics::snapshot`].
+    pub fn counters(&self) -> GCSMetricsSnapshot {
+        let avg_decode_latency_us = if self.decode_latencies_us.is_empty() {
+            0.0
+        } else {
+            self.decode_latencies_us.iter().sum::<u128>() as f64
+                / self.decode_latencies_us.len() as f64
+        };
+        GCSMetricsSnapshot {
+            packets_received: self.packets_received,
+            valid_packets: self.valid_packets,
+            invalid_packets: self.invalid_packets,
+            packets_lost: self.packets_lost,
+            duplicate_packets: self.duplicate_packets,
+            out_of_order_packets: self.out_of_order_packets,
+            unknown_version_packets: self.unknown_version_packets,
+            edge_cases_detected: self.edge_cases_detected,
+            latency_violations: self.latency_violations,
+            jitter_violations: self.jitter_violations,
+            faults_detected: self.total_faults(),
+            sender_resets: self.sender_resets,
+            ocs_restarts: self.ocs_restarts,
+            avg_decode_latency_us,
+            p95_decode_latency_us: self.decode_p95_us(),
+        }
+    }
+
+    /// Encodes the session outcome as a process exit code so CI can tell
+    /// failure classes apart without parsing logs. `0` means all constraints
+    /// were met; otherwise the applicable bits are ORed together:
+    /// [`EXIT_LATENCY_VIOLATION`] (8) for any decode-latency overrun,
+    /// [`EXIT_LOSS_OF_CONTACT`] (16) for any loss-of-contact episode, and
+    /// [`EXIT_FAULT_RESPONSE_OVERRUN`] (32) for any fault response over its
+    /// real-time budget. A bitmask rather than "most severe" was chosen so a
+    /// run failing in several ways reports all of them at once.
+    pub fn exit_code(&self) -> i32 {
+        let mut code = 0;
+        if self.latency_violations > 0 {
+            code |= EXIT_LATENCY_VIOLATION;
+        }
+        if self.faults_detected.contains_key(&Fault::LossOfContact) {
+            code |= EXIT_LOSS_OF_CONTACT;
+        }
+        if self.fault_response_violations > 0 {
+            code |= EXIT_FAULT_RESPONSE_OVERRUN;
+        }
+        code
+    }
+
+    /// Prints the full performance report, advancing the loss-rate window.
+    pub fn report(&mut self) {
+        print!("{}", self.report_text());
+    }
+
+    /// Writes the decode-latency, jitter and gap-size distributions as one
+    /// CSV (`histogram,bucket_lower,bucket_upper,count`) for offline
+    /// plotting. Latency and jitter use [`HISTOGRAM_EXPORT_BUCKETS`]
+    /// equal-width buckets over the observed range; gap sizes, being small
+    /// integers, are exported exactly. Empty distributions are omitted.
+    pub fn export_histograms_csv(&self, path: &std::path::Path) -> io::Result<()> {
+        let mut out = String::from("histogram,bucket_lower,bucket_upper,count\n");
+        append_histogram(
+            &mut out,
+            "decode_latency_us",
+            &self.decode_latencies_us.iter().map(|&v| v as f64).collect::<Vec<_>>(),
+        );
+        append_histogram(
+            &mut out,
+            "jitter_us",
+            &self.jitter_us.iter().map(|&v| v as f64).collect::<Vec<_>>(),
+        );
+        let mut gap_counts: HashMap<u32, u64> = HashMap::new();
+        for per_source in self.seq_gaps.values() {
+            for (&gap, &count) in per_source {
+                *gap_counts.entry(gap).or_insert(0) += count;
+            }
+        }
+        let mut gaps: Vec<_> = gap_counts.into_iter().collect();
+        gaps.sort_unstable();
+        for (gap, count) in gaps {
+            out.push_str(&format!("gap_size,{gap},{gap},{count}\n"));
+        }
+        std::fs::write(path, out)
+    }
+
+    /// Builds the full performance report as text, advancing the loss-rate
+    /// window, so it can be printed locally or returned over a control
+    /// channel.
+    pub fn report_text(&mut self) -> String {
+        use std::fmt::Write;
+        let mut out = String::new();
+        let _ = writeln!(out, "===== GCS Performance Report =====");
+        let _ = writeln!(out, "Packets received:   {}", self.packets_received);
+        let _ = writeln!(out, "Valid packets:      {}", self.valid_packets);
+        let _ = writeln!(out, "Invalid packets:    {}", self.invalid_packets);
+        let _ = writeln!(out, "Unknown versions:   {}", self.unknown_version_packets);
+        let _ = writeln!(out, "Spoofed (bad tag):  {}", self.spoofed_packets);
+        if self.packets_shed > 0 {
+            let _ = writeln!(out, "Shed (rate cap):    {}", self.packets_shed);
+        }
+        if self.untrusted_packets > 0 {
+            let _ = writeln!(out, "Untrusted dropped:  {}", self.untrusted_packets);
+        }
+        if !self.rejections_by_reason.is_empty() {
+            let mut reasons: Vec<_> = self.rejections_by_reason.iter().collect();
+            reasons.sort_by_key(|(reason, _)| *reason);
+            let listed = reasons
+                .iter()
+                .map(|(reason, count)| format!("{reason} x{count}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let _ = writeln!(out, "Rejection reasons:  {listed}");
+        }
+        if !self.loss_stats {
+            let _ = writeln!(out, "Transport:          tcp (loss/reorder stats not applicable)");
+        } else {
+            let _ = writeln!(out, "Packets lost:       {}", self.packets_lost);
+            let expected = self.expected_packets();
+            if expected == 0 {
+                let _ = writeln!(out, "Loss rate:          n/a (no sequenced packets)");
+            } else {
+                let overall = 100.0 * self.packets_lost as f64 / expected as f64;
+                let window_expected = expected.saturating_sub(self.window_expected_mark);
+                let window_lost = self.packets_lost.saturating_sub(self.window_lost_mark);
+                if window_expected == 0 {
+                    let _ = writeln!(out, "Loss rate:          {overall:.2}% overall");
+                } else {
+                    let window = 100.0 * window_lost as f64 / window_expected as f64;
+                    let _ = writeln!(out, "Loss rate:          {overall:.2}% overall, {window:.2}% this window");
+                }
+                self.window_expected_mark = expected;
+                self.window_lost_mark = self.packets_lost;
+            }
+            let _ = writeln!(out, "Duplicates:         {}", self.duplicate_packets);
+            let _ = writeln!(out, "Out of order:       {}", self.out_of_order_packets);
+            if self.sender_resets > 0 {
+                let _ = writeln!(out, "Sender resets:      {}", self.sender_resets);
+            }
+            if !self.seq_gaps.is_empty() {
+                let _ = writeln!(out, "Gap sizes by source:");
+                let mut sources: Vec<_> = self.seq_gaps.iter().collect();
+                sources.sort_by_key(|(source, _)| source.as_str());
+                for (source, gaps) in sources {
+                    let mut sizes: Vec<_> = gaps.iter().collect();
+                    sizes.sort();
+                    let listed = sizes
+                        .iter()
+                        .map(|(size, count)| format!("{size} x{count}"))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    let _ = writeln!(out, "  {source}: {listed}");
+                }
+            }
+        }
+        if !self.frame_lengths.is_empty() {
+            let mut lengths: Vec<_> = self.frame_lengths.iter().collect();
+            lengths.sort();
+            let listed = lengths
+                .iter()
+                .map(|(len, count)| format!("{len}B x{count}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let _ = writeln!(out, "Frame lengths:      {listed}");
+            if self.format_changes > 0 {
+                let _ = writeln!(out, "Format changes:     {}", self.format_changes);
+            }
+        }
+        let _ = writeln!(out, "Edge cases:         {}", self.edge_cases_detected);
+        let _ = writeln!(
+            out,
+            "Sustained edge:     {} episodes (max streak {})",
+            self.sustained_edge_episodes, self.max_edge_streak
+        );
+        let _ = writeln!(out, "Auto commands:      {}", self.auto_commands);
+        if self.forwarded_packets > 0 || self.forward_errors > 0 || self.forward_queue_drops > 0 {
+            let _ = writeln!(
+                out,
+                "Forwarded:          {} ({} errors, {} queue drops)",
+                self.forwarded_packets, self.forward_errors, self.forward_queue_drops
+            );
+        }
+        if self.log_queue_drops > 0 {
+            let _ = writeln!(out, "Log records shed:   {}", self.log_queue_drops);
+        }
+        if !self.ping_rtts_ms.is_empty() || self.ping_failures > 0 {
+            let n = self.ping_rtts_ms.len();
+            let avg = self.ping_rtts_ms.iter().sum::<f64>() / (n.max(1)) as f64;
+            let max = self.ping_rtts_ms.iter().cloned().fold(0.0_f64, f64::max);
+            let mut sorted = self.ping_rtts_ms.clone();
+            sorted.sort_unstable_by(f64::total_cmp);
+            let p95 = sorted
+                .get(((n as f64 * 0.95).ceil() as usize).saturating_sub(1).min(n.saturating_sub(1)))
+                .copied()
+                .unwrap_or(0.0);
+            let _ = writeln!(
+                out,
+                "Command RTT:        n={n} avg={avg:.3}ms p95={p95:.3}ms max={max:.3}ms ({} lost)",
+                self.ping_failures
+            );
+        }
+        let _ = writeln!(
+            out,
+            "Rate anomalies:     {} ({:.1} s total)",
+            self.rate_anomaly_events,
+            self.rate_anomaly_total_ms / 1000.0
+        );
+        if !self.decode_latencies_us.is_empty() {
+            let min = self.decode_latencies_us.iter().min().unwrap();
+            let max = self.decode_latencies_us.iter().max().unwrap();
+            let avg = self.decode_latencies_us.iter().sum::<u128>()
+                / self.decode_latencies_us.len() as u128;
+            let _ = writeln!(
+                out,
+                "Decode latency (us): min={min} avg={avg} p50={} p95={} p99={} max={max}",
+                self.decode_latency_percentile_us(50.0),
+                self.decode_latency_percentile_us(95.0),
+                self.decode_latency_percentile_us(99.0)
+            );
+            let status = if self.latency_violations == 0 { "MET" } else { "VIOLATED" };
+            let _ = writeln!(
+                out,
+                "Decode constraint ({}us): {} ({} violations)",
+                DECODE_LATENCY_THRESHOLD_US, status, self.latency_violations
+            );
+        }
+        if !self.jitter_us.is_empty() {
+            let avg = self.jitter_us.iter().map(|j| j.abs()).sum::<i64>()
+                / self.jitter_us.len() as i64;
+            let _ = writeln!(
+                out,
+                "|Jitter| (us):      avg={avg} p50={} p95={} p99={}",
+                self.abs_jitter_percentile_us(50.0),
+                self.abs_jitter_percentile_us(95.0),
+                self.abs_jitter_percentile_us(99.0)
+            );
+            let _ = writeln!(
+                out,
+                "Jitter violations:  {} (worst excursion {} us)",
+                self.jitter_violations,
+                self.worst_jitter_us()
+            );
+        }
+        if self.health_samples > 0 {
+            let _ = writeln!(
+                out,
+                "Health score:       min={:.1} avg={:.1} worst={:.1}",
+                self.health_min,
+                self.health_sum / self.health_samples as f64,
+                self.health_max
+            );
+        }
+        if !self.warn_episodes.is_empty() || !self.alarm_episodes.is_empty() {
+            let _ = writeln!(out, "Alert episodes:");
+            let mut fields: Vec<&'static str> = self
+                .warn_episodes
+                .keys()
+                .chain(self.alarm_episodes.keys())
+                .copied()
+                .collect();
+            fields.sort_unstable();
+            fields.dedup();
+            for field in fields {
+                let warns = self.warn_episodes.get(field).copied().unwrap_or(0);
+                let alarms = self.alarm_episodes.get(field).copied().unwrap_or(0);
+                let _ = writeln!(out, "  {field:<22} {warns} warn, {alarms} alarm");
+            }
+        }
+        if self.batches_received > 0 {
+            let _ = writeln!(
+                out,
+                "Batches unpacked:   {} ({} B raw from {} B wire, {:.2}x)",
+                self.batches_received,
+                self.batch_raw_bytes,
+                self.batch_wire_bytes,
+                self.batch_raw_bytes as f64 / self.batch_wire_bytes as f64
+            );
+        }
+        if self.ocs_restarts > 0 {
+            let _ = writeln!(out, "OCS restarts:       {}", self.ocs_restarts);
+        }
+        if self.unconfirmed_commands > 0 {
+            let _ = writeln!(out, "Unconfirmed cmds:   {}", self.unconfirmed_commands);
+        }
+        if self.confirmed_commands > 0 {
+            let avg = self.confirmation_latencies_ms.iter().sum::<f64>()
+                / self.confirmation_latencies_ms.len() as f64;
+            let _ = writeln!(
+                out,
+                "Confirmed cmds:     {} (avg {avg:.1} ms in-band)",
+                self.confirmed_commands
+            );
+        }
+        if self.unexpected_echoes > 0 {
+            let _ = writeln!(out, "Unexpected echoes:  {}", self.unexpected_echoes);
+        }
+        if self.clock_skew_alarms > 0 {
+            let status = if self.clock_uncalibrated { "UNCALIBRATED" } else { "recalibrated" };
+            let _ = writeln!(
+                out,
+                "Clock skew alarms:  {} (max offset {} ms, timestamp metrics {status})",
+                self.clock_skew_alarms, self.max_clock_skew_ms
+            );
+        }
+        if self.max_busy_fraction > 0.0 {
+            let _ = writeln!(
+                out,
+                "Receive busy:       {:.1}% of wall time (peak {:.1}%, {} alarms)",
+                self.busy_fraction * 100.0,
+                self.max_busy_fraction * 100.0,
+                self.busy_alarms
+            );
+        }
+        if !self.rate_spikes.is_empty() {
+            let _ = writeln!(out, "Rate spikes:");
+            let mut entries: Vec<_> = self.rate_spikes.iter().collect();
+            entries.sort_unstable_by_key(|(field, _)| *field);
+            for (field, count) in entries {
+                let _ = writeln!(out, "  {field:<22} {count}");
+            }
+        }
+        if !self.stuck_episodes.is_empty() {
+            let _ = writeln!(out, "Stuck sensors:");
+            let mut entries: Vec<_> = self.stuck_episodes.iter().collect();
+            entries.sort_unstable_by_key(|(field, _)| *field);
+            for (field, count) in entries {
+                let _ = writeln!(out, "  {field:<22} {count}");
+            }
+        }
+        if !self.faults_detected.is_empty() {
+            let _ = writeln!(out, "Faults detected:");
+            let mut entries: Vec<_> = self.faults_detected.iter().collect();
+            entries.sort_by_key(|(f, _)| f.name());
+            for (fault, count) in entries {
+                let episodes = self.fault_episodes.get(fault).copied().unwrap_or(0);
+                let _ = writeln!(
+                    out,
+                    "  {:<22} {count} occurrences, {episodes} episodes",
+                    fault.name()
+                );
+            }
+        }
+        if self.fault_response_times_ms.is_empty() {
+            let _ = writeln!(out, "Average fault response: n/a (no samples)");
+        } else {
+            let avg = self.fault_response_times_ms.iter().sum::<f64>()
+                / self.fault_response_times_ms.len() as f64;
+            let status = if self.fault_response_violations == 0 { "MET" } else { "VIOLATED" };
+            let _ = writeln!(out, "Average fault response: {avg:.3} ms");
+            let _ = writeln!(
+                out,
+                "Fault response constraint ({}ms): {} ({} violations)",
+                FAULT_RESPONSE_THRESHOLD_MS, status, self.fault_response_violations
+            );
+            let mut entries: Vec<_> = self.fault_response_by_type.iter().collect();
+            entries.sort_by_key(|(f, _)| f.name());
+            for (fault, samples) in entries {
+                let avg = samples.iter().sum::<f64>() / samples.len() as f64;
+                let max = samples.iter().cloned().fold(0.0_f64, f64::max);
+                let _ = writeln!(
+                    out,
+                    "  {:<22} n={} avg={avg:.3}ms max={max:.3}ms",
+                    fault.name(),
+                    samples.len()
+                );
+            }
+        }
+        let _ = writeln!(out, "==================================");
+        out
+    }
+}
+
+impl Default for GCSPerformanceMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The comparable subset of a run's metrics, saved as flat JSON and diffed
+/// against a later run by the golden-run regression guard. Only rates and
+/// latency averages appear here: raw counts depend on run length and
+/// timestamps on wall clock, so neither compares across runs. For every
+/// compared metric larger is worse.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricsSnapshot {
+    /// Context only (shown in the verdict), never compared.
+    pub packets_received: u64,
+    pub loss_pct: f64,
+    pub invalid_pct: f64,
+    pub avg_decode_us: f64,
+    pub p95_decode_us: f64,
+    pub avg_abs_jitter_us: f64,
+}
+
+/// Point-in-time copy of the raw per-session tallies, so tests and external
+/// consumers can assert on metrics without scraping the report output.
+/// Unlike [`MetricsSnapshot`] — rates and averages for cross-run golden
+/// comparisons — these are plain counters; being `Copy` and `PartialEq`,
+/// two captures bracket an operation and diff cleanly.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GCSMetricsSnapshot {
+    pub packets_received: u64,
+    pub valid_packets: u64,
+    pub invalid_packets: u64,
+    pub packets_lost: u64,
+    pub duplicate_packets: u64,
+    pub out_of_order_packets: u64,
+    pub unknown_version_packets: u64,
+    pub edge_cases_detected: u64,
+    pub latency_violations: u64,
+    pub jitter_violations: u64,
+    /// Fault occurrences summed across all fault types.
+    pub faults_detected: u64,
+    pub sender_resets: u64,
+    pub ocs_restarts: u64,
+    pub avg_decode_latency_us: f64,
+    pub p95_decode_latency_us: u128,
+}
+
+impl MetricsSnapshot {
+    /// The compared metrics as `(name, golden value, current value)` rows.
+    fn compared(&self, golden: &MetricsSnapshot) -> [(&'static str, f64, f64); 5] {
+        [
+            ("loss_pct", golden.loss_pct, self.loss_pct),
+            ("invalid_pct", golden.invalid_pct, self.invalid_pct),
+            ("avg_decode_us", golden.avg_decode_us, self.avg_decode_us),
+            ("p95_decode_us", golden.p95_decode_us, self.p95_decode_us),
+            ("avg_abs_jitter_us", golden.avg_abs_jitter_us, self.avg_abs_jitter_us),
+        ]
+    }
+
+    /// Metrics that worsened beyond `tolerance_pct` relative to the golden
+    /// baseline, as printable descriptions; empty means the run passed.
+    /// Improvements are never flagged. A zero baseline allows no worsening
+    /// at all: any nonzero current value of that metric is a regression.
+    pub fn regressions_against(
+        &self,
+        golden: &MetricsSnapshot,
+        tolerance_pct: f64,
+    ) -> Vec<String> {
+        let mut out = Vec::new();
+        for (name, base, current) in self.compared(golden) {
+            let allowed = base * (1.0 + tolerance_pct / 100.0);
+            if current > allowed + 1e-9 {
+                out.push(format!(
+                    "{name}: {current:.2} vs golden {base:.2} (allowed {allowed:.2})"
+                ));
+            }
+        }
+        out
+    }
+
+    /// The snapshot as a single flat JSON object.
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"packets_received\":{},\"loss_pct\":{:.4},\"invalid_pct\":{:.4},\
+             \"avg_decode_us\":{:.4},\"p95_decode_us\":{:.4},\"avg_abs_jitter_us\":{:.4}}}\n",
+            self.packets_received,
+            self.loss_pct,
+            self.invalid_pct,
+            self.avg_decode_us,
+            self.p95_decode_us,
+            self.avg_abs_jitter_us,
+        )
+    }
+
+    pub fn save(&self, path: &std::path::Path) -> io::Result<()> {
+        std::fs::write(path, self.to_json())
+    }
+
+    pub fn load(path: &std::path::Path) -> io::Result<MetricsSnapshot> {
+        let text = std::fs::read_to_string(path)?;
+        Self::parse(&text).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{} is not a metrics snapshot", path.display()),
+            )
+        })
+    }
+
+    /// Parses the flat JSON object written by [`MetricsSnapshot::to_json`].
+    /// Unknown keys are ignored so snapshots stay loadable as fields grow.
+    fn parse(text: &str) -> Option<MetricsSnapshot> {
+        let mut map = HashMap::new();
+        let body = text.trim().strip_prefix('{')?.strip_suffix('}')?;
+        for pair in body.split(',') {
+            let (key, value) = pair.split_once(':')?;
+            map.insert(key.trim().trim_matches('"').to_string(), value.trim().parse::<f64>().ok()?);
+        }
+        Some(MetricsSnapshot {
+            packets_received: *map.get("packets_received")? as u64,
+            loss_pct: *map.get("loss_pct")?,
+            invalid_pct: *map.get("invalid_pct")?,
+            avg_decode_us: *map.get("avg_decode_us")?,
+            p95_decode_us: *map.get("p95_decode_us")?,
+            avg_abs_jitter_us: *map.get("avg_abs_jitter_us")?,
+        })
+    }
+}
+
+/// Downstream telemetry tap for relay/tee topologies.
+///
+/// Every valid datagram is queued toward a worker thread that re-transmits it
+/// unchanged to the configured address, so a slow or unreachable downstream
+/// never blocks the receive loop. The queue is bounded: when the worker falls
+/// behind, the forward is shed (and counted), never the local processing.
+struct Forwarder {
+    queue: mpsc::SyncSender<Vec<u8>>,
+    /// Worker-side totals, read back at report time.
+    forwarded: Arc<AtomicU64>,
+    errors: Arc<AtomicU64>,
+    /// Frames shed on the receive side because the queue was full.
+    queue_drops: u64,
+}
+
+/// Periodic command-link round-trip probe.
+///
+/// A worker thread sends `PING <n>` to the OCS command port every interval
+/// and waits for the matching `ACK PONG <n>`, so the round trip is measured
+/// continuously and independently of the downlink. A probe that times out is
+/// counted as lost rather than blocking the next one; the receive loop never
+/// waits on the probe socket.
+struct PingProbe {
+    /// Worker-side round-trip samples, copied into the metrics at sync time.
+    rtts_ms: Arc<std::sync::Mutex<Vec<f64>>>,
+    failures: Arc<AtomicU64>,
+}
+
+/// One ready-to-write record handed to the background logging thread.
+enum LogRecord {
+    Capture {
+        telemetry: Telemetry,
+        health: f64,
+        arrival_delta_us: Option<u64>,
+    },
+    Rejection {
+        reason: &'static str,
+        source: String,
+        data: Vec<u8>,
+    },
+}
+
+/// Graceful-degradation ladder state: when per-packet processing time
+/// persistently overruns the configured budget, optional work is shed in
+/// priority order and restored (with hysteresis) once the load recovers.
+struct Degradation {
+    /// Per-packet processing budget in microseconds.
+    budget_us: u64,
+    /// Current ladder level, an index into [`DEGRADE_LEVEL_NAMES`].
+    level: usize,
+    over_streak: u32,
+    under_streak: u32,
+    entered_at: Instant,
+    /// Cumulative time spent at each ladder level.
+    time_at: [Duration; DEGRADE_LEVEL_NAMES.len()],
+}
+
+impl Degradation {
+    fn new(budget_us: u64) -> Self {
+        Degradation {
+            budget_us,
+            level: 0,
+            over_streak: 0,
+            under_streak: 0,
+            entered_at: Instant::now(),
+            time_at: [Duration::ZERO; DEGRADE_LEVEL_NAMES.len()],
+        }
+    }
+
+    /// Moves to `level`, charging the elapsed interval to the level left.
+    fn step_to(&mut self, level: usize) {
+        self.time_at[self.level] += self.entered_at.elapsed();
+        self.level = level;
+        self.over_streak = 0;
+        self.under_streak = 0;
+        self.entered_at = Instant::now();
+    }
+}
+
+/// Clock-relationship watchdog state. Each packet's mission timestamp
+/// against its local arrival time yields an estimate of the offset between
+/// the two clocks; the constant part is arbitrary (the epochs differ), so
+/// deviations are measured from the first post-warm-up sample. A deviation
+/// beyond the configured bound, or an offset moving faster than the
+/// drift-rate bound, raises `[GCS-CLOCK-SKEW]` — and while that alarm is
+/// active the timestamp-dependent figures are flagged as uncalibrated,
+/// since neither packet age nor end-to-end latency means anything against
+/// an untrusted clock.
+struct ClockSkew {
+    max_offset_ms: i64,
+    max_drift_ms_per_s: f64,
+    /// First post-warm-up offset sample; the epoch difference to cancel out.
+    baseline_ms: Option<i64>,
+    /// Anchor for the drift-rate estimate — when it was taken and the offset
+    /// then — replaced every [`CLOCK_DRIFT_WINDOW`].
+    anchor: Option<(Instant, i64)>,
+    /// Offset change per second over the most recent complete window.
+    last_drift_ms_per_s: f64,
+    alarm_active: bool,
+}
+
+/// Receive-loop saturation gauge. Wall time is split into "blocked waiting
+/// in recv" and "busy processing" spans bracketed around the blocking call;
+/// when the busy share of a sliding [`BUSY_WINDOW`] exceeds the configured
+/// budget, `[GCS-BUSY]` warns that the loop is nearing saturation — before
+/// the kernel buffer overflows and the shortfall shows up as loss.
+struct BusyMonitor {
+    /// Busy-fraction budget, `0.0..=1.0`.
+    budget: f64,
+    /// Completed loop iterations still inside the window: when the span
+    /// ended, its busy share and its total length.
+    spans: VecDeque<(Instant, Duration, Duration)>,
+    /// Running sums over `spans`, so each evaluation is O(1).
+    busy_sum: Duration,
+    total_sum: Duration,
+    alarm_active: bool,
+}
+
+impl BusyMonitor {
+    /// Busy share of the retained window (0 with no history yet).
+    fn fraction(&self) -> f64 {
+        if self.total_sum.is_zero() {
+            0.0
+        } else {
+            self.busy_sum.as_secs_f64() / self.total_sum.as_secs_f64()
+        }
+    }
+}
+
+/// Automatic load-shedding response to a critically low battery.
+///
+/// When telemetry reports the battery below `floor_mv`, the GCS commands
+/// `SET_MODE safe` on the uplink exactly once; it re-arms only after the
+/// battery recovers above `clear_mv` (hysteresis), so a level hovering at the
+/// floor does not produce a stream of repeated commands.
+struct AutoSafe {
+    sender: CommandSender,
+    floor_mv: u16,
+    clear_mv: u16,
+    engaged: bool,
+}
+
+/// One fault occurrence handed to a [`MitigationPolicy`], pairing the fault
+/// class with the sample that triggered it.
+#[derive(Debug, Clone, Copy)]
+pub struct FaultEvent {
+    pub fault: Fault,
+    pub telemetry: Telemetry,
+}
+
+/// Automatic command response to detected faults.
+///
+/// The GCS calls `on_fault` once per fault episode (when a fault first
+/// appears, not on every packet reporting it) with an uplink sender aimed at
+/// the OCS command port. Implementations decide which command, if any, to
+/// send — encapsulating the response so alternatives can be swapped in and
+/// tested in isolation by driving them with synthetic [`FaultEvent`]s.
+pub trait MitigationPolicy {
+    fn on_fault(&mut self, event: &FaultEvent, uplink: &mut CommandSender);
+}
+
+/// Built-in responses: `SET_MODE safe` on a low battery and `SET_ANTENNA 0`
+/// on a misaligned antenna; everything else is log-only.
+pub struct DefaultPolicy;
+
+impl MitigationPolicy for DefaultPolicy {
+    fn on_fault(&mut self, event: &FaultEvent, uplink: &mut CommandSender) {
+        let command = match event.fault {
+            Fault::LowBattery => "SET_MODE safe",
+            Fault::AntennaMisalignment => "SET_ANTENNA 0",
+            _ => return,
+        };
+        println!("[GCS-MITIGATE] {}: commanding {command}", event.fault.name());
+        if let Err(e) = uplink.send(command) {
+            eprintln!("[GCS-MITIGATE] {command} failed: {e}");
+        }
+    }
+}
+
+/// Pure monitoring: every fault is observed but none is acted on.
+pub struct NoOpPolicy;
+
+impl MitigationPolicy for NoOpPolicy {
+    fn on_fault(&mut self, _event: &FaultEvent, _uplink: &mut CommandSender) {}
+}
+
+/// One accepted packet as handed to a [`TelemetrySink`]: the decoded sample
+/// plus the derived values the receive loop reports alongside it.
+#[derive(Debug, Clone, Copy)]
+pub struct PacketRecord<'a> {
+    pub telemetry: &'a Telemetry,
+    pub health: f64,
+    pub decode_latency_us: u128,
+    /// Antenna angle normalized into the GCS's display convention.
+    pub display_angle: i16,
+}
+
+/// Destination for the GCS's structured output: the per-packet line, fault
+/// notifications and the periodic/final performance report. The receive
+/// loop emits through a boxed sink ([`StdoutSink`] by default), so a
+/// benchmark can plug a [`NullSink`] and a deployment a [`JsonLinesSink`]
+/// without touching the loop itself. Alarms, command traffic and the rest
+/// of the operational chatter still go straight to stdout.
+pub trait TelemetrySink {
+    fn on_packet(&mut self, packet: &PacketRecord);
+    fn on_fault(&mut self, fault: Fault, telemetry: &Telemetry);
+    fn on_report(&mut self, report: &str);
+}
+
+/// The historical behavior: human-readable lines on stdout.
+pub struct StdoutSink;
+
+impl TelemetrySink for StdoutSink {
+    fn on_packet(&mut self, packet: &PacketRecord) {
+        let t = packet.telemetry;
+        let mode_display = t.mode.map_or(String::new(), |m| format!(" mode={}", mode_label(m)));
+        println!(
+            "[GCS] seq={} t={}ms temp={}C batt={}mV ant={}deg{mode_display} health={:.1} ({}us)",
+            t.seq,
+            t.timestamp_ms,
+            t.temperature,
+            t.battery_mv,
+            packet.display_angle,
+            packet.health,
+            packet.decode_latency_us
+        );
+    }
+
+    fn on_fault(&mut self, fault: Fault, telemetry: &Telemetry) {
+        println!("[GCS-FAULT] {} at seq {}", fault.name(), telemetry.seq);
+    }
+
+    fn on_report(&mut self, report: &str) {
+        print!("{report}");
+    }
+}
+
+/// Swallows everything; for benchmarks and tests that only read metrics.
+pub struct NullSink;
+
+impl TelemetrySink for NullSink {
+    fn on_packet(&mut self, _packet: &PacketRecord) {}
+    fn on_fault(&mut self, _fault: Fault, _telemetry: &Telemetry) {}
+    fn on_report(&mut self, _report: &str) {}
+}
+
+/// Structured output: one JSON object per event on an arbitrary writer,
+/// discriminated by a `"type"` field. Packet field spelling matches the
+/// `.jsonl` capture log where the two overlap; write errors are swallowed,
+/// matching the stdout sink's indifference to a closed pipe.
+pub struct JsonLinesSink<W: io::Write> {
+    out: W,
+}
+
+impl<W: io::Write> JsonLinesSink<W> {
+    pub fn new(out: W) -> Self {
+        JsonLinesSink { out }
+    }
+}
+
+impl<W: io::Write> TelemetrySink for JsonLinesSink<W> {
+    fn on_packet(&mut self, packet: &PacketRecord) {
+        let t = packet.telemetry;
+        let mode = t.mode.map_or_else(|| "null".to_string(), |m| m.to_string());
+        let _ = writeln!(
+            self.out,
+            "{{\"type\":\"packet\",\"seq\":{},\"timestamp_ms\":{},\"temperature\":{},\"battery_mv\":{},\"antenna_angle\":{},\"mode\":{mode},\"health\":{:.1},\"decode_latency_us\":{}}}",
+            t.seq,
+            t.timestamp_ms,
+            t.temperature,
+            t.battery_mv,
+            packet.display_angle,
+            packet.health,
+            packet.decode_latency_us
+        );
+    }
+
+    fn on_fault(&mut self, fault: Fault, telemetry: &Telemetry) {
+        let _ = writeln!(
+            self.out,
+            "{{\"type\":\"fault\",\"fault\":\"{}\",\"seq\":{}}}",
+            fault.name(),
+            telemetry.seq
+        );
+    }
+
+    fn on_report(&mut self, report: &str) {
+        // The report is multi-line prose; ship it escaped rather than
+        // re-modeling every figure as a JSON field.
+        let escaped = report.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n");
+        let _ = writeln!(self.out, "{{\"type\":\"report\",\"text\":\"{escaped}\"}}");
+    }
+}
+
+/// Ground control station receive loop and link-state tracking.
+pub struct GCS {
+    socket: UdpSocket,
+    pub metrics: GCSPerformanceMetrics,
+    /// Central live-state ledger mirrored from every alarm's transitions.
+    pub alarms: AlarmRegistry,
+    limits: Limits,
+    health_weights: HealthWeights,
+    expected_interval_ms: u64,
+    decoders: DecoderRegistry,
+    /// Shared HMAC secret; when set, frames without a valid tag are rejected.
+    key: Option<Vec<u8>>,
+    auto_safe: Option<AutoSafe>,
+    /// Commanded mode byte awaiting confirmation from mode-echoing
+    /// telemetry, with the instant the command was sent.
+    pending_mode: Option<(u8, Instant)>,
+    /// Uplink command ids awaiting in-band confirmation via the TLV
+    /// command-echo field, with their send instants.
+    pending_commands: HashMap<u32, Instant>,
+    /// Last echoed id already handled, so an echo persisting across packets
+    /// confirms (or surprises) exactly once.
+    last_command_echo: u32,
+    /// Fault-response policy plus the uplink it commands through; both must
+    /// be present before any automatic mitigation fires.
+    mitigation: Option<Box<dyn MitigationPolicy>>,
+    mitigation_uplink: Option<CommandSender>,
+    last_seq: Option<u32>,
+    /// Whether any alarm was active at the last edge check, for the
+    /// distinct onset/clearance log lines.
+    any_alarm_active: bool,
+    /// Graceful-degradation ladder; `None` leaves every feature always on.
+    degradation: Option<Degradation>,
+    /// Clock-skew watchdog; `None` trusts the clock relationship blindly.
+    clock_skew: Option<ClockSkew>,
+    /// Receive-loop busy-fraction gauge; `None` leaves CPU health unjudged.
+    busy: Option<BusyMonitor>,
+    /// Structured-output destination; see [`TelemetrySink`].
+    sink: Box<dyn TelemetrySink>,
+    /// Peer the current datagram came from, labelling per-source statistics.
+    current_source: Option<std::net::SocketAddr>,
+    /// Source allowlist; when non-empty, telemetry from any other address is
+    /// dropped as untrusted before decoding.
+    allowed_sources: Vec<std::net::IpAddr>,
+    /// Unknown sources already warned about, so each logs exactly once.
+    warned_sources: HashSet<std::net::IpAddr>,
+    last_arrival: Option<Instant>,
+    contact_lost: bool,
+    /// Arrival instants within [`RATE_WINDOW`], for the sliding rate gauge.
+    arrivals: VecDeque<Instant>,
+    rate_anomaly_fraction: f64,
+    rate_below_since: Option<Instant>,
+    rate_anomaly_since: Option<Instant>,
+    warmup_remaining: u64,
+    /// Time-based warm-up deadline; enforcement also waits for this when set.
+    warmup_until: Option<Instant>,
+    start: Instant,
+    status_interval: Option<Duration>,
+    last_status: Instant,
+    rx_at_last_status: u64,
+    report_interval: Option<Duration>,
+    last_report: Instant,
+    status_stream: Option<StatusStream>,
+    /// TCP telemetry listener; when set, `run` serves framed TCP instead of
+    /// UDP datagrams.
+    tcp_listener: Option<std::net::TcpListener>,
+    /// Artificial decode delay for exercising the latency alarm in tests.
+    inject_decode_delay_us: Option<u64>,
+    /// Rotating capture log fed with every valid sample.
+    capture_log: Option<crate::logfile::TelemetryLog>,
+    /// Last arrival per source, for the capture log's inter-arrival column.
+    capture_last_arrival: HashMap<String, Instant>,
+    /// Rate-limited forensic log of rejected datagrams (reason, sender, raw
+    /// bytes), for post-hoc corruption diagnosis.
+    reject_log: Option<crate::logfile::RejectionLog>,
+    /// When set, capture and rejection records are handed to a background
+    /// writer thread instead of being written inline; see
+    /// [`GCS::set_async_logging`].
+    async_logger: Option<crate::logfile::AsyncLogger<LogRecord>>,
+    /// Timestamp of the latest valid sample: the OCS's mission elapsed time,
+    /// shown alongside wall clock in the status line.
+    last_timestamp_ms: Option<u64>,
+    /// Jitter beyond this band is flagged and counted (`None` disables).
+    jitter_tolerance_us: Option<i64>,
+    /// Operator control socket (`REPORT` etc.), polled between packets.
+    control: Option<UdpSocket>,
+    /// Edge streaks beyond this count raise the sustained-edge alarm
+    /// (`None` disables).
+    edge_streak_limit: Option<u64>,
+    edge_streak: u64,
+    sustained_edge_active: bool,
+    /// Faults active as of the last packet, for episode (transition) counting.
+    active_faults: HashSet<Fault>,
+    /// Two-stage alert state machines, one per monitored field direction.
+    field_alerts: Vec<FieldAlert>,
+    /// Identical consecutive readings beyond this raise the stuck-sensor
+    /// alarm (`None` disables).
+    stuck_limit: Option<u64>,
+    /// Flat-run state per telemetry field for the stuck-sensor check.
+    stuck_tracks: Vec<StuckTrack>,
+    /// Receive-side rate cap (`None` disables) and its shedding state:
+    /// the current one-second window, arrivals and admissions within it,
+    /// and the previous window's arrival count for the sampling stride.
+    rate_cap: Option<(u32, ShedPolicy)>,
+    cap_window_start: Option<Instant>,
+    cap_arrived: u64,
+    cap_admitted: u64,
+    cap_prev_arrived: u64,
+    /// Last boot counter seen per source, for v2 restart detection.
+    boot_by_source: HashMap<String, u8>,
+    /// Per-field rate-of-change limits (`None` disables the check).
+    roc_limits: Option<RateOfChangeLimits>,
+    /// Previous sample for the rate-of-change check; only a packet whose seq
+    /// directly follows it is compared, so reordering never false-positives.
+    roc_prev: Option<Telemetry>,
+    /// Wrap convention antenna angles are displayed in; the misalignment
+    /// math uses angular distance, which is convention-independent.
+    angle_convention: crate::angle::AngleConvention,
+    /// Downstream tap re-transmitting every valid datagram, when configured.
+    forwarder: Option<Forwarder>,
+    /// Command-link round-trip prober, when configured.
+    ping_probe: Option<PingProbe>,
+    /// Modal datagram length currently considered "the" wire format.
+    modal_frame_length: Option<usize>,
+    /// Known downlink off-window length; silence up to this is scheduled,
+    /// not a fault (`None` when the OCS is not duty-cycled).
+    scheduled_gap_ms: Option<u64>,
+}
+
+impl GCS {
+    /// Binds the telemetry port on all interfaces.
+    pub fn new(port: u16, expected_interval_ms: u64) -> crate::Result<Self> {
+        Self::bind(port, expected_interval_ms, false)
+    }
+
+    /// Full constructor: like [`GCS::new`] but optionally setting
+    /// `SO_REUSEADDR` before the bind.
+    pub fn bind(port: u16, expected_interval_ms: u64, reuse_addr: bool) -> crate::Result<Self> {
+        let socket =
+            crate::util::bind_udp("GCS telemetry", port, reuse_addr).map_err(crate::Error::Bind)?;
+        Self::from_socket(socket, expected_interval_ms)
+    }
+
+    /// Binds a specific local address instead of all interfaces — e.g.
+    /// `127.0.0.1:8080` to stay loopback-only for tests, or one interface's
+    /// address on a multi-homed host so instances can share a port number.
+    pub fn with_bind_addr(
+        addr: impl std::net::ToSocketAddrs + std::fmt::Display,
+        expected_interval_ms: u64,
+    ) -> crate::Result<Self> {
+        let socket = UdpSocket::bind(&addr).map_err(|e| {
+            crate::Error::Bind(io::Error::new(
+                e.kind(),
+                format!("GCS telemetry bind to {addr} failed: {e}"),
+            ))
+        })?;
+        Self::from_socket(socket, expected_interval_ms)
+    }
+
+    /// Shared construction over an already-bound telemetry socket.
+    fn from_socket(socket: UdpSocket, expected_interval_ms: u64) -> crate::Result<Self> {
+        socket.set_read_timeout(Some(Duration::from_millis(100)))?;
+        let limits = Limits::default();
+        let field_alerts = AlertField::ALL
+            .iter()
+            .map(|&field| FieldAlert {
+                field,
+                threshold: field.default_threshold(&limits),
+                tier: AlertTier::Normal,
+            })
+            .collect();
+        Ok(GCS {
+            socket,
+            metrics: GCSPerformanceMetrics::new(),
+            alarms: AlarmRegistry::new(),
+            limits,
+            health_weights: HealthWeights::default(),
+            expected_interval_ms,
+            decoders: DecoderRegistry::with_defaults(),
+            key: None,
+            auto_safe: None,
+            pending_mode: None,
+            pending_commands: HashMap::new(),
+            last_command_echo: 0,
+            mitigation: None,
+            mitigation_uplink: None,
+            last_seq: None,
+            any_alarm_active: false,
+            degradation: None,
+            clock_skew: None,
+            busy: None,
+            sink: Box::new(StdoutSink),
+            current_source: None,
+            allowed_sources: Vec::new(),
+            warned_sources: HashSet::new(),
+            last_arrival: None,
+            contact_lost: false,
+            arrivals: VecDeque::new(),
+            rate_anomaly_fraction: DEFAULT_RATE_ANOMALY_FRACTION,
+            rate_below_since: None,
+            rate_anomaly_since: None,
+            warmup_remaining: DEFAULT_WARMUP_PACKETS,
+            warmup_until: None,
+            start: Instant::now(),
+            status_interval: Some(Duration::from_secs(5)),
+            last_status: Instant::now(),
+            rx_at_last_status: 0,
+            report_interval: Some(Duration::from_secs(DEFAULT_REPORT_EVERY_SECS)),
+            last_report: Instant::now(),
+            status_stream: None,
+            tcp_listener: None,
+            inject_decode_delay_us: None,
+            capture_log: None,
+            capture_last_arrival: HashMap::new(),
+            reject_log: None,
+            async_logger: None,
+            last_timestamp_ms: None,
+            jitter_tolerance_us: Some((DEFAULT_JITTER_TOLERANCE_MS * 1000) as i64),
+            control: None,
+            edge_streak_limit: Some(DEFAULT_EDGE_STREAK_LIMIT),
+            edge_streak: 0,
+            sustained_edge_active: false,
+            active_faults: HashSet::new(),
+            field_alerts,
+            stuck_limit: Some(DEFAULT_STUCK_PACKET_LIMIT),
+            stuck_tracks: ["temperature", "battery", "antenna"]
+                .into_iter()
+                .map(StuckTrack::new)
+                .collect(),
+            rate_cap: None,
+            cap_window_start: None,
+            cap_arrived: 0,
+            cap_admitted: 0,
+            cap_prev_arrived: 0,
+            boot_by_source: HashMap::new(),
+            roc_limits: None,
+            roc_prev: None,
+            angle_convention: crate::angle::AngleConvention::Signed180,
+            forwarder: None,
+            ping_probe: None,
+            modal_frame_length: None,
+            scheduled_gap_ms: None,
+        })
+    }
+
+    /// TCP-transport constructor: listens for an OCS connection streaming
+    /// length-prefixed frames. Loss statistics are suppressed since the
+    /// transport is reliable.
+    pub fn bind_tcp(port: u16, expected_interval_ms: u64) -> crate::Result<Self> {
+        // The UDP socket is unused in this mode; bind it ephemeral.
+        let mut gcs = Self::bind(0, expected_interval_ms, false)?;
+        let listener = std::net::TcpListener::bind(("0.0.0.0", port)).map_err(|e| {
+            crate::Error::Bind(io::Error::new(
+                e.kind(),
+                format!("GCS tcp bind to 0.0.0.0:{port} failed: {e}"),
+            ))
+        })?;
+        listener.set_nonblocking(true)?;
+        gcs.tcp_listener = Some(listener);
+        gcs.metrics.suppress_loss_stats();
+        Ok(gcs)
+    }
+
+    /// Enables the critical-battery auto-safe response: below `floor_mv` the
+    /// GCS commands `SET_MODE safe` on the OCS command port, once per episode,
+    /// re-arming only after recovery above `clear_mv`.
+    pub fn enable_auto_safe(
+        &mut self,
+        ocs_command_addr: &str,
+        floor_mv: u16,
+        clear_mv: u16,
+    ) -> io::Result<()> {
+        let sender = CommandSender::new(ocs_command_addr)?;
+        self.auto_safe = Some(AutoSafe {
+            sender,
+            floor_mv,
+            clear_mv: clear_mv.max(floor_mv),
+            engaged: false,
+        });
+        Ok(())
+    }
+
+    /// Installs a fault-mitigation policy with an uplink to the OCS command
+    /// port. The policy is consulted once per fault episode; pass
+    /// [`NoOpPolicy`] for pure monitoring (also the default when no policy
+    /// is installed).
+    pub fn set_mitigation_policy(
+        &mut self,
+        ocs_command_addr: &str,
+        policy: Box<dyn MitigationPolicy>,
+    ) -> io::Result<()> {
+        self.mitigation_uplink = Some(CommandSender::new(ocs_command_addr)?);
+        self.mitigation = Some(policy);
+        Ok(())
+    }
+
+    /// Sets the shared secret for authenticated telemetry. Once set, every
+    /// frame must carry a valid [`crate::auth`] tag; frames without one are
+    /// counted as spoofed and dropped before decoding.
+    pub fn set_key(&mut self, key: Vec<u8>) {
+        self.key = Some(key);
+    }
+
+    /// Restricts telemetry to an allowlist of source hosts. Datagrams from
+    /// any other address are counted as untrusted and dropped before
+    /// decoding, with a warning logged on the first packet from each unknown
+    /// source. A hostname contributes every address it resolves to; an empty
+    /// list (the default) accepts all sources. Pairs with the HMAC check for
+    /// layered defense: the allowlist filters by address, the tag by key.
+    pub fn set_allowed_sources(&mut self, hosts: &[String]) -> io::Result<()> {
+        for host in hosts {
+            // `ToSocketAddrs` wants a port; only the address matters here.
+            let addrs = (host.as_str(), 0u16).to_socket_addrs().map_err(|e| {
+                io::Error::new(
+                    e.kind(),
+                    format!("allowed source '{host}' does not resolve: {e}"),
+                )
+            })?;
+            self.allowed_sources.extend(addrs.map(|addr| addr.ip()));
+        }
+        Ok(())
+    }
+
+    /// Registers a decoder for an additional wire-format version.
+    pub fn register_decoder(&mut self, version: u8, decoder: crate::telemetry::VersionedDecoder) {
+        self.decoders.register(version, decoder);
+    }
+
+    /// Sends one synthetic frame to its own socket over loopback and runs it
+    /// through the registered decoders, confirming size, version byte and
+    /// checksum agree before any real traffic arrives. A mismatch between the
+    /// compiled GCS and the wire format it expects fails here with a clear
+    /// message instead of as silent rejections later. The probe is consumed
+    /// directly and never reaches the metrics.
+    pub fn self_check(&self) -> io::Result<()> {
+        let expected = Telemetry {
+            seq: 0,
+            timestamp_ms: 0,
+            temperature: 21,
+            battery_mv: 12_000,
+            antenna_angle: 0,
+            boot_id: 0,
+            mode: None,
+            command_echo: None,
+        };
+        let port = self.socket.local_addr()?.port();
+        let probe = UdpSocket::bind(("127.0.0.1", 0))?;
+        probe.send_to(&expected.to_bytes(), ("127.0.0.1", port))?;
+
+        let saved_timeout = self.socket.read_timeout()?;
+        self.socket.set_read_timeout(Some(Duration::from_secs(1)))?;
+        let mut buf = [0u8; 64];
+        let received = self.socket.recv_from(&mut buf);
+        self.socket.set_read_timeout(saved_timeout)?;
+        let (len, _) = received
+            .map_err(|e| io::Error::new(e.kind(), format!("loopback receive failed: {e}")))?;
+
+        let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidData, msg);
+        if len != TELEMETRY_WIRE_SIZE {
+            return Err(invalid(format!(
+                "loopback frame was {len} bytes, expected {TELEMETRY_WIRE_SIZE}"
+            )));
+        }
+        let decoded = self
+            .decoders
+            .decode(&buf[..len])
+            .map_err(|e| invalid(format!("loopback frame failed to decode: {e:?}")))?;
+        if decoded != expected {
+            return Err(invalid(
+                "loopback frame decoded to different field values".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Subscribes the telemetry socket to a multicast group so this GCS can
+    /// receive a one-to-many downlink alongside other ground stations.
+    pub fn join_multicast(&self, group: std::net::Ipv4Addr) -> io::Result<()> {
+        if !group.is_multicast() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{group} is not a multicast group"),
+            ));
+        }
+        self.socket
+            .join_multicast_v4(&group, &std::net::Ipv4Addr::UNSPECIFIED)
+    }
+
+    /// Tees every valid telemetry datagram to `addr`, unchanged, so this GCS
+    /// can sit in the middle of a relay chain as a monitoring tap. Sending
+    /// happens on a worker thread behind a bounded queue; see [`Forwarder`].
+    pub fn set_forward(&mut self, addr: &str) -> io::Result<()> {
+        let socket = UdpSocket::bind(("0.0.0.0", 0))?;
+        socket.connect(addr).map_err(|e| {
+            io::Error::new(e.kind(), format!("forward target {addr} unusable: {e}"))
+        })?;
+        let (queue, rx) = mpsc::sync_channel::<Vec<u8>>(FORWARD_QUEUE_CAPACITY);
+        let forwarded = Arc::new(AtomicU64::new(0));
+        let errors = Arc::new(AtomicU64::new(0));
+        let worker_forwarded = Arc::clone(&forwarded);
+        let worker_errors = Arc::clone(&errors);
+        std::thread::spawn(move || {
+            for frame in rx {
+                match socket.send(&frame) {
+                    Ok(_) => {
+                        worker_forwarded.fetch_add(1, Ordering::SeqCst);
+                    }
+                    Err(e) => {
+                        // Log the first failure; the counter covers the rest.
+                        if worker_errors.fetch_add(1, Ordering::SeqCst) == 0 {
+                            eprintln!("[GCS] forward send failed: {e}");
+                        }
+                    }
+                }
+            }
+        });
+        self.forwarder = Some(Forwarder {
+            queue,
+            forwarded,
+            errors,
+            queue_drops: 0,
+        });
+        Ok(())
+    }
+
+    /// Queues one valid frame toward the forwarding worker, shedding (and
+    /// counting) it if the worker has fallen behind.
+    fn forward_frame(&mut self, data: &[u8]) {
+        let Some(forwarder) = &mut self.forwarder else {
+            return;
+        };
+        match forwarder.queue.try_send(data.to_vec()) {
+            Ok(()) => {}
+            Err(mpsc::TrySendError::Full(_)) => forwarder.queue_drops += 1,
+            Err(mpsc::TrySendError::Disconnected(_)) => {}
+        }
+    }
+
+    /// Copies the forwarding worker's totals into the metrics, so a report
+    /// built next reflects them. Cheap and idempotent.
+    fn sync_forward_stats(&mut self) {
+        if let Some(forwarder) = &self.forwarder {
+            self.metrics.set_forward_stats(
+                forwarder.forwarded.load(Ordering::SeqCst),
+                forwarder.errors.load(Ordering::SeqCst),
+                forwarder.queue_drops,
+            );
+        }
+    }
+
+    /// Starts the periodic command-link round-trip probe toward the OCS
+    /// command port; see [`PingProbe`]. `interval_ms` is both the probe
+    /// cadence and the reply deadline, so a lost probe costs one interval
+    /// and is counted as failed.
+    pub fn enable_ping(&mut self, ocs_command_addr: &str, interval_ms: u64) -> io::Result<()> {
+        use std::net::ToSocketAddrs;
+        let socket = UdpSocket::bind("0.0.0.0:0")?;
+        let target = ocs_command_addr
+            .to_socket_addrs()
+            .map_err(|e| {
+                io::Error::new(e.kind(), format!("ping target {ocs_command_addr} unusable: {e}"))
+            })?
+            .next()
+            .ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidInput, "unresolvable ping target")
+            })?;
+        let interval = Duration::from_millis(interval_ms.max(1));
+        let rtts_ms = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let failures = Arc::new(AtomicU64::new(0));
+        let worker_rtts = Arc::clone(&rtts_ms);
+        let worker_failures = Arc::clone(&failures);
+        std::thread::spawn(move || {
+            let mut buf = [0u8; 128];
+            let mut seq: u64 = 0;
+            loop {
+                seq += 1;
+                let wire = format!("PING {seq}");
+                let sent = Instant::now();
+                if let Err(e) = socket.send_to(wire.as_bytes(), target) {
+                    eprintln!("[GCS-PING] send failed: {e}");
+                    worker_failures.fetch_add(1, Ordering::SeqCst);
+                    std::thread::sleep(interval);
+                    continue;
+                }
+                // Wait out the interval for the matching echo; stale echoes
+                // from an earlier timed-out probe are skipped, not matched.
+                let expected = format!("ACK PONG {seq}");
+                let deadline = sent + interval;
+                let mut answered = false;
+                loop {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    if remaining.is_zero() {
+                        break;
+                    }
+                    if socket.set_read_timeout(Some(remaining)).is_err() {
+                        break;
+                    }
+                    match socket.recv_from(&mut buf) {
+                        Ok((len, _)) => {
+                            if String::from_utf8_lossy(&buf[..len]).trim() == expected {
+                                answered = true;
+                                break;
+                            }
+                        }
+                        Err(e)
+                            if e.kind() == io::ErrorKind::WouldBlock
+                                || e.kind() == io::ErrorKind::TimedOut =>
+                        {
+                            break;
+                        }
+                        Err(e) => {
+                            eprintln!("[GCS-PING] recv failed: {e}");
+                            break;
+                        }
+                    }
+                }
+                if answered {
+                    worker_rtts.lock().unwrap().push(sent.elapsed().as_secs_f64() * 1000.0);
+                } else {
+                    worker_failures.fetch_add(1, Ordering::SeqCst);
+                    println!(
+                        "[GCS-PING] probe {seq} lost (no echo within {} ms)",
+                        interval.as_millis()
+                    );
+                }
+                std::thread::sleep(deadline.saturating_duration_since(Instant::now()));
+            }
+        });
+        self.ping_probe = Some(PingProbe { rtts_ms, failures });
+        Ok(())
+    }
+
+    /// Copies the probe worker's round-trip samples into the metrics,
+    /// mirroring [`GCS::sync_forward_stats`].
+    fn sync_ping_stats(&mut self) {
+        if let Some(probe) = &self.ping_probe {
+            self.metrics.set_ping_stats(
+                probe.rtts_ms.lock().unwrap().clone(),
+                probe.failures.load(Ordering::SeqCst),
+            );
+        }
+    }
+
+    /// Replaces the two-stage alert thresholds for one monitored field. The
+    /// ordering must be consistent with the field's direction: for a rising
+    /// field `clear <= warn <= alarm`, mirrored for a falling one. The
+    /// field's alert state resets so the new thresholds judge from scratch.
+    pub fn set_alert_threshold(
+        &mut self,
+        field: AlertField,
+        threshold: FieldThreshold,
+    ) -> io::Result<()> {
+        if !threshold.ordered_for(field) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "{} thresholds warn={} alarm={} clear={} out of order",
+                    field.name(),
+                    threshold.warn,
+                    threshold.alarm,
+                    threshold.clear
+                ),
+            ));
+        }
+        let alert = self
+            .field_alerts
+            .iter_mut()
+            .find(|a| a.field == field)
+            .expect("every alert field has a monitor");
+        alert.threshold = threshold;
+        alert.tier = AlertTier::Normal;
+        Ok(())
+    }
+
+    /// Enables the per-field rate-of-change check with the given per-packet
+    /// limits; all three must be positive.
+    pub fn set_rate_of_change_limits(&mut self, limits: RateOfChangeLimits) -> io::Result<()> {
+        if limits.temperature <= 0.0 || limits.battery <= 0.0 || limits.antenna <= 0.0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "rate-of-change limits {}:{}:{} must all be positive",
+                    limits.temperature, limits.battery, limits.antenna
+                ),
+            ));
+        }
+        self.roc_limits = Some(limits);
+        Ok(())
+    }
+
+    /// Arms the graceful-degradation ladder with a per-packet processing
+    /// budget. When processing overruns the budget for
+    /// [`DEGRADE_AFTER_OVERRUNS`] consecutive packets, one level of optional
+    /// work is shed — first the anomaly analysis, then per-packet logging —
+    /// keeping decode, integrity and critical-fault detection under load.
+    /// Levels are restored one at a time after [`DEGRADE_RESTORE_AFTER`]
+    /// consecutive packets under half the budget. `0` disables the ladder.
+    pub fn set_degradation_budget(&mut self, budget_us: u64) {
+        self.degradation = (budget_us > 0).then(|| Degradation::new(budget_us));
+    }
+
+    /// Arms the clock-skew watchdog: the offset between the OCS mission
+    /// clock and local time is estimated from every packet, and deviating
+    /// more than `max_offset_ms` from the post-warm-up baseline — or moving
+    /// faster than `max_drift_ms_per_s` — raises `[GCS-CLOCK-SKEW]`. While
+    /// the alarm is active the status line and report mark the timestamp
+    /// metrics as uncalibrated. A detected OCS restart re-baselines rather
+    /// than alarming, since the mission clock legitimately restarts with
+    /// it. `0` disables the watchdog.
+    pub fn set_clock_skew_bound(&mut self, max_offset_ms: u64, max_drift_ms_per_s: f64) {
+        self.clock_skew = (max_offset_ms > 0).then_some(ClockSkew {
+            max_offset_ms: max_offset_ms as i64,
+            max_drift_ms_per_s,
+            baseline_ms: None,
+            anchor: None,
+            last_drift_ms_per_s: 0.0,
+            alarm_active: false,
+        });
+    }
+
+    /// Arms the receive-loop busy-fraction gauge: when processing (rather
+    /// than blocking in recv) takes more than `budget_pct` percent of wall
+    /// time over a sliding [`BUSY_WINDOW`], `[GCS-BUSY]` is raised — a
+    /// saturation warning that fires before packets start dropping. Only the
+    /// UDP receive loop is instrumented. `0` disables the gauge.
+    pub fn set_busy_budget(&mut self, budget_pct: u64) {
+        self.busy = (budget_pct > 0).then_some(BusyMonitor {
+            budget: budget_pct as f64 / 100.0,
+            spans: VecDeque::new(),
+            busy_sum: Duration::ZERO,
+            total_sum: Duration::ZERO,
+            alarm_active: false,
+        });
+    }
+
+    /// Feeds one loop iteration to the busy gauge: `blocked` is the time
+    /// spent waiting inside recv, `total` the whole iteration. Judgement
+    /// waits for half a window of history, so the first packets after start
+    /// (or after a long silence) cannot alarm on a sliver of data.
+    fn track_busy(&mut self, blocked: Duration, total: Duration) {
+        let Some(b) = &mut self.busy else {
+            return;
+        };
+        let now = Instant::now();
+        let busy = total.saturating_sub(blocked);
+        b.spans.push_back((now, busy, total));
+        b.busy_sum += busy;
+        b.total_sum += total;
+        while let Some(&(ended, span_busy, span_total)) = b.spans.front() {
+            if now.duration_since(ended) > BUSY_WINDOW {
+                b.spans.pop_front();
+                b.busy_sum = b.busy_sum.saturating_sub(span_busy);
+                b.total_sum = b.total_sum.saturating_sub(span_total);
+            } else {
+                break;
+            }
+        }
+        if b.total_sum < BUSY_WINDOW / 2 {
+            return;
+        }
+        let fraction = b.fraction();
+        self.metrics.note_busy_fraction(fraction);
+        if fraction > b.budget && !b.alarm_active {
+            b.alarm_active = true;
+            self.alarms.raise("busy");
+            self.metrics.record_busy_alarm();
+            let line = format!(
+                "[GCS-BUSY] receive loop {:.0}% busy over the last {} s (budget {:.0}%)",
+                fraction * 100.0,
+                BUSY_WINDOW.as_secs(),
+                b.budget * 100.0
+            );
+            println!("{line}");
+            self.publish_event(&line);
+        } else if fraction <= b.budget && b.alarm_active {
+            b.alarm_active = false;
+            self.alarms.clear("busy");
+            let line = format!(
+                "[GCS-BUSY] cleared: receive loop back to {:.0}% busy",
+                fraction * 100.0
+            );
+            println!("{line}");
+            self.publish_event(&line);
+        }
+    }
+
+    /// Replaces the structured-output destination; see [`TelemetrySink`].
+    /// The default [`StdoutSink`] reproduces the historical line format.
+    pub fn set_sink(&mut self, sink: Box<dyn TelemetrySink>) {
+        self.sink = sink;
+    }
+
+    /// Current degradation ladder level (0 = full service).
+    fn degradation_level(&self) -> usize {
+        self.degradation.as_ref().map_or(0, |d| d.level)
+    }
+
+    /// Feeds one packet's processing time to the ladder, stepping down on a
+    /// sustained overrun and back up on a sustained clear margin.
+    fn track_degradation(&mut self, spent: Duration) {
+        let Some(d) = &mut self.degradation else {
+            return;
+        };
+        let spent_us = spent.as_micros() as u64;
+        if spent_us > d.budget_us {
+            d.over_streak += 1;
+            d.under_streak = 0;
+        } else if spent_us * 2 <= d.budget_us {
+            d.under_streak += 1;
+            d.over_streak = 0;
+        } else {
+            // In the hysteresis band: neither degrading nor recovering.
+            d.over_streak = 0;
+            d.under_streak = 0;
+        }
+        if d.over_streak >= DEGRADE_AFTER_OVERRUNS && d.level + 1 < DEGRADE_LEVEL_NAMES.len() {
+            d.step_to(d.level + 1);
+            let line = format!(
+                "[GCS-DEGRADE] falling behind ({spent_us} us/packet against a {} us budget); \
+                 down to {}",
+                d.budget_us, DEGRADE_LEVEL_NAMES[d.level]
+            );
+            println!("{line}");
+            self.publish_event(&line);
+        } else if d.under_streak >= DEGRADE_RESTORE_AFTER && d.level > 0 {
+            d.step_to(d.level - 1);
+            let line = format!(
+                "[GCS-DEGRADE] load recovered; back up to {}",
+                DEGRADE_LEVEL_NAMES[d.level]
+            );
+            println!("{line}");
+            self.publish_event(&line);
+        }
+    }
+
+    /// Prints the time spent at each ladder level; nothing when the ladder
+    /// is disarmed.
+    fn report_degradation(&self) {
+        let Some(d) = &self.degradation else {
+            return;
+        };
+        println!("Degradation ladder:");
+        for (level, name) in DEGRADE_LEVEL_NAMES.iter().enumerate() {
+            let mut at = d.time_at[level];
+            if level == d.level {
+                at += d.entered_at.elapsed();
+            }
+            println!("  {name:<18} {:.1} s", at.as_secs_f64());
+        }
+    }
+
+    /// Caps how many datagrams per second are fully processed; the excess is
+    /// shed (counted, never decoded) according to `policy`, protecting the
+    /// decode latency budget under a flood. Shed datagrams surface as
+    /// sequence gaps, since their contents are never examined.
+    pub fn set_rate_cap(&mut self, max_per_sec: u32, policy: ShedPolicy) -> io::Result<()> {
+        if max_per_sec == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "rate cap must be positive",
+            ));
+        }
+        self.rate_cap = Some((max_per_sec, policy));
+        Ok(())
+    }
+
+    /// Decides whether one arriving datagram is processed or shed under the
+    /// rate cap. Counters reset each second; the sampling stride comes from
+    /// the previous second's arrival count so the budget spreads across the
+    /// window instead of burning in its first instants.
+    fn admit_datagram(&mut self, arrival: Instant) -> bool {
+        let Some((cap, policy)) = self.rate_cap else {
+            return true;
+        };
+        let window_start = *self.cap_window_start.get_or_insert(arrival);
+        if arrival.duration_since(window_start) >= Duration::from_secs(1) {
+            self.cap_prev_arrived = self.cap_arrived;
+            self.cap_arrived = 0;
+            self.cap_admitted = 0;
+            self.cap_window_start = Some(arrival);
+        }
+        self.cap_arrived += 1;
+        if self.cap_admitted >= cap as u64 {
+            return false;
+        }
+        let admit = match policy {
+            ShedPolicy::TailDrop => true,
+            ShedPolicy::Sample => {
+                let stride = (self.cap_prev_arrived / cap as u64).max(1);
+                (self.cap_arrived - 1).is_multiple_of(stride)
+            }
+        };
+        if admit {
+            self.cap_admitted += 1;
+        }
+        admit
+    }
+
+    /// Flags fields whose change since the directly preceding sample exceeds
+    /// the configured per-packet limit. Only a packet whose seq follows the
+    /// stored predecessor's is compared — across a gap or a reordering the
+    /// "previous" sample is not the wire predecessor, so the check skips.
+    fn track_rate_of_change(&mut self, t: &Telemetry) {
+        let Some(limits) = self.roc_limits else {
+            return;
+        };
+        if let Some(prev) = self.roc_prev {
+            if t.seq == prev.seq.wrapping_add(1) {
+                let jumps = [
+                    (
+                        "temperature",
+                        (t.temperature as f64 - prev.temperature as f64).abs(),
+                        limits.temperature,
+                    ),
+                    (
+                        "battery",
+                        (t.battery_mv as f64 - prev.battery_mv as f64).abs(),
+                        limits.battery,
+                    ),
+                    (
+                        // Angular distance, so a wrap is not a spike.
+                        "antenna",
+                        crate::angle::angular_distance(
+                            t.antenna_angle as f64,
+                            prev.antenna_angle as f64,
+                        ),
+                        limits.antenna,
+                    ),
+                ];
+                for (field, jump, limit) in jumps {
+                    if jump > limit {
+                        self.metrics.record_rate_spike(field);
+                        self.alarms.pulse("rate-spike");
+                        println!(
+                            "[GCS-RATE-SPIKE] {field} jumped {jump:.0} in one packet \
+                             (limit {limit}/packet, seq {})",
+                            t.seq
+                        );
+                    }
+                }
+            }
+        }
+        self.roc_prev = Some(*t);
+    }
+
+    /// Feeds one sample through the per-field alert state machines, logging
+    /// and counting tier transitions. Independent of the fault model: a field
+    /// can warn well before it would ever classify as a fault.
+    fn track_alerts(&mut self, t: &Telemetry) {
+        let mut events = Vec::new();
+        for alert in &mut self.field_alerts {
+            let value = alert.field.sample(t);
+            if let Some(outcome) = alert.observe(value) {
+                events.push((alert.field.name(), alert.threshold, value, outcome));
+            }
+        }
+        for (name, threshold, value, outcome) in events {
+            let line = match outcome {
+                AlertOutcome::Warned => {
+                    self.metrics.record_warn_episode(name);
+                    format!("[GCS-WARN] {name}: {value} past warn threshold {}", threshold.warn)
+                }
+                AlertOutcome::Alarmed => {
+                    self.metrics.record_alarm_episode(name);
+                    self.alarms.raise("field-alert");
+                    format!(
+                        "[GCS-ALARM] {name}: {value} past alarm threshold {}",
+                        threshold.alarm
+                    )
+                }
+                AlertOutcome::Cleared => {
+                    if !self.field_alerts.iter().any(|a| a.tier == AlertTier::Alarm) {
+                        self.alarms.clear("field-alert");
+                    }
+                    format!(
+                        "[GCS-ALERT] {name} cleared: {value} back past {}",
+                        threshold.clear
+                    )
+                }
+            };
+            println!("{line}");
+            self.publish_event(&line);
+        }
+    }
+
+    /// Sets the wrap convention antenna angles are displayed in.
+    pub fn set_angle_convention(&mut self, convention: crate::angle::AngleConvention) {
+        self.angle_convention = convention;
+    }
+
+    /// Sets the per-field weights of the composite health score. Negative
+    /// weights are treated as zero; all-zero weights score every packet 0.
+    pub fn set_health_weights(&mut self, weights: HealthWeights) {
+        self.health_weights = weights;
+    }
+
+    /// Sets the jitter tolerance band in milliseconds (`0` disables the
+    /// alarm). Excursions are only flagged after warm-up, so startup timing
+    /// noise does not alarm spuriously.
+    pub fn set_jitter_tolerance(&mut self, ms: u64) {
+        self.jitter_tolerance_us = if ms == 0 { None } else { Some((ms * 1000) as i64) };
+    }
+
+    /// Tells the GCS the OCS downlink is duty-cycled with `off_ms` of
+    /// scheduled silence per pass, so expected gaps neither declare loss of
+    /// contact nor judge the receive rate. Uninformed, a long gap is flagged
+    /// as usual.
+    pub fn set_duty_cycle(&mut self, off_ms: u64) {
+        self.scheduled_gap_ms = if off_ms == 0 { None } else { Some(off_ms) };
+    }
+
+    /// Silence threshold for declaring loss of contact: the base timeout,
+    /// extended by any scheduled off-window.
+    fn contact_timeout_ms(&self) -> u64 {
+        LOSS_OF_CONTACT_TIMEOUT_MS + self.scheduled_gap_ms.unwrap_or(0)
+    }
+
+    /// Sets how many consecutive edge-case packets are tolerated before
+    /// `[GCS-SUSTAINED-EDGE]` is raised (`0` disables the alarm). A single
+    /// edge case may be sensor noise; a streak suggests a developing fault.
+    pub fn set_edge_streak_limit(&mut self, count: u64) {
+        self.edge_streak_limit = if count == 0 { None } else { Some(count) };
+    }
+
+    /// Sets how many identical consecutive readings of one field are
+    /// tolerated before `[GCS-STUCK]` is raised (`0` disables the check).
+    /// Note a safe-moded OCS deliberately pins its fields, which a tight
+    /// limit will flag.
+    pub fn set_stuck_limit(&mut self, packets: u64) {
+        self.stuck_limit = if packets == 0 { None } else { Some(packets) };
+    }
+
+    /// Attaches a rotating capture log; every valid sample is appended.
+    pub fn set_capture_log(&mut self, log: crate::logfile::TelemetryLog) {
+        self.capture_log = Some(log);
+    }
+
+    /// Attaches a rejection log; every dropped datagram is appended with its
+    /// rejection reason, sender and raw bytes, subject to the log's rate cap.
+    pub fn set_reject_log(&mut self, log: crate::logfile::RejectionLog) {
+        self.reject_log = Some(log);
+    }
+
+    /// Moves the attached capture and rejection logs onto a background
+    /// writer thread behind a bounded queue of `capacity` records, so disk
+    /// latency never stalls the receive loop. Call after attaching the logs;
+    /// when the writer falls behind, records are shed and counted rather
+    /// than blocked on. The queue is drained at the end of the run, before
+    /// the final report.
+    pub fn set_async_logging(&mut self, capacity: usize) {
+        let mut capture = self.capture_log.take();
+        let mut reject = self.reject_log.take();
+        self.async_logger = Some(crate::logfile::AsyncLogger::spawn(
+            capacity,
+            move |record| match record {
+                LogRecord::Capture {
+                    telemetry,
+                    health,
+                    arrival_delta_us,
+                } => {
+                    if let Some(log) = &mut capture {
+                        if let Err(e) = log.log_with_arrival(&telemetry, health, arrival_delta_us)
+                        {
+                            eprintln!("[GCS] capture log write failed: {e}");
+                        }
+                    }
+                }
+                LogRecord::Rejection {
+                    reason,
+                    source,
+                    data,
+                } => {
+                    if let Some(log) = &mut reject {
+                        if let Err(e) = log.log(reason, &source, &data) {
+                            eprintln!("[GCS] rejection log write failed: {e}");
+                        }
+                    }
+                }
+            },
+        ));
+    }
+
+    /// Copies the async logger's drop count into the metrics, mirroring
+    /// [`GCS::sync_forward_stats`].
+    fn sync_log_stats(&mut self) {
+        if let Some(logger) = &self.async_logger {
+            self.metrics.set_log_queue_drops(logger.dropped());
+        }
+    }
+
+    /// Adds an artificial delay inside the measured decode path so the
+    /// 3 ms latency constraint, its `[LATENCY VIOLATION]` log, and the
+    /// violation counter can be exercised deterministically. Debug aid only;
+    /// `0` disables it.
+    pub fn set_inject_decode_delay(&mut self, delay_us: u64) {
+        self.inject_decode_delay_us = if delay_us == 0 { None } else { Some(delay_us) };
+    }
+
+    /// Sets the fraction of the expected rate below which a sustained
+    /// shortfall raises `[GCS-RATE-ANOMALY]`.
+    pub fn set_rate_anomaly_fraction(&mut self, fraction: f64) {
+        self.rate_anomaly_fraction = fraction.clamp(0.0, 1.0);
+    }
+
+    /// Sets the packet-count warm-up window (`0` disables it): until it is
+    /// spent the GCS establishes its sequence and rate baselines but counts
+    /// no loss, jitter excursions, rate anomalies or decode latencies, so a
+    /// start mid-stream doesn't alarm on history it never saw.
+    pub fn set_warmup(&mut self, packets: u64) {
+        self.warmup_remaining = packets;
+    }
+
+    /// Sets a time-based warm-up window (`0` disables it), measured from
+    /// GCS start. Combines with the packet-count window: enforcement begins
+    /// only once both are over.
+    pub fn set_warmup_secs(&mut self, secs: u64) {
+        self.warmup_until = (secs > 0).then(|| self.start + Duration::from_secs(secs));
+    }
+
+    /// Whether either warm-up window is still open.
+    fn in_warmup(&self) -> bool {
+        self.warmup_remaining > 0
+            || self.warmup_until.is_some_and(|until| Instant::now() < until)
+    }
+
+    /// Opens the operator control port. Control is demultiplexed from
+    /// telemetry by port, mirroring the OCS command port: telemetry stays
+    /// binary on the telemetry socket while control commands arrive as ASCII
+    /// lines here. `REPORT` forces an immediate full report, which is also
+    /// returned to the requester; `SET_THRESHOLD <name> <value>` adjusts one
+    /// whitelisted mission limit live (see [`Limits::set_threshold`]).
+    pub fn bind_control(&mut self, port: u16) -> io::Result<()> {
+        let control = crate::util::bind_udp("GCS control", port, false)?;
+        control.set_nonblocking(true)?;
+        self.control = Some(control);
+        Ok(())
+    }
+
+    /// Drains pending control commands; runs between packets so a request
+    /// never waits longer than one socket timeout (~100 ms).
+    fn poll_control(&mut self) {
+        if self.control.is_none() {
+            return;
+        }
+        // Sync up front: the control borrow below pins `self` for the loop,
+        // and a REPORT should reflect the forwarding counters.
+        self.sync_forward_stats();
+        self.sync_log_stats();
+        self.sync_ping_stats();
+        let Some(control) = &self.control else {
+            return;
+        };
+        let mut buf = [0u8; 128];
+        loop {
+            let (len, from) = match control.recv_from(&mut buf) {
+                Ok(r) => r,
+                Err(e)
+                    if e.kind() == io::ErrorKind::WouldBlock
+                        || e.kind() == io::ErrorKind::TimedOut =>
+                {
+                    return;
+                }
+                Err(e) => {
+                    eprintln!("[GCS-CTL] recv error: {e}");
+                    return;
+                }
+            };
+            let line = String::from_utf8_lossy(&buf[..len]).trim().to_string();
+            let reply = match line.as_str() {
+                "REPORT" => {
+                    println!("[GCS-CTL] report requested by {from}");
+                    let report = self.metrics.report_text();
+                    print!("{report}");
+                    format!("ACK REPORT\n{report}")
+                }
+                line if line.starts_with("SET_THRESHOLD") => {
+                    let mut parts = line.split_whitespace().skip(1);
+                    match (parts.next(), parts.next(), parts.next()) {
+                        (Some(name), Some(value), None) => {
+                            match self.limits.set_threshold(name, value) {
+                                Ok(()) => {
+                                    println!(
+                                        "[GCS-CTL] threshold {name} set to {value} by {from}"
+                                    );
+                                    format!("ACK SET_THRESHOLD {name} {value}")
+                                }
+                                Err(reason) => format!("NAK SET_THRESHOLD {reason}"),
+                            }
+                        }
+                        _ => "NAK SET_THRESHOLD usage: SET_THRESHOLD <name> <value>".to_string(),
+                    }
+                }
+                other => format!("NAK unknown control command {other}"),
+            };
+            if let Err(e) = control.send_to(reply.as_bytes(), from) {
+                eprintln!("[GCS-CTL] reply send error: {e}");
+            }
+        }
+    }
+
+    /// Streams status lines and fault events to a Unix domain socket a
+    /// supervising process can connect to, instead of it parsing stdout.
+    pub fn set_status_socket(&mut self, path: &str) -> io::Result<()> {
+        self.status_stream = Some(StatusStream::bind(path)?);
+        Ok(())
+    }
+
+    /// Forwards an event line to connected status-stream consumers, if any.
+    fn publish_event(&mut self, line: &str) {
+        if let Some(stream) = &mut self.status_stream {
+            stream.publish(line);
+        }
+    }
+
+    /// Sets the period of the one-line status heartbeat (`0` disables it).
+    pub fn set_status_interval(&mut self, secs: u64) {
+        self.status_interval = if secs == 0 {
+            None
+        } else {
+            Some(Duration::from_secs(secs))
+        };
+    }
+
+    /// Sets the period of the full in-flight performance report (`0` leaves
+    /// only the final report at shutdown). Wall-clock driven, so the cadence
+    /// is predictable whether the link is a trickle or a flood.
+    pub fn set_report_interval(&mut self, secs: u64) {
+        self.report_interval = if secs == 0 {
+            None
+        } else {
+            Some(Duration::from_secs(secs))
+        };
+    }
+
+    /// Runs the receive loop until `shutdown` is set, then prints the final
+    /// report. The socket read timeout doubles as a maintenance tick so
+    /// loss-of-contact is noticed even while no packets arrive.
+    pub fn run(&mut self, shutdown: &AtomicBool) {
+        if self.tcp_listener.is_some() {
+            self.run_tcp(shutdown);
+            return;
+        }
+        let mut buf = [0u8; MAX_DATAGRAM];
+        println!(
+            "[GCS] listening on {}",
+            self.socket.local_addr().map_or_else(|_| "?".into(), |a| a.to_string())
+        );
+
+        while !shutdown.load(Ordering::SeqCst) {
+            let iteration_start = Instant::now();
+            let received = self.socket.recv_from(&mut buf);
+            // Everything from here to the end of the iteration counts as
+            // busy time; only the blocking recv above counts as waiting.
+            let blocked = iteration_start.elapsed();
+            match received {
+                Ok((len, from)) => {
+                    let arrival = Instant::now();
+                    self.current_source = Some(from);
+                    self.handle_datagram(&buf[..len], arrival);
+                }
+                Err(e)
+                    if e.kind() == io::ErrorKind::WouldBlock
+                        || e.kind() == io::ErrorKind::TimedOut =>
+                {
+                    self.maintenance_tick();
+                }
+                Err(e) => {
+                    eprintln!("[GCS] recv error: {e}");
+                }
+            }
+            self.poll_control();
+            self.maybe_emit_status();
+            self.maybe_emit_report();
+            self.track_busy(blocked, iteration_start.elapsed());
+        }
+
+        self.sync_forward_stats();
+        self.sync_log_stats();
+        self.sync_ping_stats();
+        // Joining the writer here drains the queue, so the capture and
+        // rejection files are complete before the final report prints.
+        self.async_logger = None;
+        let report = self.metrics.report_text();
+        self.sink.on_report(&report);
+        self.alarms.report(self.start);
+        self.report_degradation();
+    }
+
+    /// TCP receive loop: accepts one OCS connection at a time and feeds each
+    /// framed telemetry message through the same path as a UDP datagram. A
+    /// dropped connection returns to accepting, so the OCS can reconnect.
+    fn run_tcp(&mut self, shutdown: &AtomicBool) {
+        use std::io::Read;
+
+        let listener = self.tcp_listener.take().expect("tcp listener configured");
+        println!(
+            "[GCS] tcp listening on {}",
+            listener.local_addr().map_or_else(|_| "?".into(), |a| a.to_string())
+        );
+
+        while !shutdown.load(Ordering::SeqCst) {
+            let (mut conn, peer) = match listener.accept() {
+                Ok(c) => c,
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    std::thread::sleep(Duration::from_millis(100));
+                    self.maintenance_tick();
+                    self.poll_control();
+                    self.maybe_emit_status();
+                    self.maybe_emit_report();
+                    continue;
+                }
+                Err(e) => {
+                    eprintln!("[GCS] tcp accept error: {e}");
+                    continue;
+                }
+            };
+            println!("[GCS] tcp connection from {peer}");
+            self.current_source = Some(peer);
+            let _ = conn.set_read_timeout(Some(Duration::from_millis(100)));
+
+            let mut pending = Vec::new();
+            let mut chunk = [0u8; 512];
+            'conn: while !shutdown.load(Ordering::SeqCst) {
+                match conn.read(&mut chunk) {
+                    Ok(0) => {
+                        println!("[GCS] tcp connection from {peer} closed");
+                        break 'conn;
+                    }
+                    Ok(n) => {
+                        let arrival = Instant::now();
+                        pending.extend_from_slice(&chunk[..n]);
+                        for frame in crate::transport::drain_frames(&mut pending) {
+                            self.handle_datagram(&frame, arrival);
+                        }
+                    }
+                    Err(e)
+                        if e.kind() == io::ErrorKind::WouldBlock
+                            || e.kind() == io::ErrorKind::TimedOut =>
+                    {
+                        self.maintenance_tick();
+                    }
+                    Err(e) => {
+                        eprintln!("[GCS] tcp read error from {peer}: {e}");
+                        break 'conn;
+                    }
+                }
+                self.poll_control();
+                self.maybe_emit_status();
+                self.maybe_emit_report();
+            }
+        }
+
+        self.sync_forward_stats();
+        self.sync_log_stats();
+        self.sync_ping_stats();
+        // Joining the writer here drains the queue, so the capture and
+        // rejection files are complete before the final report prints.
+        self.async_logger = None;
+        let report = self.metrics.report_text();
+        self.sink.on_report(&report);
+        self.alarms.report(self.start);
+        self.report_degradation();
+    }
+
+    /// Books one dropped datagram: bumps the per-reason counter and, when a
+    /// rejection log is attached, appends the reason, sender and raw bytes.
+    fn note_rejection(&mut self, reason: &'static str, data: &[u8]) {
+        self.metrics.record_rejection(reason);
+        if self.async_logger.is_none() && self.reject_log.is_none() {
+            return;
+        }
+        let source = self
+            .current_source
+            .map_or_else(|| "local".to_string(), |a| a.to_string());
+        if let Some(logger) = &mut self.async_logger {
+            logger.log(LogRecord::Rejection {
+                reason,
+                source,
+                data: data.to_vec(),
+            });
+        } else if let Some(log) = &mut self.reject_log {
+            if let Err(e) = log.log(reason, &source, data) {
+                eprintln!("[GCS] rejection log write failed: {e}");
+            }
+        }
+    }
+
+    /// Decodes and validates one datagram, updating link state and metrics.
+    fn handle_datagram(&mut self, data: &[u8], arrival: Instant) {
+        self.metrics.record_packet_received();
+        if !self.admit_datagram(arrival) {
+            self.metrics.record_shed();
+            return;
+        }
+        if !self.allowed_sources.is_empty() {
+            if let Some(source) = self.current_source {
+                if !self.allowed_sources.contains(&source.ip()) {
+                    self.metrics.record_untrusted();
+                    self.note_rejection("untrusted", data);
+                    if self.warned_sources.insert(source.ip()) {
+                        println!("[GCS] dropping telemetry from untrusted source {source}");
+                    }
+                    return;
+                }
+            }
+        }
+        self.track_frame_length(data.len());
+
+        // Authenticate before decoding: the tag covers the entire frame, so a
+        // forged or tampered datagram never reaches the decoders.
+        let data = if self.key.is_some() {
+            if data.len() < crate::auth::TAG_LEN {
+                self.metrics.record_spoofed();
+                self.note_rejection("no-auth-tag", data);
+                println!("[GCS] rejected unauthenticated datagram ({} bytes)", data.len());
+                return;
+            }
+            let (frame, tag) = data.split_at(data.len() - crate::auth::TAG_LEN);
+            let verified = self
+                .key
+                .as_ref()
+                .is_some_and(|key| crate::auth::verify_tag(key, frame, tag));
+            if !verified {
+                self.metrics.record_spoofed();
+                self.note_rejection("bad-auth-tag", data);
+                println!("[GCS] rejected datagram with bad auth tag (seq unknown)");
+                return;
+            }
+            frame
+        } else {
+            data
+        };
+
+        // Batch containers are unpacked here, inside the auth boundary, and
+        // each inner frame runs the normal pipeline. The container counts as
+        // the received datagram; the samples book their own valid/fault
+        // accounting.
+        if data.first() == Some(&crate::batch::TELEMETRY_VERSION_BATCH) {
+            match crate::batch::decode_batch(data) {
+                Some(frames) => {
+                    let raw: usize = frames.iter().map(Vec::len).sum();
+                    self.metrics.record_batch(raw, data.len());
+                    for frame in frames {
+                        self.process_frame(&frame, arrival);
+                    }
+                }
+                None => {
+                    self.metrics.record_invalid_packet();
+                    self.note_rejection("malformed-batch", data);
+                    println!("[GCS] rejected invalid batch frame ({} bytes)", data.len());
+                }
+            }
+            return;
+        }
+        self.process_frame(data, arrival);
+    }
+
+    /// Validates and processes one telemetry frame: everything after the
+    /// datagram-level concerns (admission, auth, batch unpacking).
+    fn process_frame(&mut self, data: &[u8], arrival: Instant) {
+        let decode_start = Instant::now();
+        let telemetry = self.decoders.decode(data);
+        if let Some(delay_us) = self.inject_decode_delay_us {
+            std::thread::sleep(Duration::from_micros(delay_us));
+        }
+        let decode_latency_us = decode_start.elapsed().as_micros();
+
+        let t = match telemetry {
+            Ok(t) => t,
+            Err(DecodeError::UnknownVersion(v)) => {
+                self.metrics.record_unknown_version();
+                self.note_rejection("unknown-version", data);
+                println!("[GCS] skipped frame with unknown version {v}");
+                return;
+            }
+            Err(e) => {
+                self.metrics.record_invalid_packet();
+                self.note_rejection(
+                    match e {
+                        DecodeError::Empty => "empty",
+                        _ => "malformed",
+                    },
+                    data,
+                );
+                println!("[GCS] rejected invalid datagram ({} bytes)", data.len());
+                return;
+            }
+        };
+
+        self.metrics.record_valid_packet();
+        self.forward_frame(data);
+        let health = health_score(&t, &self.limits, &self.health_weights);
+        self.metrics.record_health(health);
+        let shed_analysis = self.degradation_level() >= 1;
+        let shed_logging = self.degradation_level() >= 2;
+        if !shed_logging && (self.async_logger.is_some() || self.capture_log.is_some()) {
+            let source = self
+                .current_source
+                .map_or_else(|| "local".to_string(), |a| a.to_string());
+            let arrival_delta_us = self
+                .capture_last_arrival
+                .insert(source, arrival)
+                .map(|prev| arrival.duration_since(prev).as_micros() as u64);
+            if let Some(logger) = &mut self.async_logger {
+                logger.log(LogRecord::Capture {
+                    telemetry: t,
+                    health,
+                    arrival_delta_us,
+                });
+            } else if let Some(log) = &mut self.capture_log {
+                if let Err(e) = log.log_with_arrival(&t, health, arrival_delta_us) {
+                    eprintln!("[GCS] capture log write failed: {e}");
+                }
+            }
+        }
+        if self.warmup_remaining > 0 || self.warmup_until.is_some() {
+            if self.warmup_remaining > 0 {
+                self.warmup_remaining -= 1;
+            }
+            if !self.in_warmup() {
+                self.warmup_until = None;
+                println!("[GCS] warm-up complete; loss, jitter and rate enforcement enabled");
+            }
+        } else {
+            self.metrics.record_decode_latency(decode_latency_us);
+            if decode_latency_us > DECODE_LATENCY_THRESHOLD_US {
+                println!("[LATENCY VIOLATION] decode took {decode_latency_us} us");
+            }
+        }
+
+        self.track_boot(&t);
+        self.track_sequence(t.seq);
+        if !shed_analysis {
+            self.track_jitter(arrival);
+        }
+        self.arrivals.push_back(arrival);
+        self.check_rate_anomaly();
+        if self.contact_lost {
+            self.contact_lost = false;
+            self.alarms.clear("loss-of-contact");
+            println!("[GCS] contact restored at seq {}", t.seq);
+        }
+        self.last_arrival = Some(arrival);
+        self.last_timestamp_ms = Some(t.timestamp_ms);
+        self.check_clock_skew(&t, arrival);
+
+        if !shed_logging {
+            let display_angle =
+                crate::angle::normalize_angle(t.antenna_angle as f64, self.angle_convention) as i16;
+            self.sink.on_packet(&PacketRecord {
+                telemetry: &t,
+                health,
+                decode_latency_us,
+                display_angle,
+            });
+        }
+
+        let faults = classify_faults(&t, &self.limits);
+        self.track_fault_episodes(&t, &faults);
+        if !faults.is_empty() {
+            self.respond_to_faults(&t, &faults);
+        }
+        if !shed_analysis {
+            self.track_alerts(&t);
+            self.track_rate_of_change(&t);
+            self.track_stuck(&t);
+            self.track_edge_streak(&t, !faults.is_empty());
+        }
+        self.check_auto_safe(&t);
+        self.check_mode_confirmation(&t, arrival);
+        self.check_command_echo(&t);
+        self.track_alarm_edge();
+        self.track_degradation(decode_start.elapsed());
+    }
+
+    /// Logs the onset and clearance of "any alarm": one distinct line when
+    /// the active count leaves zero and one when it returns, so post-run
+    /// analysis can bracket an event without reconstructing every
+    /// individual alarm edge.
+    fn track_alarm_edge(&mut self) {
+        let active = self.alarms.active_count() > 0;
+        if active == self.any_alarm_active {
+            return;
+        }
+        self.any_alarm_active = active;
+        let line = if active {
+            format!(
+                "[GCS-ALARM] alarms active: {}",
+                self.alarms.active_names().join(", ")
+            )
+        } else {
+            "[GCS-ALARM] all alarms clear".to_string()
+        };
+        println!("{line}");
+        self.publish_event(&line);
+    }
+
+    /// Watches the modal datagram length for a sustained shift, which points
+    /// at a wire-format change on the OCS side (e.g. a mid-session upgrade to
+    /// a larger frame) rather than one corrupt datagram.
+    fn track_frame_length(&mut self, len: usize) {
+        self.metrics.record_frame_length(len);
+        let Some((modal, _)) = self.metrics.modal_frame_length() else {
+            return;
+        };
+        match self.modal_frame_length {
+            None => self.modal_frame_length = Some(modal),
+            Some(previous) if previous != modal => {
+                self.modal_frame_length = Some(modal);
+                self.metrics.record_format_change();
+                self.alarms.pulse("format-change");
+                let line = format!(
+                    "[GCS-FORMAT-CHANGE] modal datagram length shifted {previous}B -> {modal}B"
+                );
+                println!("{line}");
+                self.publish_event(&line);
+            }
+            Some(_) => {}
+        }
+    }
+
+    /// Tracks consecutive edge-case packets, raising `[GCS-SUSTAINED-EDGE]`
+    /// when the streak exceeds the configured limit and clearing on the next
+    /// normal packet. Distinct from both the per-packet fault response and
+    /// the rate-anomaly watchdog: this one flags persistence, not severity.
+    /// Flags a field whose value has not changed across many consecutive
+    /// packets: real sensors have noise, so a long flat run means the reading
+    /// is suspect even though every individual value may be in limits.
+    fn track_stuck(&mut self, t: &Telemetry) {
+        let Some(limit) = self.stuck_limit else {
+            return;
+        };
+        let mut flagged = Vec::new();
+        let mut recovered = false;
+        for track in &mut self.stuck_tracks {
+            let value = match track.field {
+                "temperature" => t.temperature as i64,
+                "battery" => t.battery_mv as i64,
+                _ => t.antenna_angle as i64,
+            };
+            if track.last == Some(value) {
+                track.run += 1;
+                if !track.alarmed && track.run >= limit {
+                    track.alarmed = true;
+                    flagged.push((track.field, value, track.run));
+                }
+            } else {
+                if track.alarmed {
+                    recovered = true;
+                    println!(
+                        "[GCS-STUCK] {} recovered after {} identical readings at seq {}",
+                        track.field,
+                        track.run + 1,
+                        t.seq
+                    );
+                }
+                track.last = Some(value);
+                track.run = 0;
+                track.alarmed = false;
+            }
+        }
+        if recovered && !self.stuck_tracks.iter().any(|track| track.alarmed) {
+            self.alarms.clear("stuck");
+        }
+        for (field, value, run) in flagged {
+            self.metrics.record_stuck_episode(field);
+            self.alarms.raise("stuck");
+            let line = format!(
+                "[GCS-STUCK] {field} unchanged for {} packets (value {value}) at seq {}",
+                run + 1,
+                t.seq
+            );
+            println!("{line}");
+            self.publish_event(&line);
+        }
+    }
+
+    fn track_edge_streak(&mut self, t: &Telemetry, is_edge: bool) {
+        if is_edge {
+            self.edge_streak += 1;
+            self.metrics.note_edge_streak(self.edge_streak);
+            if let Some(limit) = self.edge_streak_limit {
+                if !self.sustained_edge_active && self.edge_streak > limit {
+                    self.sustained_edge_active = true;
+                    self.metrics.record_sustained_edge();
+                    self.alarms.raise("sustained-edge");
+                    let line = format!(
+                        "[GCS-SUSTAINED-EDGE] {} consecutive edge cases (limit {limit}) at seq {}",
+                        self.edge_streak, t.seq
+                    );
+                    println!("{line}");
+                    self.publish_event(&line);
+                }
+            }
+        } else {
+            if self.sustained_edge_active {
+                self.sustained_edge_active = false;
+                self.alarms.clear("sustained-edge");
+                println!(
+                    "[GCS-SUSTAINED-EDGE] cleared after {} packets at seq {}",
+                    self.edge_streak, t.seq
+                );
+            }
+            self.edge_streak = 0;
+        }
+    }
+
+    /// Counts episode transitions: a fault newly present since the previous
+    /// packet starts an episode; one absent from this packet ends its episode.
+    /// Sustained conditions thus count once, however many packets report them.
+    /// The mitigation policy, when installed, sees each episode start once.
+    fn track_fault_episodes(&mut self, t: &Telemetry, faults: &[Fault]) {
+        for &fault in faults {
+            if self.active_faults.insert(fault) {
+                self.metrics.record_fault_episode(fault);
+                self.alarms.raise("fault");
+                if let (Some(policy), Some(uplink)) =
+                    (self.mitigation.as_mut(), self.mitigation_uplink.as_mut())
+                {
+                    let before = uplink.ids_issued();
+                    policy.on_fault(&FaultEvent { fault, telemetry: *t }, uplink);
+                    let after = uplink.ids_issued();
+                    // Watch the in-band echo for every id the policy spent.
+                    for id in before + 1..=after {
+                        self.pending_commands.insert(id, Instant::now());
+                    }
+                }
+            }
+        }
+        self.active_faults.retain(|f| faults.contains(f));
+        if self.active_faults.is_empty() {
+            self.alarms.clear("fault");
+        }
+    }
+
+    /// Fires or re-arms the critical-battery auto-safe response.
+    fn check_auto_safe(&mut self, t: &Telemetry) {
+        let Some(auto) = self.auto_safe.as_mut() else {
+            return;
+        };
+        if !auto.engaged && t.battery_mv < auto.floor_mv {
+            auto.engaged = true;
+            println!(
+                "[GCS-AUTO] commanded safe mode due to critical battery ({} mV < {} mV)",
+                t.battery_mv, auto.floor_mv
+            );
+            self.metrics.record_auto_command();
+            let result = auto.sender.send("SET_MODE safe");
+            // Watch for the in-band echo even when the ack timed out: the
+            // command may well have landed with only the reply lost.
+            let id = auto.sender.ids_issued();
+            match result {
+                Ok(_) => {
+                    // Watch mode-echoing telemetry for the change to land.
+                    self.pending_mode = Some((
+                        crate::mock_ocs::command::Mode::Safe as u8,
+                        Instant::now(),
+                    ));
+                }
+                Err(e) => eprintln!("[GCS-AUTO] safe-mode command failed: {e}"),
+            }
+            self.pending_commands.insert(id, Instant::now());
+        } else if auto.engaged && t.battery_mv > auto.clear_mv {
+            auto.engaged = false;
+            println!(
+                "[GCS-AUTO] battery recovered above {} mV; auto-safe re-armed",
+                auto.clear_mv
+            );
+        }
+    }
+
+    /// Cross-checks a commanded mode change against the mode byte echoed in
+    /// version-3 telemetry. Confirmation clears the watch; telemetry still
+    /// reporting a different mode past [`MODE_CONFIRM_TIMEOUT_MS`] raises
+    /// `[GCS-CMD-UNCONFIRMED]` once. Frames without a mode byte can neither
+    /// confirm nor refute, so the watch stays armed.
+    fn check_mode_confirmation(&mut self, t: &Telemetry, arrival: Instant) {
+        let Some((expected, sent)) = self.pending_mode else {
+            return;
+        };
+        let Some(reported) = t.mode else {
+            return;
+        };
+        if reported == expected {
+            println!("[GCS] mode change confirmed: telemetry reports {}", mode_label(reported));
+            self.pending_mode = None;
+        } else if arrival.duration_since(sent) >= Duration::from_millis(MODE_CONFIRM_TIMEOUT_MS) {
+            self.metrics.record_unconfirmed_command();
+            self.alarms.pulse("cmd-unconfirmed");
+            println!(
+                "[GCS-CMD-UNCONFIRMED] commanded {} but telemetry still reports {} after {} ms",
+                mode_label(expected),
+                mode_label(reported),
+                MODE_CONFIRM_TIMEOUT_MS
+            );
+            self.pending_mode = None;
+        }
+    }
+
+    /// Registers an uplink command id sent to the OCS, to be confirmed when
+    /// its echo appears in version-4 telemetry. The GCS registers its own
+    /// automatic commands (auto-safe, mitigation) itself; external senders
+    /// sharing the uplink can register theirs through this.
+    pub fn note_command_sent(&mut self, id: u32) {
+        self.pending_commands.insert(id, Instant::now());
+    }
+
+    /// Cross-checks the command-echo field against commands registered via
+    /// [`GCS::note_command_sent`]. A newly echoed id confirms its command
+    /// and records command-to-confirmation latency; an id the GCS never sent
+    /// is logged as unexpected (someone else commanding the OCS, or a stale
+    /// echo from before this session). Zero — no command processed yet — and
+    /// an echo merely persisting from an earlier packet say nothing new.
+    fn check_command_echo(&mut self, t: &Telemetry) {
+        let Some(id) = t.command_echo else {
+            return;
+        };
+        if id == 0 || id == self.last_command_echo {
+            return;
+        }
+        self.last_command_echo = id;
+        match self.pending_commands.remove(&id) {
+            Some(sent) => {
+                let latency_ms = sent.elapsed().as_secs_f64() * 1_000.0;
+                self.metrics.record_command_confirmation(latency_ms);
+                println!("[GCS] command {id} confirmed in telemetry after {latency_ms:.1} ms");
+            }
+            None => {
+                self.metrics.record_unexpected_echo();
+                println!("[GCS] unexpected command echo {id}: no matching command outstanding");
+            }
+        }
+    }
+
+    /// Executes the response action for detected faults and measures how long
+    /// detection-to-response took against the 100 ms real-time budget.
+    fn respond_to_faults(&mut self, t: &Telemetry, faults: &[Fault]) {
+        let response_start = Instant::now();
+        self.metrics.record_edge_case();
+        for &fault in faults {
+            self.metrics.record_fault(fault);
+            self.sink.on_fault(fault, t);
+            self.publish_event(&format!("[GCS-FAULT] {} at seq {}", fault.name(), t.seq));
+        }
+        let response_ms = response_start.elapsed().as_secs_f64() * 1000.0;
+        self.metrics.record_fault_response(faults, response_ms);
+        if response_ms > FAULT_RESPONSE_THRESHOLD_MS as f64 {
+            println!(
+                "[FAULT-RESPONSE VIOLATION] {response_ms:.3} ms > {FAULT_RESPONSE_THRESHOLD_MS} ms"
+            );
+        }
+    }
+
+    /// Watches the v2 boot counter per source. A changed counter means the
+    /// OCS restarted, so the sequence baseline and rate-of-change predecessor
+    /// reset too — the restart shouldn't register as thousands of lost
+    /// packets or an implausible jump. Version-1 frames always carry boot 0,
+    /// so a pure-v1 stream never trips this. Comparison is `!=`, not `>`,
+    /// so a counter wrapping past 255 still counts as a restart.
+    fn track_boot(&mut self, t: &Telemetry) {
+        let source = self
+            .current_source
+            .map_or_else(|| "local".to_string(), |a| a.to_string());
+        match self.boot_by_source.insert(source, t.boot_id) {
+            Some(prev) if prev != t.boot_id => {
+                self.metrics.record_ocs_restart();
+                println!(
+                    "[GCS] OCS restart detected (boot {prev} -> {})",
+                    t.boot_id
+                );
+                self.last_seq = None;
+                self.roc_prev = None;
+                if let Some(skew) = &mut self.clock_skew {
+                    // The mission clock restarted with the OCS; re-baseline
+                    // instead of reading the jump as skew.
+                    skew.baseline_ms = None;
+                    skew.anchor = None;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Updates loss/duplicate/reorder accounting from the sequence number.
+    /// A backward jump of [`RESET_BACKWARD_JUMP`] or more is a sender reset
+    /// (onboard reboot restarting the sequence), not thousands of lost
+    /// packets: the baseline restarts at the new sequence instead.
+    fn track_sequence(&mut self, seq: u32) {
+        if let Some(last) = self.last_seq {
+            let jump = seq.wrapping_sub(last) as i32;
+            if jump <= -RESET_BACKWARD_JUMP {
+                self.metrics.record_sender_reset(seq);
+                self.last_seq = Some(seq);
+                println!("[GCS] sender reset detected: sequence restarted at {seq} (was {last})");
+                return;
+            }
+        }
+        self.metrics.note_seq(seq);
+        if self.in_warmup() {
+            // Baseline only: keep the high-water mark but count nothing, so
+            // a GCS started mid-stream doesn't book the stream's past (or
+            // its own settling) as loss.
+            match self.last_seq {
+                Some(last) if seq.wrapping_sub(last) as i32 <= 0 => {}
+                _ => self.last_seq = Some(seq),
+            }
+            return;
+        }
+        if let Some(last) = self.last_seq {
+            let delta = seq.wrapping_sub(last) as i32;
+            match delta {
+                1 => {}
+                0 => self.metrics.record_duplicate(),
+                d if d > 1 => {
+                    for _ in 0..(d - 1) {
+                        self.metrics.record_packet_lost();
+                    }
+                    let source = self
+                        .current_source
+                        .map_or_else(|| "local".to_string(), |a| a.to_string());
+                    self.metrics.record_seq_gap(&source, (d - 1) as u32);
+                    println!("[GCS] sequence gap: {last} -> {seq} ({} lost)", delta - 1);
+                }
+                _ => self.metrics.record_out_of_order(),
+            }
+            // Keep the high-water mark so a late packet doesn't re-count loss.
+            if delta > 0 {
+                self.last_seq = Some(seq);
+            }
+        } else {
+            self.last_seq = Some(seq);
+        }
+    }
+
+    /// Records inter-arrival jitter against the expected interval, flagging
+    /// excursions beyond the tolerance band once warm-up is over.
+    fn track_jitter(&mut self, arrival: Instant) {
+        if let Some(last) = self.last_arrival {
+            let delta_us = arrival.duration_since(last).as_micros() as i64;
+            let expected_us = (self.expected_interval_ms * 1000) as i64;
+            let jitter_us = delta_us - expected_us;
+            self.metrics.record_jitter(jitter_us);
+            if let Some(tolerance_us) = self.jitter_tolerance_us {
+                if !self.in_warmup() && jitter_us.abs() > tolerance_us {
+                    self.metrics.record_jitter_violation();
+                    self.alarms.pulse("jitter");
+                    println!(
+                        "[GCS-JITTER] {jitter_us} us outside tolerance band of +/-{tolerance_us} us"
+                    );
+                }
+            }
+        }
+    }
+
+    /// Feeds one packet to the clock-skew watchdog. The offset estimate is
+    /// local elapsed time minus the packet's mission timestamp; arrival
+    /// jitter moves it by milliseconds per packet, which both bounds are
+    /// expected to sit well above.
+    fn check_clock_skew(&mut self, t: &Telemetry, arrival: Instant) {
+        if self.in_warmup() {
+            return;
+        }
+        let Some(skew) = &mut self.clock_skew else {
+            return;
+        };
+        let local_ms = arrival.duration_since(self.start).as_millis() as i64;
+        let offset_ms = local_ms - t.timestamp_ms as i64;
+        let Some(baseline) = skew.baseline_ms else {
+            skew.baseline_ms = Some(offset_ms);
+            skew.anchor = Some((arrival, offset_ms));
+            return;
+        };
+        let deviation_ms = offset_ms - baseline;
+        self.metrics.note_clock_offset(deviation_ms.unsigned_abs());
+        match skew.anchor {
+            Some((at, offset_then)) if arrival.duration_since(at) >= CLOCK_DRIFT_WINDOW => {
+                let elapsed_s = arrival.duration_since(at).as_secs_f64();
+                skew.last_drift_ms_per_s = (offset_ms - offset_then) as f64 / elapsed_s;
+                skew.anchor = Some((arrival, offset_ms));
+            }
+            Some(_) => {}
+            None => skew.anchor = Some((arrival, offset_ms)),
+        }
+        let offset_breach = deviation_ms.abs() > skew.max_offset_ms;
+        let drift_breach = skew.last_drift_ms_per_s.abs() > skew.max_drift_ms_per_s;
+        if (offset_breach || drift_breach) && !skew.alarm_active {
+            skew.alarm_active = true;
+            self.alarms.raise("clock-skew");
+            self.metrics.record_clock_skew_alarm();
+            self.metrics.set_clock_uncalibrated(true);
+            let cause = if offset_breach {
+                format!("offset {deviation_ms} ms beyond +/-{} ms bound", skew.max_offset_ms)
+            } else {
+                format!(
+                    "offset drifting {:.1} ms/s (bound {:.1} ms/s)",
+                    skew.last_drift_ms_per_s, skew.max_drift_ms_per_s
+                )
+            };
+            let line = format!("[GCS-CLOCK-SKEW] {cause}; timestamp metrics uncalibrated");
+            println!("{line}");
+            self.publish_event(&line);
+        } else if !offset_breach && !drift_breach && skew.alarm_active {
+            skew.alarm_active = false;
+            self.alarms.clear("clock-skew");
+            self.metrics.set_clock_uncalibrated(false);
+            let line = format!(
+                "[GCS-CLOCK-SKEW] cleared: offset {deviation_ms} ms back in bounds; \
+                 timestamp metrics calibrated again"
+            );
+            println!("{line}");
+            self.publish_event(&line);
+        }
+    }
+
+    /// Emits the compact heartbeat line when its wall-clock period elapses.
+    /// Runs every loop iteration (packet or timeout) so the pulse is steady
+    /// regardless of traffic; the full report has its own, longer cadence.
+    fn maybe_emit_status(&mut self) {
+        let Some(period) = self.status_interval else {
+            return;
+        };
+        if self.last_status.elapsed() < period {
+            return;
+        }
+        let rx = self.metrics.packets_received();
+        let rate = (rx - self.rx_at_last_status) as f64 / self.last_status.elapsed().as_secs_f64();
+        let uncal = if self.clock_skew.as_ref().is_some_and(|s| s.alarm_active) {
+            " clock=UNCALIBRATED"
+        } else {
+            ""
+        };
+        let busy = self
+            .busy
+            .as_ref()
+            .map_or(String::new(), |b| format!(" busy={:.0}%", b.fraction() * 100.0));
+        let met = self.last_timestamp_ms.map_or(String::new(), |ms| {
+            format!(" met={} wall={}{uncal}", format_met(ms), wall_clock_hms())
+        });
+        let names = self.alarms.active_names();
+        let alarm_list = if names.is_empty() {
+            String::new()
+        } else {
+            format!(" [{}]", names.join(","))
+        };
+        let line = format!(
+            "[GCS] t={}s rx={} rate={:.1}/s loss={} p95={}us faults={} alarms={}{alarm_list}{busy}{met}",
+            self.start.elapsed().as_secs(),
+            rx,
+            rate,
+            self.metrics.packets_lost(),
+            self.metrics.decode_p95_us(),
+            self.metrics.total_faults(),
+            names.len(),
+        );
+        println!("{line}");
+        self.publish_event(&line);
+        self.last_status = Instant::now();
+        self.rx_at_last_status = rx;
+    }
+
+    /// Prints the full report when its wall-clock period elapses. The final
+    /// report at shutdown prints unconditionally, so a cadence longer than
+    /// the session still yields one complete report.
+    fn maybe_emit_report(&mut self) {
+        let Some(period) = self.report_interval else {
+            return;
+        };
+        if self.last_report.elapsed() < period {
+            return;
+        }
+        self.sync_forward_stats();
+        self.sync_log_stats();
+        self.sync_ping_stats();
+        let report = self.metrics.report_text();
+        self.sink.on_report(&report);
+        self.alarms.report(self.start);
+        self.report_degradation();
+        self.last_report = Instant::now();
+    }
+
+    /// Compares the sliding-window receive rate against the expected rate,
+    /// raising a rate anomaly on a sustained shortfall and clearing it (with
+    /// the episode duration) on recovery. A partial slowdown is caught here
+    /// long before the loss-of-contact watchdog would fire.
+    fn check_rate_anomaly(&mut self) {
+        let now = Instant::now();
+        while let Some(&oldest) = self.arrivals.front() {
+            if now.duration_since(oldest) > RATE_WINDOW {
+                self.arrivals.pop_front();
+            } else {
+                break;
+            }
+        }
+        // Need a full window of history, a live link and a finished warm-up
+        // before judging rate.
+        if self.last_arrival.is_none()
+            || self.contact_lost
+            || self.start.elapsed() < RATE_WINDOW
+            || self.in_warmup()
+        {
+            return;
+        }
+        let rate = self.arrivals.len() as f64 / RATE_WINDOW.as_secs_f64();
+        let expected_rate = 1000.0 / self.expected_interval_ms as f64;
+        let mut below = rate < self.rate_anomaly_fraction * expected_rate;
+        // Silence inside a known off-window is scheduled, not a shortfall.
+        if let (Some(gap_ms), Some(last)) = (self.scheduled_gap_ms, self.last_arrival) {
+            if below && last.elapsed().as_millis() as u64 <= gap_ms {
+                below = false;
+            }
+        }
+
+        if below {
+            let since = *self.rate_below_since.get_or_insert(now);
+            if self.rate_anomaly_since.is_none()
+                && now.duration_since(since) >= RATE_ANOMALY_SUSTAIN
+            {
+                self.rate_anomaly_since = Some(since);
+                self.alarms.raise("rate-anomaly");
+                println!(
+                    "[GCS-RATE-ANOMALY] rate {rate:.1}/s below {:.0}% of expected {expected_rate:.1}/s",
+                    self.rate_anomaly_fraction * 100.0
+                );
+            }
+        } else {
+            self.rate_below_since = None;
+            if let Some(since) = self.rate_anomaly_since.take() {
+                let duration_ms = now.duration_since(since).as_secs_f64() * 1000.0;
+                self.metrics.record_rate_anomaly(duration_ms);
+                self.alarms.clear("rate-anomaly");
+                println!("[GCS-RATE-ANOMALY] cleared after {:.1} s", duration_ms / 1000.0);
+            }
+        }
+    }
+
+    /// Runs between packets: the loss-of-contact watchdog plus the rate gauge
+    /// (which must keep decaying even when nothing arrives).
+    fn maintenance_tick(&mut self) {
+        self.check_rate_anomaly();
+        if let Some(last) = self.last_arrival {
+            let silent_ms = last.elapsed().as_millis() as u64;
+            if silent_ms > self.contact_timeout_ms() && !self.contact_lost {
+                self.contact_lost = true;
+                self.metrics.record_fault(Fault::LossOfContact);
+                self.alarms.raise("loss-of-contact");
+                // The contact_lost flag already gates this to one firing per
+                // silence, so occurrence and episode track together here.
+                self.metrics.record_fault_episode(Fault::LossOfContact);
+                let line = format!("[GCS-FAULT] LossOfContact: no telemetry for {silent_ms} ms");
+                println!("{line}");
+                self.publish_event(&line);
+            }
+        }
+        self.track_alarm_edge();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry::TELEMETRY_WIRE_SIZE;
+
+    fn nominal() -> Telemetry {
+        Telemetry {
+            seq: 0,
+            timestamp_ms: 0,
+            temperature: 20,
+            battery_mv: 12_000,
+            antenna_angle: 3,
+            boot_id: 0,
+            mode: None,
+            command_echo: None,
+        }
+    }
+
+    #[test]
+    fn nominal_sample_has_no_faults() {
+        assert!(classify_faults(&nominal(), &Limits::default()).is_empty());
+    }
+
+    #[test]
+    fn fault_produces_response_time_sample() {
+        // Ephemeral port so the test never collides with a running GCS.
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        let mut t = nominal();
+        t.temperature = 150;
+        gcs.handle_datagram(&t.to_bytes(), Instant::now());
+        assert_eq!(gcs.metrics.fault_response_times_ms.len(), 1);
+        assert_eq!(gcs.metrics.faults_detected[&Fault::HighTemperature], 1);
+        assert_eq!(
+            gcs.metrics.fault_response_by_type[&Fault::HighTemperature].len(),
+            1
+        );
+    }
+
+    #[test]
+    fn jitter_outside_tolerance_is_counted_after_warmup() {
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        gcs.set_warmup(0);
+        gcs.set_jitter_tolerance(1);
+        let first = Instant::now();
+        let mut t = nominal();
+        gcs.handle_datagram(&t.to_bytes(), first);
+        // Second packet arrives 200 ms after the first against a 1000 ms
+        // expected interval: -800 ms of jitter, far outside the 1 ms band.
+        t.seq = 1;
+        gcs.handle_datagram(&t.to_bytes(), first + Duration::from_millis(200));
+        assert_eq!(gcs.metrics.jitter_violations, 1);
+        assert!(gcs.metrics.worst_jitter_us() < -700_000);
+    }
+
+    #[test]
+    fn warmup_establishes_sequence_baseline_without_counting_loss() {
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        gcs.set_warmup(3);
+        let mut t = nominal();
+        for seq in [0u32, 5, 6] {
+            t.seq = seq;
+            gcs.handle_datagram(&t.to_bytes(), Instant::now());
+        }
+        // The 0 -> 5 gap fell inside warm-up: baseline moved, nothing booked.
+        assert_eq!(gcs.metrics.packets_lost, 0);
+        // Warm-up spent on the third packet; the next gap counts normally.
+        t.seq = 8;
+        gcs.handle_datagram(&t.to_bytes(), Instant::now());
+        assert_eq!(gcs.metrics.packets_lost, 1);
+    }
+
+    #[test]
+    fn time_based_warmup_keeps_enforcement_off_until_it_elapses() {
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        gcs.set_warmup(0);
+        gcs.set_warmup_secs(60);
+        assert!(gcs.in_warmup());
+        let mut t = nominal();
+        for seq in [0u32, 5] {
+            t.seq = seq;
+            gcs.handle_datagram(&t.to_bytes(), Instant::now());
+        }
+        assert_eq!(gcs.metrics.packets_lost, 0, "gap inside the time window");
+        // Zeroing the window restores immediate enforcement.
+        gcs.set_warmup_secs(0);
+        assert!(!gcs.in_warmup());
+    }
+
+    #[test]
+    fn warmup_suppresses_jitter_alarm() {
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        gcs.set_warmup(10);
+        gcs.set_jitter_tolerance(1);
+        let first = Instant::now();
+        let mut t = nominal();
+        gcs.handle_datagram(&t.to_bytes(), first);
+        t.seq = 1;
+        gcs.handle_datagram(&t.to_bytes(), first + Duration::from_millis(200));
+        assert_eq!(gcs.metrics.jitter_violations, 0);
+    }
+
+    #[test]
+    fn injected_decode_delay_counts_latency_violation() {
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        gcs.set_warmup(0);
+        gcs.set_inject_decode_delay(DECODE_LATENCY_THRESHOLD_US as u64 + 1_000);
+        gcs.handle_datagram(&nominal().to_bytes(), Instant::now());
+        assert_eq!(gcs.metrics.latency_violations, 1);
+        assert_eq!(gcs.metrics.decode_latencies_us.len(), 1);
+        assert!(gcs.metrics.decode_latencies_us[0] > DECODE_LATENCY_THRESHOLD_US);
+    }
+
+    #[test]
+    fn keyed_gcs_rejects_untagged_and_forged_frames() {
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        gcs.set_key(b"orbit-secret".to_vec());
+        let t = nominal();
+
+        // Untagged frame: spoofed, not decoded.
+        gcs.handle_datagram(&t.to_bytes(), Instant::now());
+        assert_eq!(gcs.metrics.spoofed_packets, 1);
+        assert_eq!(gcs.metrics.valid_packets, 0);
+
+        // Wrong-key tag: spoofed.
+        let mut forged = t.to_bytes().to_vec();
+        let bad_tag = crate::auth::frame_tag(b"wrong-key", &forged);
+        forged.extend_from_slice(&bad_tag);
+        gcs.handle_datagram(&forged, Instant::now());
+        assert_eq!(gcs.metrics.spoofed_packets, 2);
+
+        // Correct tag: accepted.
+        let mut signed = t.to_bytes().to_vec();
+        let tag = crate::auth::frame_tag(b"orbit-secret", &signed);
+        signed.extend_from_slice(&tag);
+        gcs.handle_datagram(&signed, Instant::now());
+        assert_eq!(gcs.metrics.valid_packets, 1);
+    }
+
+    #[test]
+    fn flat_field_raises_stuck_alarm_once_until_it_recovers() {
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        gcs.set_stuck_limit(3);
+        let mut t = nominal();
+        // nominal() never varies, so every field flatlines together.
+        for seq in 0..6 {
+            t.seq = seq;
+            gcs.handle_datagram(&t.to_bytes(), Instant::now());
+        }
+        assert_eq!(gcs.metrics.stuck_episodes["temperature"], 1);
+        assert_eq!(gcs.metrics.stuck_episodes["battery"], 1);
+        assert_eq!(gcs.metrics.stuck_episodes["antenna"], 1);
+
+        // A changed reading recovers the field; a fresh flat run of the same
+        // length is a new episode.
+        t.temperature += 1;
+        for seq in 6..11 {
+            t.seq = seq;
+            gcs.handle_datagram(&t.to_bytes(), Instant::now());
+        }
+        assert_eq!(gcs.metrics.stuck_episodes["temperature"], 2);
+        assert_eq!(gcs.metrics.stuck_episodes["battery"], 1, "battery never recovered");
+    }
+
+    #[test]
+    fn metrics_snapshot_round_trips_through_json() {
+        let snap = MetricsSnapshot {
+            packets_received: 1234,
+            loss_pct: 1.5,
+            invalid_pct: 0.0,
+            avg_decode_us: 3.25,
+            p95_decode_us: 7.5,
+            avg_abs_jitter_us: 120.125,
+        };
+        let parsed = MetricsSnapshot::parse(&snap.to_json()).expect("own output parses");
+        assert_eq!(parsed, snap);
+        assert!(MetricsSnapshot::parse("not json").is_none());
+        assert!(MetricsSnapshot::parse("{\"loss_pct\":1.0}").is_none(), "missing keys rejected");
+    }
+
+    #[test]
+    fn golden_comparison_flags_only_worsened_metrics() {
+        let golden = MetricsSnapshot {
+            packets_received: 1000,
+            loss_pct: 2.0,
+            invalid_pct: 0.0,
+            avg_decode_us: 4.0,
+            p95_decode_us: 10.0,
+            avg_abs_jitter_us: 100.0,
+        };
+        let mut current = golden.clone();
+
+        // Within tolerance and outright improvements both pass.
+        current.loss_pct = 2.3;
+        current.avg_decode_us = 1.0;
+        assert!(current.regressions_against(&golden, 20.0).is_empty());
+
+        // Beyond tolerance fails, naming the metric and the allowance.
+        current.loss_pct = 2.5;
+        let regressions = current.regressions_against(&golden, 20.0);
+        assert_eq!(regressions.len(), 1);
+        assert!(regressions[0].starts_with("loss_pct: 2.50 vs golden 2.00"), "{}", regressions[0]);
+
+        // A zero baseline tolerates no worsening at all.
+        current.loss_pct = 2.0;
+        current.invalid_pct = 0.01;
+        assert_eq!(current.regressions_against(&golden, 20.0).len(), 1);
+    }
+
+    #[test]
+    fn runtime_threshold_updates_are_whitelisted_and_validated() {
+        let mut limits = Limits::default();
+        limits.set_threshold("temp_high", "110").unwrap();
+        assert_eq!(limits.temp_high, 110);
+
+        // Ordering violation: temp_low may not climb past temp_high.
+        let err = limits.set_threshold("temp_low", "120").unwrap_err();
+        assert!(err.contains("below temp_high"), "{err}");
+        assert_eq!(limits.temp_low, -50, "rejected update must not apply");
+
+        // Unparseable value and out-of-whitelist name both carry a reason.
+        assert!(limits.set_threshold("battery_low_mv", "lots").unwrap_err().contains("invalid"));
+        assert!(limits.set_threshold("health_floor", "1").unwrap_err().contains("unknown threshold"));
+    }
+
+    #[test]
+    fn met_formats_as_days_plus_time_of_day() {
+        assert_eq!(format_met(0), "0+00:00:00");
+        // 2 days, 3 hours, 4 minutes, 5 seconds.
+        assert_eq!(format_met(((2 * 24 + 3) * 3_600 + 4 * 60 + 5) * 1_000), "2+03:04:05");
+    }
+
+    #[test]
+    fn rejections_are_counted_per_reason_and_logged_with_raw_bytes() {
+        let dir = std::env::temp_dir().join(format!("gcs-test-{}-reject", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let log = crate::logfile::RejectionLog::create(&dir.join("reject.log"), 10).unwrap();
+        let path = log.current_path().to_path_buf();
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        gcs.set_reject_log(log);
+
+        // A flipped CRC byte, a bogus version byte, and an empty datagram
+        // each land under their own reason.
+        let mut frame = nominal().to_bytes();
+        frame[19] ^= 0xff;
+        gcs.handle_datagram(&frame, Instant::now());
+        gcs.handle_datagram(&[9u8, 1, 2, 3], Instant::now());
+        gcs.handle_datagram(&[], Instant::now());
+
+        assert_eq!(gcs.metrics.rejections_by_reason["malformed"], 1);
+        assert_eq!(gcs.metrics.rejections_by_reason["unknown-version"], 1);
+        assert_eq!(gcs.metrics.rejections_by_reason["empty"], 1);
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert!(text.contains("reason=malformed from=local"), "{text}");
+        assert!(
+            text.contains("reason=unknown-version from=local len=4 bytes=09010203"),
+            "{text}"
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn unlisted_sources_are_dropped_as_untrusted_and_warned_once() {
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        gcs.set_allowed_sources(&["127.0.0.1".to_string()])
+            .expect("loopback resolves");
+
+        // A stray sender is dropped before decoding, counted once per
+        // packet but warned about only on the first.
+        gcs.current_source = Some("10.1.2.3:5000".parse().unwrap());
+        gcs.handle_datagram(&nominal().to_bytes(), Instant::now());
+        gcs.handle_datagram(&nominal().to_bytes(), Instant::now());
+        assert_eq!(gcs.metrics.untrusted_packets, 2);
+        assert_eq!(gcs.metrics.rejections_by_reason["untrusted"], 2);
+        assert_eq!(gcs.metrics.valid_packets, 0);
+
+        // The allowlisted source still flows through the normal pipeline.
+        gcs.current_source = Some("127.0.0.1:5000".parse().unwrap());
+        gcs.handle_datagram(&nominal().to_bytes(), Instant::now());
+        assert_eq!(gcs.metrics.valid_packets, 1);
+    }
+
+    #[test]
+    fn command_echo_confirms_pending_ids_and_flags_unknown_ones() {
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        gcs.set_warmup(0);
+        gcs.note_command_sent(5);
+        let mut t = nominal();
+        // Echo disabled-or-idle values say nothing.
+        t.command_echo = Some(0);
+        gcs.handle_datagram(&t.to_bytes_tlv(), Instant::now());
+        assert_eq!(gcs.metrics.confirmed_commands, 0);
+        // The awaited id confirms once, even when echoed repeatedly.
+        t.seq = 1;
+        t.command_echo = Some(5);
+        gcs.handle_datagram(&t.to_bytes_tlv(), Instant::now());
+        t.seq = 2;
+        gcs.handle_datagram(&t.to_bytes_tlv(), Instant::now());
+        assert_eq!(gcs.metrics.confirmed_commands, 1);
+        assert_eq!(gcs.metrics.confirmation_latencies_ms.len(), 1);
+        assert!(gcs.pending_commands.is_empty());
+        // An id this GCS never sent is flagged, once per distinct id.
+        t.seq = 3;
+        t.command_echo = Some(99);
+        gcs.handle_datagram(&t.to_bytes_tlv(), Instant::now());
+        assert_eq!(gcs.metrics.unexpected_echoes, 1);
+        assert_eq!(gcs.metrics.confirmed_commands, 1);
+    }
+
+    #[test]
+    fn clock_skew_alarm_fires_on_a_large_offset_and_recalibrates() {
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        gcs.set_warmup(0);
+        gcs.set_clock_skew_bound(500, 1_000.0);
+        let mut t = nominal();
+        // First sample establishes the offset baseline.
+        gcs.handle_datagram(&t.to_bytes(), Instant::now());
+        // A mission timestamp ten seconds ahead of local time breaches the
+        // 500 ms bound.
+        t.seq = 1;
+        t.timestamp_ms = 10_000;
+        gcs.handle_datagram(&t.to_bytes(), Instant::now());
+        assert_eq!(gcs.metrics.clock_skew_alarms, 1);
+        assert!(gcs.metrics.clock_uncalibrated);
+        assert!(gcs.metrics.max_clock_skew_ms >= 5_000);
+        assert!(gcs.alarms.get("clock-skew").expect("alarm on the books").active);
+        // Back within bounds: the alarm clears and the figures recalibrate.
+        t.seq = 2;
+        t.timestamp_ms = 0;
+        gcs.handle_datagram(&t.to_bytes(), Instant::now());
+        assert_eq!(gcs.metrics.clock_skew_alarms, 1);
+        assert!(!gcs.metrics.clock_uncalibrated);
+        assert!(!gcs.alarms.get("clock-skew").unwrap().active);
+    }
+
+    #[test]
+    fn clock_skew_alarm_catches_a_fast_drift_inside_the_offset_bound() {
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        gcs.set_warmup(0);
+        gcs.set_clock_skew_bound(10_000, 5.0);
+        let start = gcs.start;
+        let mut t = nominal();
+        gcs.handle_datagram(&t.to_bytes(), start);
+        // Six seconds later the offset has crept only 50 ms — well inside
+        // the bound — but that is 8.3 ms/s against a 5 ms/s drift budget.
+        t.seq = 1;
+        t.timestamp_ms = 5_950;
+        gcs.handle_datagram(&t.to_bytes(), start + Duration::from_secs(6));
+        assert_eq!(gcs.metrics.clock_skew_alarms, 1);
+        assert!(gcs.metrics.clock_uncalibrated);
+    }
+
+    #[test]
+    fn busy_gauge_alarms_at_saturation_and_clears_when_the_loop_idles() {
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        gcs.set_busy_budget(80);
+        // Thirty fully-busy 200 ms iterations: six seconds of history, all
+        // of it processing time.
+        for _ in 0..30 {
+            gcs.track_busy(Duration::ZERO, Duration::from_millis(200));
+        }
+        assert_eq!(gcs.metrics.busy_alarms, 1);
+        assert!(gcs.alarms.get("busy").expect("alarm on the books").active);
+        assert!(gcs.metrics.max_busy_fraction > 0.99);
+        // Enough fully-blocked iterations drag the window share under the
+        // budget again.
+        for _ in 0..60 {
+            gcs.track_busy(Duration::from_millis(200), Duration::from_millis(200));
+        }
+        assert_eq!(gcs.metrics.busy_alarms, 1);
+        assert!(!gcs.alarms.get("busy").unwrap().active);
+    }
+
+    #[test]
+    fn degradation_ladder_sheds_analysis_under_load_and_recovers() {
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        gcs.set_warmup(0);
+        gcs.set_stuck_limit(0);
+        gcs.set_degradation_budget(200);
+        gcs.set_inject_decode_delay(1_000);
+        let mut t = nominal();
+        t.temperature = 150;
+        for seq in 0..DEGRADE_AFTER_OVERRUNS {
+            t.seq = seq;
+            gcs.handle_datagram(&t.to_bytes(), Instant::now());
+        }
+        assert_eq!(gcs.degradation_level(), 1, "sustained overruns shed a level");
+
+        // At reduced analysis the critical fault path still runs, but the
+        // two-tier field alerts do not.
+        let episodes_at_shed = gcs.metrics.alarm_episodes.len();
+        t.seq = DEGRADE_AFTER_OVERRUNS;
+        gcs.handle_datagram(&t.to_bytes(), Instant::now());
+        assert!(gcs.metrics.faults_detected[&Fault::HighTemperature] > 0);
+        assert_eq!(gcs.metrics.alarm_episodes.len(), episodes_at_shed);
+
+        // Comfortably under half the budget for the hysteresis depth
+        // restores full service.
+        gcs.set_inject_decode_delay(0);
+        let mut seq = DEGRADE_AFTER_OVERRUNS + 1;
+        let normal = nominal();
+        let mut n = normal;
+        while gcs.degradation_level() > 0 && seq < 10_000 {
+            n.seq = seq;
+            gcs.handle_datagram(&n.to_bytes(), Instant::now());
+            seq += 1;
+        }
+        assert_eq!(gcs.degradation_level(), 0, "recovery should restore");
+        let d = gcs.degradation.as_ref().unwrap();
+        assert!(d.time_at[1] > Duration::ZERO, "time at level 1 is charged");
+    }
+
+    #[test]
+    fn batch_containers_unpack_into_individual_samples() {
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        gcs.set_warmup(0);
+        let samples: Vec<Telemetry> = (0..3)
+            .map(|seq| {
+                let mut t = nominal();
+                t.seq = seq;
+                t.timestamp_ms = 1_000 * u64::from(seq);
+                t
+            })
+            .collect();
+        let frame = crate::batch::encode_batch(&samples);
+        gcs.handle_datagram(&frame, Instant::now());
+
+        // One datagram on the wire, three valid samples out of it, and
+        // sequence tracking spans all of them.
+        assert_eq!(gcs.metrics.packets_received, 1);
+        assert_eq!(gcs.metrics.valid_packets, 3);
+        assert_eq!(gcs.metrics.batches_received, 1);
+        assert_eq!(gcs.metrics.batch_wire_bytes, frame.len() as u64);
+        assert_eq!(
+            gcs.metrics.batch_raw_bytes,
+            (samples.len() * crate::telemetry::TELEMETRY_WIRE_SIZE) as u64
+        );
+        assert_eq!(gcs.metrics.seq_span, Some((0, 2)));
+
+        // A corrupt container is rejected whole under its own reason.
+        let mut corrupt = frame;
+        corrupt[5] ^= 0x01;
+        gcs.handle_datagram(&corrupt, Instant::now());
+        assert_eq!(gcs.metrics.valid_packets, 3);
+        assert_eq!(gcs.metrics.rejections_by_reason["malformed-batch"], 1);
+    }
+
+    #[test]
+    fn async_logging_flushes_queued_records_at_shutdown() {
+        let dir = std::env::temp_dir().join(format!("gcs-test-{}-async-log", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let capture = crate::logfile::TelemetryLog::create(
+            &dir.join("telemetry.csv"),
+            crate::logfile::LogFormat::Csv,
+            crate::logfile::RotationPolicy::default(),
+        )
+        .unwrap();
+        let capture_path = capture.current_path().to_path_buf();
+        let reject = crate::logfile::RejectionLog::create(&dir.join("reject.log"), 0).unwrap();
+        let reject_path = reject.current_path().to_path_buf();
+
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        gcs.set_capture_log(capture);
+        gcs.set_reject_log(reject);
+        gcs.set_async_logging(64);
+        gcs.handle_datagram(&nominal().to_bytes(), Instant::now());
+        gcs.handle_datagram(&[], Instant::now());
+
+        // Dropping the logger joins the writer thread, so both records must
+        // be on disk afterwards.
+        gcs.async_logger = None;
+        let capture_text = std::fs::read_to_string(&capture_path).unwrap();
+        assert!(capture_text.lines().count() >= 2, "header + row: {capture_text}");
+        let reject_text = std::fs::read_to_string(&reject_path).unwrap();
+        assert!(reject_text.contains("reason=empty from=local"), "{reject_text}");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn ping_probe_measures_round_trips_end_to_end() {
+        use crate::mock_ocs::command::{CommandReceiver, Mode, OcsShared};
+        let shared = Arc::new(OcsShared::new(1000, Mode::Normal));
+        let receiver = CommandReceiver::bind(0, Arc::clone(&shared)).unwrap();
+        let addr = receiver.local_addr().unwrap();
+        receiver.spawn();
+
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        gcs.enable_ping(&format!("127.0.0.1:{}", addr.port()), 100).unwrap();
+        let deadline = Instant::now() + Duration::from_secs(3);
+        loop {
+            gcs.sync_ping_stats();
+            if !gcs.metrics.ping_rtts_ms.is_empty() || Instant::now() > deadline {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        assert!(!gcs.metrics.ping_rtts_ms.is_empty(), "no echo within 3 s");
+        assert!(gcs.metrics.report_text().contains("Command RTT:"));
+    }
+
+    #[test]
+    fn lost_ping_probes_are_counted_instead_of_hanging() {
+        // A bound socket that never replies.
+        let silent = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let addr = silent.local_addr().unwrap();
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        gcs.enable_ping(&addr.to_string(), 50).unwrap();
+        let deadline = Instant::now() + Duration::from_secs(3);
+        loop {
+            gcs.sync_ping_stats();
+            if gcs.metrics.ping_failures > 0 || Instant::now() > deadline {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        assert!(gcs.metrics.ping_failures > 0, "timeout never booked");
+        assert!(gcs.metrics.ping_rtts_ms.is_empty());
+    }
+
+    #[test]
+    fn wrapped_antenna_angles_measure_the_shortest_distance() {
+        let limits = Limits::default();
+        let mut t = nominal();
+        // 350 is 10 degrees off boresight the short way around, not 350.
+        t.antenna_angle = 350;
+        assert!(classify_faults(&t, &limits).is_empty());
+        t.antenna_angle = -10;
+        assert!(classify_faults(&t, &limits).is_empty());
+        // 180 is the farthest possible pointing error.
+        t.antenna_angle = 180;
+        assert!(classify_faults(&t, &limits).contains(&Fault::AntennaMisalignment));
+        t.antenna_angle = -170;
+        assert!(classify_faults(&t, &limits).contains(&Fault::AntennaMisalignment));
+    }
+
+    #[test]
+    fn two_tier_alerts_fire_and_clear_with_independent_hysteresis() {
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        gcs.set_alert_threshold(
+            AlertField::TemperatureHigh,
+            FieldThreshold {
+                warn: 80.0,
+                alarm: 100.0,
+                clear: 70.0,
+            },
+        )
+        .unwrap();
+        let mut t = nominal();
+        let mut feed = |gcs: &mut GCS, temp: i16| {
+            t.temperature = temp;
+            t.seq += 1;
+            gcs.handle_datagram(&t.to_bytes(), Instant::now());
+        };
+
+        feed(&mut gcs, 85); // warn episode
+        feed(&mut gcs, 90); // still warn: no new episode
+        feed(&mut gcs, 105); // escalates to alarm
+        feed(&mut gcs, 75); // above clear: alarm holds
+        feed(&mut gcs, 60); // clears
+        feed(&mut gcs, 85); // second warn episode
+
+        assert_eq!(gcs.metrics.warn_episodes["temperature-high"], 2);
+        assert_eq!(gcs.metrics.alarm_episodes["temperature-high"], 1);
+    }
+
+    #[test]
+    fn rate_spikes_flag_implausible_jumps_but_skip_gaps() {
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        gcs.set_rate_of_change_limits(RateOfChangeLimits {
+            temperature: 10.0,
+            battery: 500.0,
+            antenna: 20.0,
+        })
+        .unwrap();
+        let mut t = nominal();
+        gcs.handle_datagram(&t.to_bytes(), Instant::now()); // first: no previous
+        t.seq += 1;
+        t.temperature += 60; // implausible between consecutive packets
+        gcs.handle_datagram(&t.to_bytes(), Instant::now());
+        assert_eq!(gcs.metrics.rate_spikes["temperature"], 1);
+        // Across a sequence gap the predecessor is unknown: no spike even
+        // though the value swings back just as hard.
+        t.seq += 3;
+        t.temperature -= 60;
+        gcs.handle_datagram(&t.to_bytes(), Instant::now());
+        assert_eq!(gcs.metrics.rate_spikes["temperature"], 1);
+        assert!(gcs.metrics.report_text().contains("Rate spikes:"));
+    }
+
+    #[test]
+    fn antenna_rate_spikes_use_angular_distance_across_the_wrap() {
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        gcs.set_rate_of_change_limits(RateOfChangeLimits {
+            temperature: 1_000.0,
+            battery: 100_000.0,
+            antenna: 30.0,
+        })
+        .unwrap();
+        let mut t = nominal();
+        t.antenna_angle = 175;
+        gcs.handle_datagram(&t.to_bytes(), Instant::now());
+        // 175 -> -175 is only 10 degrees the short way around: no spike.
+        t.seq += 1;
+        t.antenna_angle = -175;
+        gcs.handle_datagram(&t.to_bytes(), Instant::now());
+        assert!(gcs.metrics.rate_spikes.is_empty());
+    }
+
+    #[test]
+    fn histogram_export_writes_bucketed_csv() {
+        let mut metrics = GCSPerformanceMetrics::new();
+        for latency in [100u128, 150, 2_000] {
+            metrics.record_decode_latency(latency);
+        }
+        metrics.record_seq_gap("local", 1);
+        metrics.record_seq_gap("local", 1);
+        metrics.record_seq_gap("10.0.0.2:9", 4);
+        let path = std::env::temp_dir()
+            .join(format!("wewinthis-hist-test-{}.csv", std::process::id()));
+        metrics.export_histograms_csv(&path).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(text.starts_with("histogram,bucket_lower,bucket_upper,count\n"));
+        // Latency rows cover the full range in equal-width buckets.
+        let latency_rows: Vec<_> =
+            text.lines().filter(|l| l.starts_with("decode_latency_us,")).collect();
+        assert_eq!(latency_rows.len(), HISTOGRAM_EXPORT_BUCKETS);
+        assert_eq!(latency_rows[0], "decode_latency_us,100,195,2");
+        // Gap sizes are exported exactly, merged across sources.
+        assert!(text.contains("gap_size,1,1,2\n"));
+        assert!(text.contains("gap_size,4,4,1\n"));
+        // No jitter was recorded, so no jitter rows appear.
+        assert!(!text.contains("jitter_us,"));
+    }
+
+    #[test]
+    fn mode_echo_confirms_or_flags_commanded_mode_changes() {
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        let safe = crate::mock_ocs::command::Mode::Safe as u8;
+        let normal = crate::mock_ocs::command::Mode::Normal as u8;
+
+        // Confirmation: the echoed mode matches and clears the watch.
+        gcs.pending_mode = Some((safe, Instant::now()));
+        let mut t = nominal();
+        t.mode = Some(safe);
+        gcs.handle_datagram(&t.to_bytes_v3(), Instant::now());
+        assert!(gcs.pending_mode.is_none());
+        assert_eq!(gcs.metrics.unconfirmed_commands, 0);
+
+        // A frame without a mode byte can neither confirm nor refute.
+        gcs.pending_mode = Some((safe, Instant::now() - Duration::from_secs(10)));
+        t.seq += 1;
+        gcs.handle_datagram(&t.to_bytes(), Instant::now());
+        assert!(gcs.pending_mode.is_some());
+
+        // Still reporting normal past the timeout raises the alarm once.
+        t.seq += 1;
+        t.mode = Some(normal);
+        gcs.handle_datagram(&t.to_bytes_v3(), Instant::now());
+        assert!(gcs.pending_mode.is_none());
+        assert_eq!(gcs.metrics.unconfirmed_commands, 1);
+        assert!(gcs.metrics.report_text().contains("Unconfirmed cmds:   1"));
+    }
+
+    #[test]
+    fn tail_drop_rate_cap_sheds_the_excess_and_refills_each_second() {
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        assert!(gcs.set_rate_cap(0, ShedPolicy::TailDrop).is_err());
+        gcs.set_rate_cap(2, ShedPolicy::TailDrop).unwrap();
+        let mut t = nominal();
+        let start = Instant::now();
+        for seq in 0..5 {
+            t.seq = seq;
+            gcs.handle_datagram(&t.to_bytes(), start);
+        }
+        assert_eq!(gcs.metrics.valid_packets, 2);
+        assert_eq!(gcs.metrics.packets_shed, 3);
+        // A new second refills the budget.
+        t.seq = 5;
+        gcs.handle_datagram(&t.to_bytes(), start + Duration::from_secs(1));
+        assert_eq!(gcs.metrics.valid_packets, 3);
+        assert!(gcs.metrics.report_text().contains("Shed (rate cap):    3"));
+    }
+
+    #[test]
+    fn sampled_shedding_spreads_the_budget_across_the_window() {
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        gcs.set_rate_cap(2, ShedPolicy::Sample).unwrap();
+        let mut t = nominal();
+        let start = Instant::now();
+        // First window: six arrivals teach the limiter the flood rate.
+        for seq in 0..6 {
+            t.seq = seq;
+            gcs.handle_datagram(&t.to_bytes(), start);
+        }
+        // Second window: stride 6/2 = 3 keeps the 1st and 4th arrivals
+        // instead of the first two back to back.
+        let second = start + Duration::from_secs(1);
+        for seq in 10..16 {
+            t.seq = seq;
+            gcs.handle_datagram(&t.to_bytes(), second);
+        }
+        assert_eq!(gcs.last_seq, Some(13));
+    }
+
+    #[test]
+    fn default_policy_commands_safe_mode_once_per_low_battery_episode() {
+        let ocs = UdpSocket::bind("127.0.0.1:0").unwrap();
+        ocs.set_read_timeout(Some(Duration::from_millis(500))).unwrap();
+        let mut uplink = CommandSender::new(ocs.local_addr().unwrap()).unwrap();
+        uplink.set_reliability(20, 0); // no ack coming; fail fast
+        let mut t = nominal();
+        t.battery_mv = 1_000;
+        let event = FaultEvent { fault: Fault::LowBattery, telemetry: t };
+        DefaultPolicy.on_fault(&event, &mut uplink);
+        let mut buf = [0u8; 128];
+        let (len, _) = ocs.recv_from(&mut buf).unwrap();
+        let line = std::str::from_utf8(&buf[..len]).unwrap();
+        assert!(line.ends_with("SET_MODE safe"), "unexpected uplink {line:?}");
+        // The no-op policy observes the same event without commanding.
+        ocs.set_read_timeout(Some(Duration::from_millis(100))).unwrap();
+        NoOpPolicy.on_fault(&event, &mut uplink);
+        assert!(ocs.recv_from(&mut buf).is_err(), "NoOpPolicy must not send");
+    }
+
+    #[test]
+    fn self_check_round_trips_a_loopback_frame() {
+        let gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        gcs.self_check().expect("loopback probe should decode");
+        // The probe never touches the metrics.
+        assert_eq!(gcs.metrics.packets_received, 0);
+    }
+
+    #[test]
+    fn boot_counter_change_counts_a_restart_and_resets_the_seq_baseline() {
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        let mut t = nominal();
+        t.seq = 5;
+        gcs.handle_datagram(&t.to_bytes_v2(), Instant::now());
+        // New boot restarts the sequence at 0: one restart, zero packets lost.
+        t.boot_id = 1;
+        t.seq = 0;
+        gcs.handle_datagram(&t.to_bytes_v2(), Instant::now());
+        assert_eq!(gcs.metrics.ocs_restarts, 1);
+        assert_eq!(gcs.metrics.packets_lost, 0);
+        assert!(gcs.metrics.report_text().contains("OCS restarts:"));
+    }
+
+    #[test]
+    fn boot_counter_wrap_still_counts_as_a_restart() {
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        let mut t = nominal();
+        t.boot_id = 255;
+        gcs.handle_datagram(&t.to_bytes_v2(), Instant::now());
+        t.boot_id = 0;
+        t.seq += 1;
+        gcs.handle_datagram(&t.to_bytes_v2(), Instant::now());
+        assert_eq!(gcs.metrics.ocs_restarts, 1);
+    }
+
+    #[test]
+    fn non_positive_rate_limits_are_rejected() {
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        let err = gcs
+            .set_rate_of_change_limits(RateOfChangeLimits {
+                temperature: 0.0,
+                battery: 500.0,
+                antenna: 20.0,
+            })
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn default_alert_thresholds_match_the_single_limits() {
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        let mut t = nominal();
+        t.battery_mv = 9_000; // below the default 10 V low limit
+        gcs.handle_datagram(&t.to_bytes(), Instant::now());
+        t.seq = 1;
+        gcs.handle_datagram(&t.to_bytes(), Instant::now());
+        // Both tiers collapse onto the limit, so the breach alarms directly
+        // (no separate warn stage) and only once per episode.
+        assert_eq!(gcs.metrics.alarm_episodes["battery-low"], 1);
+        assert!(gcs.metrics.warn_episodes.is_empty());
+    }
+
+    #[test]
+    fn misordered_alert_thresholds_are_rejected() {
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        // Rising field with clear above warn: no hysteresis ordering.
+        let err = gcs
+            .set_alert_threshold(
+                AlertField::TemperatureHigh,
+                FieldThreshold {
+                    warn: 80.0,
+                    alarm: 100.0,
+                    clear: 90.0,
+                },
+            )
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        // The mirrored ordering is what a falling field expects.
+        gcs.set_alert_threshold(
+            AlertField::BatteryLow,
+            FieldThreshold {
+                warn: 10_500.0,
+                alarm: 9_800.0,
+                clear: 11_000.0,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn forwarding_tees_valid_frames_to_the_downstream_address() {
+        let downstream = UdpSocket::bind(("127.0.0.1", 0)).expect("bind downstream");
+        downstream
+            .set_read_timeout(Some(Duration::from_secs(2)))
+            .unwrap();
+        let addr = downstream.local_addr().unwrap();
+
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        gcs.set_forward(&addr.to_string()).expect("forward setup");
+
+        let frame = nominal().to_bytes();
+        gcs.handle_datagram(&frame, Instant::now());
+        // An invalid datagram must not be forwarded.
+        gcs.handle_datagram(&[0u8; 4], Instant::now());
+
+        let mut buf = [0u8; 64];
+        let len = downstream.recv(&mut buf).expect("forwarded frame");
+        assert_eq!(&buf[..len], &frame[..]);
+
+        // The worker counts asynchronously; wait for the send to land.
+        let deadline = Instant::now() + Duration::from_secs(2);
+        loop {
+            gcs.sync_forward_stats();
+            if gcs.metrics.forwarded_packets == 1 || Instant::now() > deadline {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(gcs.metrics.forwarded_packets, 1);
+        assert_eq!(gcs.metrics.forward_errors, 0);
+        assert_eq!(gcs.metrics.forward_queue_drops, 0);
+    }
+
+    #[test]
+    fn alarm_registry_tracks_episodes_occurrences_and_live_state() {
+        let mut alarms = AlarmRegistry::new();
+        assert_eq!(alarms.active_count(), 0);
+        assert!(alarms.get("fault").is_none());
+
+        // Two raises inside one episode: occurrences count both, episodes one.
+        alarms.raise("fault");
+        alarms.raise("fault");
+        let state = alarms.get("fault").unwrap();
+        assert!(state.active);
+        assert_eq!((state.episodes, state.occurrences), (1, 2));
+        assert_eq!(alarms.active_count(), 1);
+
+        // Clearing ends the episode; re-raising starts a second one.
+        alarms.clear("fault");
+        assert!(!alarms.get("fault").unwrap().active);
+        alarms.raise("fault");
+        assert_eq!(alarms.get("fault").unwrap().episodes, 2);
+
+        // Momentary alarms never stay active but keep full history.
+        alarms.pulse("jitter");
+        alarms.pulse("jitter");
+        let jitter = alarms.get("jitter").unwrap();
+        assert!(!jitter.active);
+        assert_eq!((jitter.episodes, jitter.occurrences), (2, 2));
+        assert!(jitter.last_active.is_some());
+
+        // Clearing an alarm that never fired records nothing.
+        alarms.clear("rate-anomaly");
+        assert!(alarms.get("rate-anomaly").is_none());
+    }
+
+    #[test]
+    fn watchdog_transitions_mirror_into_the_alarm_registry() {
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        gcs.set_edge_streak_limit(3);
+        // Identical packets would also trip the stuck-sensor watchdog; keep
+        // this test about the fault and streak alarms.
+        gcs.set_stuck_limit(0);
+        let mut t = nominal();
+        t.temperature = 150;
+        for seq in 0..5 {
+            t.seq = seq;
+            gcs.handle_datagram(&t.to_bytes(), Instant::now());
+        }
+        // The overtemperature fault, the default temperature alert and the
+        // sustained-edge streak are all live.
+        assert!(gcs.alarms.get("fault").unwrap().active);
+        assert!(gcs.alarms.get("field-alert").unwrap().active);
+        assert!(gcs.alarms.get("sustained-edge").unwrap().active);
+        assert_eq!(gcs.alarms.active_count(), 3);
+        assert_eq!(
+            gcs.alarms.active_names(),
+            vec!["fault", "field-alert", "sustained-edge"]
+        );
+        assert!(gcs.any_alarm_active, "onset edge should be latched");
+
+        // A normal packet ends both episodes but keeps their history.
+        let mut normal = nominal();
+        normal.seq = 5;
+        gcs.handle_datagram(&normal.to_bytes(), Instant::now());
+        assert_eq!(gcs.alarms.active_count(), 0);
+        assert!(!gcs.any_alarm_active, "clearance edge should be latched");
+        let edge = gcs.alarms.get("sustained-edge").unwrap();
+        assert!(!edge.active);
+        assert_eq!(edge.episodes, 1);
+        assert!(gcs.alarms.get("fault").unwrap().episodes >= 1);
+    }
+
+    #[test]
+    fn sustained_edge_fires_past_limit_and_clears_on_normal_packet() {
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        gcs.set_edge_streak_limit(3);
+        let mut t = nominal();
+        t.temperature = 150;
+        for seq in 0..5 {
+            t.seq = seq;
+            gcs.handle_datagram(&t.to_bytes(), Instant::now());
+        }
+        assert_eq!(gcs.metrics.sustained_edge_episodes, 1);
+        assert_eq!(gcs.metrics.max_edge_streak, 5);
+
+        // One normal packet clears the alarm; a fresh streak past the limit
+        // counts as a second episode.
+        let mut normal = nominal();
+        normal.seq = 5;
+        gcs.handle_datagram(&normal.to_bytes(), Instant::now());
+        assert!(!gcs.sustained_edge_active);
+        for seq in 6..10 {
+            t.seq = seq;
+            gcs.handle_datagram(&t.to_bytes(), Instant::now());
+        }
+        assert_eq!(gcs.metrics.sustained_edge_episodes, 2);
+    }
+
+    #[test]
+    fn edge_streak_below_limit_never_alarms() {
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        gcs.set_edge_streak_limit(10);
+        let mut t = nominal();
+        t.battery_mv = 0;
+        for seq in 0..5 {
+            t.seq = seq;
+            gcs.handle_datagram(&t.to_bytes(), Instant::now());
+        }
+        assert_eq!(gcs.metrics.sustained_edge_episodes, 0);
+        assert_eq!(gcs.metrics.max_edge_streak, 5);
+    }
+
+    #[test]
+    fn scheduled_gap_extends_the_contact_timeout() {
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        assert_eq!(gcs.contact_timeout_ms(), LOSS_OF_CONTACT_TIMEOUT_MS);
+        gcs.set_duty_cycle(20_000);
+        assert_eq!(gcs.contact_timeout_ms(), LOSS_OF_CONTACT_TIMEOUT_MS + 20_000);
+        gcs.set_duty_cycle(0);
+        assert_eq!(gcs.contact_timeout_ms(), LOSS_OF_CONTACT_TIMEOUT_MS);
+    }
+
+    #[test]
+    fn modal_length_shift_raises_format_change_once() {
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        let mut t = nominal();
+        for seq in 0..5 {
+            t.seq = seq;
+            gcs.handle_datagram(&t.to_bytes(), Instant::now());
+        }
+        assert_eq!(gcs.metrics.format_changes, 0);
+
+        // The "upgraded" OCS pads every frame by four bytes; once the longer
+        // length dominates the distribution, the alarm fires exactly once.
+        for seq in 5..15 {
+            t.seq = seq;
+            let mut padded = t.to_bytes().to_vec();
+            padded.extend_from_slice(&[0u8; 4]);
+            gcs.handle_datagram(&padded, Instant::now());
+        }
+        assert_eq!(gcs.metrics.format_changes, 1);
+        assert_eq!(
+            gcs.metrics.modal_frame_length(),
+            Some((TELEMETRY_WIRE_SIZE + 4, 10))
+        );
+        assert_eq!(gcs.metrics.frame_lengths.len(), 2);
+    }
+
+    #[test]
+    fn control_report_command_returns_the_report() {
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        gcs.bind_control(0).expect("bind control port");
+        let control_addr = gcs.control.as_ref().unwrap().local_addr().unwrap();
+
+        let requester = UdpSocket::bind("127.0.0.1:0").expect("bind requester");
+        requester.send_to(b"REPORT", control_addr).unwrap();
+        // Give the datagram a moment to land before the nonblocking drain.
+        std::thread::sleep(Duration::from_millis(50));
+        gcs.poll_control();
+
+        let mut buf = [0u8; 4096];
+        requester
+            .set_read_timeout(Some(Duration::from_secs(2)))
+            .unwrap();
+        let (len, _) = requester.recv_from(&mut buf).unwrap();
+        let reply = String::from_utf8_lossy(&buf[..len]);
+        assert!(reply.starts_with("ACK REPORT"));
+        assert!(reply.contains("GCS Performance Report"));
+
+        requester.send_to(b"BOGUS", control_addr).unwrap();
+        std::thread::sleep(Duration::from_millis(50));
+        gcs.poll_control();
+        let (len, _) = requester.recv_from(&mut buf).unwrap();
+        assert!(String::from_utf8_lossy(&buf[..len]).starts_with("NAK"));
+    }
+
+    #[test]
+    fn expected_count_spans_sequence_range_including_wrap() {
+        let mut metrics = GCSPerformanceMetrics::new();
+        assert_eq!(metrics.expected_packets(), 0);
+        metrics.note_seq(10);
+        metrics.note_seq(14);
+        metrics.note_seq(12);
+        assert_eq!(metrics.expected_packets(), 5);
+
+        let mut wrapped = GCSPerformanceMetrics::new();
+        wrapped.note_seq(u32::MAX - 1);
+        wrapped.note_seq(2);
+        assert_eq!(wrapped.expected_packets(), 5);
+    }
+
+    #[test]
+    fn fault_episodes_count_transitions_not_packets() {
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        let mut hot = nominal();
+        hot.temperature = 150;
+        // Three hot packets, one normal, two hot: five occurrences but only
+        // two distinct episodes.
+        for seq in 0..3 {
+            hot.seq = seq;
+            gcs.handle_datagram(&hot.to_bytes(), Instant::now());
+        }
+        let mut normal = nominal();
+        normal.seq = 3;
+        gcs.handle_datagram(&normal.to_bytes(), Instant::now());
+        for seq in 4..6 {
+            hot.seq = seq;
+            gcs.handle_datagram(&hot.to_bytes(), Instant::now());
+        }
+        assert_eq!(gcs.metrics.faults_detected[&Fault::HighTemperature], 5);
+        assert_eq!(gcs.metrics.fault_episodes[&Fault::HighTemperature], 2);
+    }
+
+    #[test]
+    fn overlapping_faults_have_independent_episodes() {
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        let mut t = nominal();
+        t.temperature = 150;
+        gcs.handle_datagram(&t.to_bytes(), Instant::now());
+        // Battery collapses while the temperature fault persists: the new
+        // fault starts its own episode, the ongoing one does not re-count.
+        t.seq = 1;
+        t.battery_mv = 0;
+        gcs.handle_datagram(&t.to_bytes(), Instant::now());
+        assert_eq!(gcs.metrics.fault_episodes[&Fault::HighTemperature], 1);
+        assert_eq!(gcs.metrics.fault_episodes[&Fault::LowBattery], 1);
+    }
+
+    #[test]
+    fn sequence_restart_is_a_reset_not_massive_loss() {
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        gcs.set_warmup(0);
+        let mut t = nominal();
+        for seq in [5_000u32, 5_001, 5_002] {
+            t.seq = seq;
+            gcs.handle_datagram(&t.to_bytes(), Instant::now());
+        }
+        // Sender reboots and restarts the sequence at 0.
+        t.seq = 0;
+        gcs.handle_datagram(&t.to_bytes(), Instant::now());
+        assert_eq!(gcs.metrics.sender_resets, 1);
+        assert_eq!(gcs.metrics.packets_lost(), 0);
+        // Tracking resumes against the restarted sequence.
+        t.seq = 1;
+        gcs.handle_datagram(&t.to_bytes(), Instant::now());
+        assert_eq!(gcs.metrics.packets_lost(), 0);
+        assert_eq!(gcs.metrics.expected_packets(), 2);
+
+        // A small backward step is still plain reordering.
+        t.seq = 0;
+        gcs.handle_datagram(&t.to_bytes(), Instant::now());
+        assert_eq!(gcs.metrics.sender_resets, 1);
+        assert_eq!(gcs.metrics.out_of_order_packets, 1);
+    }
+
+    #[test]
+    fn custom_sink_receives_packets_and_faults() {
+        struct Recording(Arc<std::sync::Mutex<Vec<String>>>);
+        impl TelemetrySink for Recording {
+            fn on_packet(&mut self, packet: &PacketRecord) {
+                self.0.lock().unwrap().push(format!("packet {}", packet.telemetry.seq));
+            }
+            fn on_fault(&mut self, fault: Fault, telemetry: &Telemetry) {
+                self.0.lock().unwrap().push(format!("fault {} {}", fault.name(), telemetry.seq));
+            }
+            fn on_report(&mut self, _report: &str) {
+                self.0.lock().unwrap().push("report".to_string());
+            }
+        }
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        gcs.set_warmup(0);
+        gcs.set_sink(Box::new(Recording(Arc::clone(&events))));
+        let mut t = nominal();
+        gcs.handle_datagram(&t.to_bytes(), Instant::now());
+        t.seq = 1;
+        t.battery_mv = 9_000;
+        gcs.handle_datagram(&t.to_bytes(), Instant::now());
+        let events = events.lock().unwrap();
+        assert_eq!(events[0], "packet 0");
+        assert!(events.contains(&"fault LowBattery 1".to_string()));
+    }
+
+    #[test]
+    fn json_lines_sink_emits_one_object_per_event() {
+        let mut out = Vec::new();
+        {
+            let mut sink = JsonLinesSink::new(&mut out);
+            let t = nominal();
+            sink.on_packet(&PacketRecord {
+                telemetry: &t,
+                health: 100.0,
+                decode_latency_us: 42,
+                display_angle: 3,
+            });
+            sink.on_fault(Fault::LowBattery, &t);
+            sink.on_report("Packets received: 1\n");
+        }
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<_> = text.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("{\"type\":\"packet\",\"seq\":0,"));
+        assert!(lines[0].contains("\"mode\":null"));
+        assert!(lines[0].contains("\"decode_latency_us\":42"));
+        assert_eq!(lines[1], "{\"type\":\"fault\",\"fault\":\"LowBattery\",\"seq\":0}");
+        assert_eq!(lines[2], "{\"type\":\"report\",\"text\":\"Packets received: 1\\n\"}");
+    }
+
+    #[test]
+    fn percentiles_handle_empty_small_and_tailed_samples() {
+        let mut metrics = GCSPerformanceMetrics::new();
+        assert_eq!(metrics.decode_latency_percentile_us(95.0), 0);
+        assert_eq!(metrics.abs_jitter_percentile_us(99.0), 0);
+        // One sample is every percentile.
+        metrics.record_decode_latency(500);
+        assert_eq!(metrics.decode_latency_percentile_us(50.0), 500);
+        assert_eq!(metrics.decode_latency_percentile_us(99.0), 500);
+        // 1..=100 us: nearest-rank percentiles land on exact samples.
+        let mut metrics = GCSPerformanceMetrics::new();
+        for us in 1..=100u128 {
+            metrics.record_decode_latency(us);
+        }
+        assert_eq!(metrics.decode_latency_percentile_us(50.0), 50);
+        assert_eq!(metrics.decode_latency_percentile_us(95.0), 95);
+        assert_eq!(metrics.decode_latency_percentile_us(99.0), 99);
+        assert_eq!(metrics.decode_latency_percentile_us(100.0), 100);
+        // Jitter percentiles judge magnitude, not sign.
+        metrics.record_jitter(-900);
+        metrics.record_jitter(100);
+        assert_eq!(metrics.abs_jitter_percentile_us(99.0), 900);
+        let report = metrics.report_text();
+        assert!(report.contains("p50=50 p95=95 p99=99"));
+        assert!(report.contains("|Jitter| (us):"));
+    }
+
+    #[test]
+    fn counter_snapshots_diff_across_an_operation() {
+        let mut metrics = GCSPerformanceMetrics::new();
+        metrics.record_packet_received();
+        metrics.record_valid_packet();
+        metrics.record_decode_latency(100);
+        let before = metrics.counters();
+        assert_eq!(before.packets_received, 1);
+        assert_eq!(before.avg_decode_latency_us, 100.0);
+        metrics.record_packet_received();
+        metrics.record_invalid_packet();
+        metrics.record_packet_lost();
+        metrics.record_fault(Fault::LowBattery);
+        let after = metrics.counters();
+        assert_ne!(after, before);
+        assert_eq!(after.packets_received - before.packets_received, 1);
+        assert_eq!(after.invalid_packets - before.invalid_packets, 1);
+        assert_eq!(after.packets_lost - before.packets_lost, 1);
+        assert_eq!(after.faults_detected - before.faults_detected, 1);
+        assert_eq!(after.valid_packets, before.valid_packets);
+    }
+
+    #[test]
+    fn run_returns_once_the_shutdown_flag_is_set() {
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&shutdown);
+        let handle = std::thread::spawn(move || {
+            let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+            gcs.run(&flag);
+        });
+        std::thread::sleep(Duration::from_millis(250));
+        shutdown.store(true, Ordering::SeqCst);
+        // The 100 ms socket timeout bounds how long the loop can overshoot
+        // the flag; a hung join fails the test via the harness timeout.
+        handle.join().expect("receive loop exits cleanly");
+    }
+
+    #[test]
+    fn with_bind_addr_binds_the_requested_interface() {
+        let gcs = GCS::with_bind_addr("127.0.0.1:0", 1000).expect("bind loopback");
+        let addr = gcs.socket.local_addr().unwrap();
+        assert!(addr.ip().is_loopback());
+        // One host cannot exercise the cross-interface rejection, but it can
+        // verify loopback traffic still arrives on the restricted socket.
+        let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+        sender.send_to(&nominal().to_bytes(), addr).unwrap();
+        let mut buf = [0u8; 64];
+        let (len, _) = gcs.socket.recv_from(&mut buf).unwrap();
+        assert_eq!(len, TELEMETRY_WIRE_SIZE);
+    }
+
+    #[test]
+    fn replayed_datagram_counts_as_a_duplicate_not_loss() {
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        gcs.set_warmup(0);
+        let mut t = nominal();
+        for seq in [10u32, 11] {
+            t.seq = seq;
+            gcs.handle_datagram(&t.to_bytes(), Instant::now());
+        }
+        // The same frame again: booked separately, and the baseline stays
+        // put so the next in-order packet is not misread as a gap.
+        gcs.handle_datagram(&t.to_bytes(), Instant::now());
+        assert_eq!(gcs.metrics.duplicate_packets, 1);
+        assert_eq!(gcs.metrics.packets_lost(), 0);
+        t.seq = 12;
+        gcs.handle_datagram(&t.to_bytes(), Instant::now());
+        assert_eq!(gcs.metrics.packets_lost(), 0);
+    }
+
+    #[test]
+    fn gap_sizes_are_histogrammed_per_source() {
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        gcs.set_warmup(0);
+        let mut t = nominal();
+        // seq 0, 2, 3, 10: one gap of 1 and one gap of 6.
+        for seq in [0u32, 2, 3, 10] {
+            t.seq = seq;
+            gcs.handle_datagram(&t.to_bytes(), Instant::now());
+        }
+        let gaps = &gcs.metrics.seq_gaps["local"];
+        assert_eq!(gaps[&1], 1);
+        assert_eq!(gaps[&6], 1);
+        assert_eq!(gcs.metrics.packets_lost(), 7);
+        let report = gcs.metrics.report_text();
+        assert!(report.contains("Gap sizes by source:"));
+        assert!(report.contains("local: 1 x1, 6 x1"));
+    }
+
+    #[test]
+    fn exit_code_combines_severity_bits() {
+        let mut metrics = GCSPerformanceMetrics::new();
+        assert_eq!(metrics.exit_code(), 0);
+        metrics.record_decode_latency(DECODE_LATENCY_THRESHOLD_US + 1);
+        assert_eq!(metrics.exit_code(), EXIT_LATENCY_VIOLATION);
+        metrics.record_fault(Fault::LossOfContact);
+        assert_eq!(
+            metrics.exit_code(),
+            EXIT_LATENCY_VIOLATION | EXIT_LOSS_OF_CONTACT
+        );
+        metrics.record_fault_response(&[Fault::LowBattery], FAULT_RESPONSE_THRESHOLD_MS as f64 * 2.0);
+        assert_eq!(
+            metrics.exit_code(),
+            EXIT_LATENCY_VIOLATION | EXIT_LOSS_OF_CONTACT | EXIT_FAULT_RESPONSE_OVERRUN
+        );
+        // Ordinary faults that met their response budget stay nominal.
+        let mut clean = GCSPerformanceMetrics::new();
+        clean.record_fault(Fault::LowBattery);
+        clean.record_fault_response(&[Fault::LowBattery], 1.0);
+        assert_eq!(clean.exit_code(), 0);
+    }
+
+    #[test]
+    fn health_score_is_zero_at_band_centers_and_100_at_a_limit() {
+        let limits = Limits::default();
+        let weights = HealthWeights::default();
+        let mut t = nominal();
+        t.temperature = 25; // center of [-50, 100]
+        t.battery_mv = 12_500; // center of [10000, 15000]
+        t.antenna_angle = 0;
+        assert_eq!(health_score(&t, &limits, &weights), 0.0);
+
+        // An exactly-at-limit antenna scores 100 for its field; with equal
+        // weights and the other two fields centered, the composite is 100/3.
+        t.antenna_angle = limits.antenna_misalign_deg;
+        let score = health_score(&t, &limits, &weights);
+        assert!((score - 100.0 / 3.0).abs() < 1e-9);
+
+        // Beyond the limit the field contribution saturates at 100.
+        t.antenna_angle = 3 * limits.antenna_misalign_deg;
+        assert_eq!(health_score(&t, &limits, &weights), score);
+    }
+
+    #[test]
+    fn health_weights_bias_the_composite() {
+        let limits = Limits::default();
+        let mut t = nominal();
+        t.temperature = 25;
+        t.battery_mv = 12_500;
+        t.antenna_angle = limits.antenna_misalign_deg;
+        let antenna_only = HealthWeights {
+            temperature: 0.0,
+            battery: 0.0,
+            antenna: 2.0,
+        };
+        assert_eq!(health_score(&t, &limits, &antenna_only), 100.0);
+        let all_zero = HealthWeights {
+            temperature: 0.0,
+            battery: 0.0,
+            antenna: 0.0,
+        };
+        assert_eq!(health_score(&t, &limits, &all_zero), 0.0);
+    }
+
+    #[test]
+    fn health_extremes_are_tracked_per_session() {
+        let mut gcs = GCS::new(0, 1000).expect("bind ephemeral port");
+        let mut t = nominal();
+        t.temperature = 25;
+        t.battery_mv = 12_500;
+        t.antenna_angle = 0;
+        gcs.handle_datagram(&t.to_bytes(), Instant::now());
+        t.seq = 1;
+        t.antenna_angle = 45;
+        gcs.handle_datagram(&t.to_bytes(), Instant::now());
+        assert_eq!(gcs.metrics.health_samples, 2);
+        assert_eq!(gcs.metrics.health_min, 0.0);
+        assert!((gcs.metrics.health_max - 100.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn each_limit_violation_is_classified() {
+        let limits = Limits::default();
+        let mut t = nominal();
+        t.temperature = 150;
+        assert_eq!(classify_faults(&t, &limits), vec![Fault::HighTemperature]);
+        let mut t = nominal();
+        t.temperature = -90;
+        assert_eq!(classify_faults(&t, &limits), vec![Fault::LowTemperature]);
+        let mut t = nominal();
+        t.battery_mv = 0;
+        assert_eq!(classify_faults(&t, &limits), vec![Fault::LowBattery]);
+        let mut t = nominal();
+        t.battery_mv = 16_000;
+        assert_eq!(classify_faults(&t, &limits), vec![Fault::HighBattery]);
+        let mut t = nominal();
+        t.antenna_angle = -90;
+        assert_eq!(classify_faults(&t, &limits), vec![Fault::AntennaMisalignment]);
+    }
+}
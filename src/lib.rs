@@ -0,0 +1,30 @@
+//! Real-time telemetry downlink simulator.
+//!
+//! The crate models a spacecraft onboard computer system ([`mock_ocs::MockOCS`])
+//! that generates telemetry and downlinks it over UDP, and a ground control
+//! station ([`gcs::GCS`]) that receives, validates and monitors that telemetry
+//! against real-time constraints (decode latency, fault response time).
+
+#![allow(clippy::upper_case_acronyms)]
+
+pub mod angle;
+pub mod auth;
+pub mod batch;
+pub mod campaign;
+pub mod clock;
+pub mod config;
+pub mod error;
+pub mod expr;
+pub mod gcs;
+pub mod logfile;
+pub mod mock_ocs;
+pub mod reservoir;
+pub mod rng;
+pub mod scenario;
+pub mod status_stream;
+pub mod telemetry;
+pub mod transport;
+pub mod uplink;
+pub mod util;
+
+pub use error::{Error, Result};
@@ -0,0 +1,596 @@
+//! Rotating telemetry capture logs (CSV or JSONL).
+//!
+//! A multi-day capture at one packet per second would otherwise produce a
+//! single enormous file. The log writes to timestamped segment files
+//! (`telemetry-<unix_ms>.csv`) and rolls to a new segment when the current
+//! one exceeds a size or age limit, optionally pruning all but the last K
+//! segments. Rotation happens between rows, never inside one, so no row is
+//! split or duplicated at a boundary.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::telemetry::Telemetry;
+
+/// CSV header written at the top of every CSV segment.
+const CSV_HEADER: &str = "seq,timestamp_ms,temperature,battery_mv,antenna_angle,health";
+
+/// Extra CSV column appended when arrival-delta recording is on.
+const DELTA_COLUMN: &str = "arrival_delta_us";
+
+/// When to roll to a new segment, and how many old segments to keep.
+#[derive(Debug, Clone, Copy)]
+pub struct RotationPolicy {
+    /// Roll when the current segment exceeds this many bytes (0 = no limit).
+    pub max_bytes: u64,
+    /// Roll when the current segment is older than this (`None` = no limit).
+    pub max_age: Option<Duration>,
+    /// Keep only the newest K segments (`None` = keep everything).
+    pub keep: Option<usize>,
+}
+
+impl Default for RotationPolicy {
+    fn default() -> Self {
+        RotationPolicy {
+            max_bytes: 10 * 1024 * 1024,
+            max_age: None,
+            keep: None,
+        }
+    }
+}
+
+/// Row format of a capture log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    Csv,
+    Jsonl,
+}
+
+impl LogFormat {
+    /// Picks the format from a file extension (`.jsonl` => JSONL, else CSV).
+    pub fn from_path(path: &Path) -> LogFormat {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("jsonl") => LogFormat::Jsonl,
+            _ => LogFormat::Csv,
+        }
+    }
+}
+
+/// Rotating telemetry writer. The `path` given at creation names the capture
+/// (directory, stem and extension); actual segments get timestamped names
+/// derived from it.
+pub struct TelemetryLog {
+    dir: PathBuf,
+    stem: String,
+    ext: String,
+    format: LogFormat,
+    policy: RotationPolicy,
+    file: File,
+    current_path: PathBuf,
+    written_bytes: u64,
+    opened_at: Instant,
+    /// Disambiguates segments created within the same millisecond.
+    segment_counter: u64,
+    /// Append the measured inter-arrival delta as an extra column.
+    arrival_deltas: bool,
+}
+
+impl TelemetryLog {
+    pub fn create(path: &Path, format: LogFormat, policy: RotationPolicy) -> io::Result<Self> {
+        Self::create_with_deltas(path, format, policy, false)
+    }
+
+    /// Like [`TelemetryLog::create`], optionally recording each row's
+    /// inter-arrival delta (microseconds since the previous packet from the
+    /// same source) as an extra column for offline jitter analysis.
+    pub fn create_with_deltas(
+        path: &Path,
+        format: LogFormat,
+        policy: RotationPolicy,
+        arrival_deltas: bool,
+    ) -> io::Result<Self> {
+        let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        let stem = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("telemetry")
+            .to_string();
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or(match format {
+                LogFormat::Csv => "csv",
+                LogFormat::Jsonl => "jsonl",
+            })
+            .to_string();
+        let (file, current_path) = Self::new_segment(&dir, &stem, &ext, 1)?;
+        let mut log = TelemetryLog {
+            dir,
+            stem,
+            ext,
+            format,
+            policy,
+            file,
+            current_path,
+            written_bytes: 0,
+            opened_at: Instant::now(),
+            segment_counter: 1,
+            arrival_deltas,
+        };
+        log.start_segment_contents()?;
+        Ok(log)
+    }
+
+    /// Opens a fresh timestamped segment file.
+    fn new_segment(
+        dir: &Path,
+        stem: &str,
+        ext: &str,
+        counter: u64,
+    ) -> io::Result<(File, PathBuf)> {
+        let name = format!("{stem}-{:013}-{counter:04}.{ext}", unix_ms());
+        let path = dir.join(name);
+        let file = OpenOptions::new().create_new(true).append(true).open(&path)?;
+        Ok((file, path))
+    }
+
+    /// Path of the segment currently being written.
+    pub fn current_path(&self) -> &Path {
+        &self.current_path
+    }
+
+    /// Appends one sample with its composite health score, rolling to a new
+    /// segment first if the current one has hit a size or age limit.
+    pub fn log(&mut self, t: &Telemetry, health: f64) -> io::Result<()> {
+        self.log_with_arrival(t, health, None)
+    }
+
+    /// Like [`TelemetryLog::log`], carrying the measured inter-arrival delta.
+    /// `None` (the first packet from a source) writes a blank CSV field or a
+    /// JSON `null`; the delta is only emitted when the log was created with
+    /// arrival-delta recording on.
+    pub fn log_with_arrival(
+        &mut self,
+        t: &Telemetry,
+        health: f64,
+        arrival_delta_us: Option<u64>,
+    ) -> io::Result<()> {
+        if self.should_rotate() {
+            self.open_segment()?;
+        }
+        let mut line = match self.format {
+            LogFormat::Csv => format!(
+                "{},{},{},{},{},{health:.1}",
+                t.seq, t.timestamp_ms, t.temperature, t.battery_mv, t.antenna_angle
+            ),
+            LogFormat::Jsonl => format!(
+                "{{\"seq\":{},\"timestamp_ms\":{},\"temperature\":{},\"battery_mv\":{},\"antenna_angle\":{},\"health\":{health:.1}}}",
+                t.seq, t.timestamp_ms, t.temperature, t.battery_mv, t.antenna_angle
+            ),
+        };
+        if self.arrival_deltas {
+            match self.format {
+                LogFormat::Csv => match arrival_delta_us {
+                    Some(us) => line.push_str(&format!(",{us}")),
+                    None => line.push(','),
+                },
+                LogFormat::Jsonl => {
+                    line.pop(); // reopen the object to append the field
+                    match arrival_delta_us {
+                        Some(us) => line.push_str(&format!(",\"{DELTA_COLUMN}\":{us}}}")),
+                        None => line.push_str(&format!(",\"{DELTA_COLUMN}\":null}}")),
+                    }
+                }
+            }
+        }
+        writeln!(self.file, "{line}")?;
+        self.written_bytes += line.len() as u64 + 1;
+        Ok(())
+    }
+
+    fn should_rotate(&self) -> bool {
+        if self.policy.max_bytes > 0 && self.written_bytes >= self.policy.max_bytes {
+            return true;
+        }
+        if let Some(max_age) = self.policy.max_age {
+            if self.opened_at.elapsed() >= max_age {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Closes the current segment, opens a fresh timestamped one, writes the
+    /// CSV header, and prunes segments beyond the keep limit.
+    fn open_segment(&mut self) -> io::Result<()> {
+        self.segment_counter += 1;
+        let (file, path) = Self::new_segment(&self.dir, &self.stem, &self.ext, self.segment_counter)?;
+        self.file = file;
+        self.current_path = path;
+        self.written_bytes = 0;
+        self.opened_at = Instant::now();
+        self.start_segment_contents()
+    }
+
+    /// Per-segment preamble: the CSV header (JSONL segments have none), plus
+    /// pruning of segments beyond the keep limit.
+    fn start_segment_contents(&mut self) -> io::Result<()> {
+        if self.format == LogFormat::Csv {
+            let header = if self.arrival_deltas {
+                format!("{CSV_HEADER},{DELTA_COLUMN}")
+            } else {
+                CSV_HEADER.to_string()
+            };
+            writeln!(self.file, "{header}")?;
+            self.written_bytes += header.len() as u64 + 1;
+        }
+        self.prune();
+        Ok(())
+    }
+
+    /// Removes the oldest segments beyond the keep limit. Segment names sort
+    /// chronologically (fixed-width timestamp plus counter), so a name sort
+    /// is an age sort.
+    fn prune(&self) {
+        let Some(keep) = self.policy.keep else {
+            return;
+        };
+        let prefix = format!("{}-", self.stem);
+        let suffix = format!(".{}", self.ext);
+        let Ok(entries) = fs::read_dir(if self.dir.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            &self.dir
+        }) else {
+            return;
+        };
+        let mut segments: Vec<PathBuf> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| {
+                p.file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| n.starts_with(&prefix) && n.ends_with(&suffix))
+            })
+            .collect();
+        segments.sort();
+        while segments.len() > keep.max(1) {
+            let oldest = segments.remove(0);
+            if let Err(e) = fs::remove_file(&oldest) {
+                eprintln!("[LOG] failed to prune {}: {e}", oldest.display());
+            }
+        }
+    }
+}
+
+/// Wall-clock milliseconds since the Unix epoch, for segment names and
+/// rejection-log timestamps.
+fn unix_ms() -> u128 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0)
+}
+
+/// Default cap on rejection-log lines written per second.
+pub const DEFAULT_REJECTION_RATE: u32 = 20;
+
+/// Forensic log of rejected datagrams: one line per rejection carrying the
+/// reason, the sender, and the raw bytes in hex, so corruption can be
+/// diagnosed after the fact. The file gets the same timestamped name as a
+/// capture segment (`<stem>-<unix_ms>-0001.<ext>`). Writes are capped at
+/// `max_per_sec` lines per one-second window; a flood of bad packets
+/// collapses into a single `suppressed=N` summary line when the window
+/// rolls, so the log stays readable and bounded.
+pub struct RejectionLog {
+    file: File,
+    path: PathBuf,
+    /// Lines allowed per one-second window (0 = unlimited).
+    max_per_sec: u32,
+    window_start: Instant,
+    window_written: u32,
+    suppressed: u64,
+}
+
+impl RejectionLog {
+    pub fn create(path: &Path, max_per_sec: u32) -> io::Result<Self> {
+        let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        let stem = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("reject")
+            .to_string();
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("log")
+            .to_string();
+        let (file, path) = TelemetryLog::new_segment(&dir, &stem, &ext, 1)?;
+        Ok(RejectionLog {
+            file,
+            path,
+            max_per_sec,
+            window_start: Instant::now(),
+            window_written: 0,
+            suppressed: 0,
+        })
+    }
+
+    /// Path of the file being written.
+    pub fn current_path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Appends one rejection, or counts it as suppressed when the current
+    /// one-second window has used up its line budget. Any suppressed count is
+    /// flushed as one summary line when the window rolls.
+    pub fn log(&mut self, reason: &str, source: &str, data: &[u8]) -> io::Result<()> {
+        if self.window_start.elapsed() >= Duration::from_secs(1) {
+            if self.suppressed > 0 {
+                writeln!(self.file, "{} suppressed={} (rate limit)", unix_ms(), self.suppressed)?;
+                self.suppressed = 0;
+            }
+            self.window_start = Instant::now();
+            self.window_written = 0;
+        }
+        if self.max_per_sec > 0 && self.window_written >= self.max_per_sec {
+            self.suppressed += 1;
+            return Ok(());
+        }
+        self.window_written += 1;
+        let hex: String = data.iter().map(|b| format!("{b:02x}")).collect();
+        writeln!(
+            self.file,
+            "{} reason={reason} from={source} len={} bytes={hex}",
+            unix_ms(),
+            data.len()
+        )
+    }
+}
+
+/// Default bound on the async logging queue, in records.
+pub const DEFAULT_LOG_QUEUE_CAPACITY: usize = 1024;
+
+/// Background writer behind a bounded queue, so file I/O never runs on a hot
+/// path. The caller hands off ready-to-write records; a worker thread owns
+/// the actual log objects and drains the queue as fast as the disk allows.
+/// When the worker falls behind and the queue fills, new records are shed
+/// and counted rather than blocking the sender — the same policy the
+/// forwarding worker applies to frames. Dropping the logger closes the queue
+/// and joins the worker, so every record accepted before shutdown still
+/// reaches the file.
+pub struct AsyncLogger<T> {
+    queue: Option<mpsc::SyncSender<T>>,
+    handle: Option<thread::JoinHandle<()>>,
+    /// Records shed because the queue was full.
+    dropped: u64,
+}
+
+impl<T: Send + 'static> AsyncLogger<T> {
+    /// Spawns the worker thread. `write` consumes one record; it runs only
+    /// on the worker, so it may own file handles and block on I/O freely.
+    pub fn spawn<F>(capacity: usize, mut write: F) -> AsyncLogger<T>
+    where
+        F: FnMut(T) + Send + 'static,
+    {
+        let (tx, rx) = mpsc::sync_channel(capacity.max(1));
+        let handle = thread::spawn(move || {
+            while let Ok(record) = rx.recv() {
+                write(record);
+            }
+        });
+        AsyncLogger {
+            queue: Some(tx),
+            handle: Some(handle),
+            dropped: 0,
+        }
+    }
+
+    /// Queues one record without blocking. Returns `false` (and counts the
+    /// drop) when the queue is full.
+    pub fn log(&mut self, record: T) -> bool {
+        match self.queue.as_ref().map(|q| q.try_send(record)) {
+            Some(Ok(())) => true,
+            _ => {
+                self.dropped += 1;
+                false
+            }
+        }
+    }
+
+    /// Records shed so far because the worker could not keep up.
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+}
+
+impl<T> Drop for AsyncLogger<T> {
+    fn drop(&mut self) {
+        // Closing the queue ends the worker's recv loop after it drains
+        // whatever is still buffered; joining makes the flush observable.
+        self.queue = None;
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(seq: u32) -> Telemetry {
+        Telemetry {
+            seq,
+            timestamp_ms: seq as u64 * 1000,
+            temperature: 20,
+            battery_mv: 12_000,
+            antenna_angle: 0,
+            boot_id: 0,
+            mode: None,
+            command_echo: None,
+        }
+    }
+
+    fn temp_capture(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("logfile-test-{}-{name}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir.join("telemetry.csv")
+    }
+
+    fn segments(dir: &Path) -> Vec<PathBuf> {
+        let mut v: Vec<PathBuf> = fs::read_dir(dir).unwrap().flatten().map(|e| e.path()).collect();
+        v.sort();
+        v
+    }
+
+    #[test]
+    fn size_limit_rolls_without_losing_rows() {
+        let base = temp_capture("size");
+        let policy = RotationPolicy {
+            max_bytes: 120,
+            max_age: None,
+            keep: None,
+        };
+        let mut log = TelemetryLog::create(&base, LogFormat::Csv, policy).unwrap();
+        for seq in 0..20 {
+            log.log(&sample(seq), 0.0).unwrap();
+        }
+        drop(log);
+        let segs = segments(base.parent().unwrap());
+        assert!(segs.len() > 1, "expected rotation to produce segments");
+        let mut rows = 0;
+        for seg in &segs {
+            let text = fs::read_to_string(seg).unwrap();
+            let mut lines = text.lines();
+            assert_eq!(lines.next(), Some(CSV_HEADER));
+            rows += lines.count();
+        }
+        assert_eq!(rows, 20, "no rows lost or duplicated across rotation");
+        fs::remove_dir_all(base.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn keep_limit_prunes_oldest_segments() {
+        let base = temp_capture("keep");
+        let policy = RotationPolicy {
+            max_bytes: 60,
+            max_age: None,
+            keep: Some(2),
+        };
+        let mut log = TelemetryLog::create(&base, LogFormat::Csv, policy).unwrap();
+        for seq in 0..30 {
+            log.log(&sample(seq), 0.0).unwrap();
+        }
+        drop(log);
+        assert_eq!(segments(base.parent().unwrap()).len(), 2);
+        fs::remove_dir_all(base.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn arrival_delta_column_is_blank_first_then_numeric() {
+        let base = temp_capture("deltas");
+        let mut log = TelemetryLog::create_with_deltas(
+            &base,
+            LogFormat::Csv,
+            RotationPolicy::default(),
+            true,
+        )
+        .unwrap();
+        log.log_with_arrival(&sample(0), 0.0, None).unwrap();
+        log.log_with_arrival(&sample(1), 0.0, Some(1_003)).unwrap();
+        let text = fs::read_to_string(log.current_path()).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], format!("{CSV_HEADER},{DELTA_COLUMN}"));
+        assert!(lines[1].ends_with(",0.0,"), "first row has a blank delta: {}", lines[1]);
+        assert!(lines[2].ends_with(",0.0,1003"), "{}", lines[2]);
+        fs::remove_dir_all(base.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn jsonl_arrival_delta_is_null_first_then_numeric() {
+        let dir = std::env::temp_dir()
+            .join(format!("logfile-test-{}-jsonl-deltas", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let base = dir.join("telemetry.jsonl");
+        let mut log = TelemetryLog::create_with_deltas(
+            &base,
+            LogFormat::Jsonl,
+            RotationPolicy::default(),
+            true,
+        )
+        .unwrap();
+        log.log_with_arrival(&sample(0), 0.0, None).unwrap();
+        log.log_with_arrival(&sample(1), 0.0, Some(250)).unwrap();
+        let text = fs::read_to_string(log.current_path()).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert!(lines[0].ends_with(",\"arrival_delta_us\":null}"), "{}", lines[0]);
+        assert!(lines[1].ends_with(",\"arrival_delta_us\":250}"), "{}", lines[1]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn rejection_log_rate_limits_and_summarizes_the_overflow() {
+        let dir = std::env::temp_dir().join(format!("logfile-test-{}-reject", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let mut log = RejectionLog::create(&dir.join("reject.log"), 2).unwrap();
+        for _ in 0..5 {
+            log.log("bad-crc", "10.0.0.7:9000", &[0x01, 0xff]).unwrap();
+        }
+        std::thread::sleep(Duration::from_millis(1_050));
+        log.log("too-short", "10.0.0.7:9000", &[0x01]).unwrap();
+        let text = fs::read_to_string(log.current_path()).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 4, "2 entries + summary + 1 entry: {text}");
+        assert!(lines[0].contains("reason=bad-crc from=10.0.0.7:9000 len=2 bytes=01ff"));
+        assert!(lines[2].contains("suppressed=3"), "{}", lines[2]);
+        assert!(lines[3].contains("reason=too-short"), "{}", lines[3]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn async_logger_sheds_records_instead_of_blocking() {
+        use std::sync::{Arc, Mutex};
+
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&written);
+        let (started_tx, started_rx) = mpsc::channel();
+        let (gate_tx, gate_rx) = mpsc::channel::<()>();
+        let mut logger = AsyncLogger::spawn(1, move |record: u32| {
+            started_tx.send(()).unwrap();
+            let _ = gate_rx.recv(); // hold the worker mid-write
+            sink.lock().unwrap().push(record);
+        });
+
+        assert!(logger.log(1));
+        started_rx.recv().unwrap(); // worker is now stuck inside write(1)
+        assert!(logger.log(2), "one record fits in the queue");
+        assert!(!logger.log(3), "queue full: record shed, not blocked on");
+        assert_eq!(logger.dropped(), 1);
+
+        drop(gate_tx); // release the worker; drop drains and joins
+        drop(logger);
+        assert_eq!(*written.lock().unwrap(), vec![1, 2]);
+    }
+
+    #[test]
+    fn jsonl_rows_have_no_header() {
+        let dir = std::env::temp_dir().join(format!("logfile-test-{}-jsonl", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let base = dir.join("telemetry.jsonl");
+        assert_eq!(LogFormat::from_path(&base), LogFormat::Jsonl);
+        let mut log =
+            TelemetryLog::create(&base, LogFormat::Jsonl, RotationPolicy::default()).unwrap();
+        log.log(&sample(7), 12.5).unwrap();
+        let text = fs::read_to_string(log.current_path()).unwrap();
+        assert_eq!(
+            text,
+            "{\"seq\":7,\"timestamp_ms\":7000,\"temperature\":20,\"battery_mv\":12000,\"antenna_angle\":0,\"health\":12.5}\n"
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
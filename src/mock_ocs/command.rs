@@ -0,0 +1,1278 @@
+//! Uplink command handling for the OCS.
+//!
+//! Commands arrive as single-datagram ASCII lines on a dedicated command port
+//! (e.g. `SET_INTERVAL 500`). The receiver thread parses and applies them to
+//! [`OcsShared`], which the send loop reads every tick, and replies to the
+//! sender with `ACK ...` or `NAK <reason>`.
+
+use std::collections::VecDeque;
+use std::io;
+use std::net::UdpSocket;
+use std::sync::atomic::{AtomicBool, AtomicI32, AtomicU64, AtomicU8, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+
+use super::{CorruptField, LinkImpairment};
+use crate::telemetry::Telemetry;
+
+/// Bounds accepted by `SET_INTERVAL`, in milliseconds.
+pub const MIN_INTERVAL_MS: u64 = 1;
+pub const MAX_INTERVAL_MS: u64 = 60_000;
+
+/// Default capacity of the recent-telemetry ring buffer (`GET_HISTORY`).
+pub const DEFAULT_HISTORY_CAPACITY: usize = 64;
+
+/// Default probability of an edge-case packet in mixed mode
+/// (`SET_EDGE_RATIO`).
+pub const DEFAULT_EDGE_RATIO: f64 = 0.2;
+
+/// Capacity of the pending-command queue between the command socket reader
+/// and the executor; commands beyond this are dropped as `QueueFull`.
+pub const COMMAND_QUEUE_CAPACITY: usize = 32;
+
+/// Why an uplink command was dropped without being executed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DropReason {
+    QueueFull,
+    Unknown,
+    Malformed,
+    RateLimited,
+    Duplicate,
+    /// Valid command refused by onboard protection (e.g. autonomous safe).
+    Refused,
+    /// Lost to the simulated uplink impairment before reaching the queue.
+    Impaired,
+}
+
+/// Per-reason dropped-command counters.
+///
+/// Every drop site goes through [`OcsShared::drop_command`] (or the `nak`
+/// helper that wraps it) so the breakdown in the end-of-run report accounts
+/// for every command that did not execute.
+pub struct CommandDropCounters {
+    queue_full: AtomicU64,
+    unknown: AtomicU64,
+    malformed: AtomicU64,
+    rate_limited: AtomicU64,
+    duplicate: AtomicU64,
+    refused: AtomicU64,
+    impaired: AtomicU64,
+}
+
+impl CommandDropCounters {
+    pub fn new() -> Self {
+        CommandDropCounters {
+            queue_full: AtomicU64::new(0),
+            unknown: AtomicU64::new(0),
+            malformed: AtomicU64::new(0),
+            rate_limited: AtomicU64::new(0),
+            duplicate: AtomicU64::new(0),
+            refused: AtomicU64::new(0),
+            impaired: AtomicU64::new(0),
+        }
+    }
+
+    fn counter(&self, reason: DropReason) -> &AtomicU64 {
+        match reason {
+            DropReason::QueueFull => &self.queue_full,
+            DropReason::Unknown => &self.unknown,
+            DropReason::Malformed => &self.malformed,
+            DropReason::RateLimited => &self.rate_limited,
+            DropReason::Duplicate => &self.duplicate,
+            DropReason::Refused => &self.refused,
+            DropReason::Impaired => &self.impaired,
+        }
+    }
+
+    pub fn record(&self, reason: DropReason) {
+        self.counter(reason).fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub fn count(&self, reason: DropReason) -> u64 {
+        self.counter(reason).load(Ordering::SeqCst)
+    }
+
+    pub fn total(&self) -> u64 {
+        [
+            DropReason::QueueFull,
+            DropReason::Unknown,
+            DropReason::Malformed,
+            DropReason::RateLimited,
+            DropReason::Duplicate,
+            DropReason::Refused,
+            DropReason::Impaired,
+        ]
+        .iter()
+        .map(|&r| self.count(r))
+        .sum()
+    }
+
+    /// Prints the drop breakdown (only when anything was dropped).
+    pub fn report(&self) {
+        let total = self.total();
+        if total == 0 {
+            return;
+        }
+        println!("Commands dropped:   {total}");
+        for (label, reason) in [
+            ("QueueFull", DropReason::QueueFull),
+            ("Unknown", DropReason::Unknown),
+            ("Malformed", DropReason::Malformed),
+            ("RateLimited", DropReason::RateLimited),
+            ("Duplicate", DropReason::Duplicate),
+            ("Refused", DropReason::Refused),
+            ("Impaired", DropReason::Impaired),
+        ] {
+            let count = self.count(reason);
+            if count > 0 {
+                println!("  {label:<12} {count}");
+            }
+        }
+    }
+}
+
+impl Default for CommandDropCounters {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Operational mode of the spacecraft, settable via `SET_MODE`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Normal = 0,
+    Edge = 1,
+    Mixed = 2,
+    Safe = 3,
+}
+
+impl Mode {
+    pub fn from_u8(v: u8) -> Mode {
+        match v {
+            1 => Mode::Edge,
+            2 => Mode::Mixed,
+            3 => Mode::Safe,
+            _ => Mode::Normal,
+        }
+    }
+
+    /// Like [`Mode::from_u8`] but rejecting values outside the encoding, for
+    /// readers of wire bytes that must not mistake garbage for normal mode.
+    pub fn try_from_u8(v: u8) -> Option<Mode> {
+        (v <= Mode::Safe as u8).then(|| Mode::from_u8(v))
+    }
+
+    pub fn parse(s: &str) -> Option<Mode> {
+        match s {
+            "normal" => Some(Mode::Normal),
+            "edge" => Some(Mode::Edge),
+            "mixed" => Some(Mode::Mixed),
+            "safe" => Some(Mode::Safe),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Mode::Normal => "normal",
+            Mode::Edge => "edge",
+            Mode::Mixed => "mixed",
+            Mode::Safe => "safe",
+        }
+    }
+}
+
+/// What a pending checkpoint request asks the send loop to do with the
+/// named snapshot slot (`SNAPSHOT` captures, `RESTORE` rewinds).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotOp {
+    Save,
+    Load,
+}
+
+/// State shared between the command receiver and the send loop.
+///
+/// `interval_epoch` is bumped whenever `interval_ms` changes so the send loop
+/// knows to reset its drift-compensation baseline instead of bursting to
+/// catch up with deadlines computed under the old interval.
+pub struct OcsShared {
+    pub interval_ms: AtomicU64,
+    pub interval_epoch: AtomicU64,
+    pub mode: AtomicU8,
+    /// Edge-case variant to inject (`case + 1`; 0 means no injection).
+    pub inject_case: AtomicU8,
+    /// How many more packets the injected case should persist for.
+    pub inject_packets: AtomicU64,
+    /// Commanded antenna angle in degrees (`SET_ANTENNA`).
+    pub antenna_setpoint_deg: AtomicI32,
+    /// Actual antenna angle after slew limiting, published by the send loop.
+    pub antenna_actual_deg: AtomicI32,
+    /// `PAUSE`/`RESUME`: while set, the send loop transmits nothing.
+    pub paused: AtomicBool,
+    /// Pending `RESET`: 0 none, 1 reset keeping the sequence (`continue`),
+    /// 2 reset restarting the sequence at 0 (`restart`).
+    pub reset_pending: AtomicU8,
+    /// Set by onboard fault protection when the battery falls below the
+    /// configured floor; while latched, `SET_MODE` cannot leave safe mode.
+    pub auto_safe_latched: AtomicBool,
+    /// Sequence number the next outgoing packet will carry, published by the
+    /// send loop so `SEND_TELEMETRY` can ack the seq it was assigned.
+    pub next_seq: AtomicU64,
+    /// Id of the last accepted `ID=`-tagged command, echoed in version-4
+    /// telemetry as the in-band acknowledgment (0 = none yet).
+    pub last_command_id: AtomicU64,
+    /// One-shot exact values for the next packet (`SEND_TELEMETRY`):
+    /// `(temperature, battery_mv, antenna_angle)`.
+    injected_values: Mutex<Option<(i16, u16, i16)>>,
+    /// One-shot spike (`SPIKE`): drive one field to an exact value for a
+    /// single packet, then return it to the generator.
+    spike: Mutex<Option<(CorruptField, i32)>>,
+    /// One-shot checkpoint request (`SNAPSHOT`/`RESTORE`): capture or
+    /// restore the named full-state snapshot on the next tick.
+    snapshot_op: Mutex<Option<(SnapshotOp, String)>>,
+    /// Probability of an edge-case packet in mixed mode (`SET_EDGE_RATIO`),
+    /// stored as `f64` bits so the send loop can read it lock-free per tick.
+    edge_ratio_bits: AtomicU64,
+    /// Ring buffer of the most recently generated samples (`GET_HISTORY`).
+    history: Mutex<VecDeque<Telemetry>>,
+    history_capacity: usize,
+    /// Dropped-command accounting, shared by every drop site.
+    pub command_drops: CommandDropCounters,
+}
+
+impl OcsShared {
+    pub fn new(interval_ms: u64, mode: Mode) -> Self {
+        Self::with_history_capacity(interval_ms, mode, DEFAULT_HISTORY_CAPACITY)
+    }
+
+    /// Like [`OcsShared::new`] with an explicit history ring-buffer size.
+    pub fn with_history_capacity(interval_ms: u64, mode: Mode, history_capacity: usize) -> Self {
+        OcsShared {
+            interval_ms: AtomicU64::new(interval_ms),
+            interval_epoch: AtomicU64::new(0),
+            mode: AtomicU8::new(mode as u8),
+            inject_case: AtomicU8::new(0),
+            inject_packets: AtomicU64::new(0),
+            antenna_setpoint_deg: AtomicI32::new(0),
+            antenna_actual_deg: AtomicI32::new(0),
+            paused: AtomicBool::new(false),
+            reset_pending: AtomicU8::new(0),
+            auto_safe_latched: AtomicBool::new(false),
+            next_seq: AtomicU64::new(0),
+            last_command_id: AtomicU64::new(0),
+            injected_values: Mutex::new(None),
+            spike: Mutex::new(None),
+            snapshot_op: Mutex::new(None),
+            edge_ratio_bits: AtomicU64::new(DEFAULT_EDGE_RATIO.to_bits()),
+            history: Mutex::new(VecDeque::with_capacity(history_capacity)),
+            history_capacity: history_capacity.max(1),
+            command_drops: CommandDropCounters::new(),
+        }
+    }
+
+    /// Central drop-accounting entry point: counts the drop by reason.
+    pub fn drop_command(&self, reason: DropReason) {
+        self.command_drops.record(reason);
+    }
+
+    /// Appends a generated sample to the bounded history ring buffer.
+    pub fn push_history(&self, t: Telemetry) {
+        let mut history = self.history.lock().unwrap();
+        if history.len() == self.history_capacity {
+            history.pop_front();
+        }
+        history.push_back(t);
+    }
+
+    /// The last `n` generated samples, oldest first.
+    pub fn recent_history(&self, n: usize) -> Vec<Telemetry> {
+        let history = self.history.lock().unwrap();
+        history.iter().rev().take(n).rev().copied().collect()
+    }
+
+    /// Queues exact field values for the next outgoing packet, replacing any
+    /// injection not yet consumed.
+    pub fn inject_telemetry(&self, temperature: i16, battery_mv: u16, antenna_angle: i16) {
+        *self.injected_values.lock().unwrap() = Some((temperature, battery_mv, antenna_angle));
+    }
+
+    /// Consumes the pending one-shot injection, if any.
+    pub fn take_injected(&self) -> Option<(i16, u16, i16)> {
+        self.injected_values.lock().unwrap().take()
+    }
+
+    /// Queues a single-packet spike driving `field` to exactly `value`,
+    /// replacing any spike not yet consumed.
+    pub fn request_spike(&self, field: CorruptField, value: i32) {
+        *self.spike.lock().unwrap() = Some((field, value));
+    }
+
+    /// Consumes the pending one-shot spike, if any.
+    pub fn take_spike(&self) -> Option<(CorruptField, i32)> {
+        self.spike.lock().unwrap().take()
+    }
+
+    /// Queues a snapshot capture or restore for the send loop, replacing any
+    /// request not yet consumed.
+    pub fn request_snapshot(&self, op: SnapshotOp, name: String) {
+        *self.snapshot_op.lock().unwrap() = Some((op, name));
+    }
+
+    /// Consumes the pending snapshot request, if any.
+    pub fn take_snapshot_op(&self) -> Option<(SnapshotOp, String)> {
+        self.snapshot_op.lock().unwrap().take()
+    }
+
+    /// Applies a new send interval and signals the send loop to re-baseline.
+    pub fn set_interval(&self, interval_ms: u64) {
+        self.interval_ms.store(interval_ms, Ordering::SeqCst);
+        self.interval_epoch.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// The current mixed-mode edge-case probability.
+    pub fn edge_ratio(&self) -> f64 {
+        f64::from_bits(self.edge_ratio_bits.load(Ordering::SeqCst))
+    }
+
+    /// Sets the mixed-mode edge-case probability; the send loop picks the
+    /// new value up on the very next packet.
+    pub fn set_edge_ratio(&self, ratio: f64) {
+        self.edge_ratio_bits
+            .store(ratio.clamp(0.0, 1.0).to_bits(), Ordering::SeqCst);
+    }
+}
+
+/// Machine-readable outcome of one command, stored on the
+/// [`CommandOutcome`] and used to build the wire reply. `Rejected` is a
+/// command the OCS never accepted (unknown verb, bad syntax, out-of-range
+/// value); `Failed` is a well-formed command it could not carry out (e.g.
+/// refused by onboard fault protection); `Deferred` is accepted now but
+/// applied by the send loop on a later tick (`RESET`, `SEND_TELEMETRY`,
+/// `INJECT_FAULT`), so the ack does not yet prove the effect.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResultCode {
+    Ok,
+    Rejected(String),
+    Failed(String),
+    Deferred,
+}
+
+impl ResultCode {
+    /// Whether the command was accepted (immediately applied or deferred).
+    pub fn is_ack(&self) -> bool {
+        matches!(self, ResultCode::Ok | ResultCode::Deferred)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ResultCode::Ok => "ok",
+            ResultCode::Rejected(_) => "rejected",
+            ResultCode::Failed(_) => "failed",
+            ResultCode::Deferred => "deferred",
+        }
+    }
+}
+
+/// Structured result of executing one command line. The wire reply is
+/// derived from it (never the other way round), so programmatic consumers
+/// read the code while the socket still sees the familiar `ACK`/`NAK` text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandOutcome {
+    /// Echoed reliability token, when the line carried `ID=n`.
+    pub id: Option<u32>,
+    /// Reply payload after the `ACK` verb; empty for rejections, whose text
+    /// lives in the code's reason.
+    pub detail: String,
+    pub code: ResultCode,
+}
+
+impl CommandOutcome {
+    /// Renders the reply the OCS sends on the wire for this outcome.
+    pub fn wire_reply(&self) -> String {
+        let body = match &self.code {
+            ResultCode::Ok | ResultCode::Deferred => format!("ACK {}", self.detail),
+            ResultCode::Rejected(reason) | ResultCode::Failed(reason) => format!("NAK {reason}"),
+        };
+        match self.id {
+            Some(id) => format!("ID={id} {body}"),
+            None => body,
+        }
+    }
+}
+
+/// Outcome of a command applied immediately.
+fn ok(detail: impl Into<String>) -> CommandOutcome {
+    CommandOutcome {
+        id: None,
+        detail: detail.into(),
+        code: ResultCode::Ok,
+    }
+}
+
+/// Outcome of a command accepted now but applied on a later send-loop tick.
+fn deferred(detail: impl Into<String>) -> CommandOutcome {
+    CommandOutcome {
+        id: None,
+        detail: detail.into(),
+        code: ResultCode::Deferred,
+    }
+}
+
+/// Counts a dropped command against `reason` and returns the rejection
+/// outcome, so a rejection can never be reported without also being
+/// accounted for.
+fn reject(shared: &OcsShared, reason: DropReason, text: impl Into<String>) -> CommandOutcome {
+    shared.drop_command(reason);
+    CommandOutcome {
+        id: None,
+        detail: String::new(),
+        code: ResultCode::Rejected(text.into()),
+    }
+}
+
+/// Like [`reject`] for a well-formed command the OCS could not carry out.
+fn fail(shared: &OcsShared, reason: DropReason, text: impl Into<String>) -> CommandOutcome {
+    shared.drop_command(reason);
+    CommandOutcome {
+        id: None,
+        detail: String::new(),
+        code: ResultCode::Failed(text.into()),
+    }
+}
+
+/// Handles one raw command line, honouring an optional `ID=n` reliability
+/// token: the id is stripped before dispatch and carried on the outcome so
+/// the reply echoes it and the GCS can match acks to retransmitted commands.
+pub fn execute_line(shared: &OcsShared, line: &str) -> CommandOutcome {
+    if let Some(rest) = line.strip_prefix("ID=") {
+        if let Some((id, body)) = rest.split_once(' ') {
+            if let Ok(id) = id.parse::<u32>() {
+                let mut outcome = execute_command(shared, body.trim());
+                outcome.id = Some(id);
+                if outcome.code.is_ack() {
+                    // Remember the id for the telemetry command-echo field;
+                    // rejected commands were not processed and leave it.
+                    shared.last_command_id.store(id as u64, Ordering::SeqCst);
+                }
+                return outcome;
+            }
+        }
+        return reject(shared, DropReason::Malformed, "malformed ID token");
+    }
+    execute_command(shared, line)
+}
+
+/// String-reply convenience over [`execute_line`].
+pub fn handle_line(shared: &OcsShared, line: &str) -> String {
+    execute_line(shared, line).wire_reply()
+}
+
+/// Default priority for commands that carry no `PRIO=` token.
+pub const DEFAULT_COMMAND_PRIORITY: u8 = 0;
+
+/// Splits an optional `PRIO=n` token off a command line, returning the line
+/// without the token and the explicit priority if one was given. `Err` carries
+/// the NAK text for a token whose value doesn't parse as `0..=255`.
+pub fn split_priority(line: &str) -> Result<(String, Option<u8>), String> {
+    let mut priority = None;
+    let mut rest = Vec::new();
+    for token in line.split_whitespace() {
+        match token.strip_prefix("PRIO=") {
+            Some(value) => match value.parse::<u8>() {
+                Ok(p) => priority = Some(p),
+                Err(_) => return Err(format!("NAK PRIO {value:?} outside 0..=255")),
+            },
+            None => rest.push(token),
+        }
+    }
+    Ok((rest.join(" "), priority))
+}
+
+/// Applies one command line to the shared state and returns the structured
+/// outcome. An explicit `PRIO=n` token is stripped before dispatch and
+/// echoed back on the ACK so the ground can confirm the accepted priority.
+pub fn execute_command(shared: &OcsShared, line: &str) -> CommandOutcome {
+    let (line, priority) = match split_priority(line) {
+        Ok(v) => v,
+        Err(msg) => {
+            let reason = msg.strip_prefix("NAK ").unwrap_or(&msg).to_string();
+            return reject(shared, DropReason::Malformed, reason);
+        }
+    };
+    let mut outcome = dispatch_command(shared, &line);
+    if let Some(p) = priority {
+        if outcome.code.is_ack() {
+            outcome.detail.push_str(&format!(" PRIO={p}"));
+        }
+    }
+    outcome
+}
+
+/// String-reply convenience over [`execute_command`].
+pub fn process_command(shared: &OcsShared, line: &str) -> String {
+    execute_command(shared, line).wire_reply()
+}
+
+/// Dispatches one command line (already stripped of protocol tokens).
+fn dispatch_command(shared: &OcsShared, line: &str) -> CommandOutcome {
+    let mut parts = line.split_whitespace();
+    match parts.next() {
+        Some("SET_INTERVAL") => match parts.next().map(str::parse::<u64>) {
+            Some(Ok(ms)) if (MIN_INTERVAL_MS..=MAX_INTERVAL_MS).contains(&ms) => {
+                shared.set_interval(ms);
+                ok(format!("SET_INTERVAL {ms}"))
+            }
+            Some(Ok(ms)) => reject(
+                shared,
+                DropReason::Malformed,
+                format!("SET_INTERVAL {ms} out of range {MIN_INTERVAL_MS}..={MAX_INTERVAL_MS}"),
+            ),
+            _ => reject(
+                shared,
+                DropReason::Malformed,
+                "SET_INTERVAL missing or invalid milliseconds",
+            ),
+        },
+        Some("SET_MODE") => match parts.next().and_then(Mode::parse) {
+            Some(mode) => {
+                // Onboard fault protection wins: while the autonomous-safe
+                // latch is set, the ground cannot command the OCS out of safe
+                // mode until the battery recovers past the clear threshold.
+                if mode != Mode::Safe && shared.auto_safe_latched.load(Ordering::SeqCst) {
+                    return fail(
+                        shared,
+                        DropReason::Refused,
+                        "SET_MODE refused: autonomous safe latched until battery recovers",
+                    );
+                }
+                shared.mode.store(mode as u8, Ordering::SeqCst);
+                ok(format!("SET_MODE {}", mode.name()))
+            }
+            None => reject(
+                shared,
+                DropReason::Malformed,
+                "SET_MODE expected normal|edge|mixed|safe",
+            ),
+        },
+        Some("SET_EDGE_RATIO") => match parts.next().map(str::parse::<f64>) {
+            Some(Ok(ratio)) if (0.0..=1.0).contains(&ratio) => {
+                shared.set_edge_ratio(ratio);
+                ok(format!("SET_EDGE_RATIO {ratio}"))
+            }
+            Some(Ok(ratio)) => reject(
+                shared,
+                DropReason::Malformed,
+                format!("SET_EDGE_RATIO {ratio} out of range 0..=1"),
+            ),
+            _ => reject(
+                shared,
+                DropReason::Malformed,
+                "SET_EDGE_RATIO missing or invalid ratio",
+            ),
+        },
+        Some("GET_HISTORY") => match parts.next().map(str::parse::<usize>) {
+            Some(Ok(n)) if n > 0 => {
+                let samples = shared.recent_history(n);
+                let mut reply = format!("HISTORY {}", samples.len());
+                for t in samples {
+                    reply.push_str(&format!(
+                        "\nseq={} ts={} temp={} batt={} ant={}",
+                        t.seq, t.timestamp_ms, t.temperature, t.battery_mv, t.antenna_angle
+                    ));
+                }
+                ok(reply)
+            }
+            _ => reject(
+                shared,
+                DropReason::Malformed,
+                "GET_HISTORY expected a positive count",
+            ),
+        },
+        Some("INJECT_FAULT") => {
+            let case = match parts.next() {
+                Some("temp") => 0u8,
+                Some("cold") => 1,
+                Some("battery") => 2,
+                Some("overvolt") => 3,
+                Some("antenna") => 4,
+                _ => {
+                    return reject(
+                        shared,
+                        DropReason::Malformed,
+                        "INJECT_FAULT expected temp|cold|battery|overvolt|antenna",
+                    )
+                }
+            };
+            let duration_ms: u64 = match parts.next() {
+                None => 0,
+                Some(ms) => match ms.parse() {
+                    Ok(v) => v,
+                    Err(_) => {
+                        return reject(shared, DropReason::Malformed, "INJECT_FAULT invalid duration")
+                    }
+                },
+            };
+            // Convert the duration to a packet count at the current interval
+            // (at least one packet so a zero-duration inject still fires).
+            let interval = shared.interval_ms.load(Ordering::SeqCst).max(1);
+            let packets = (duration_ms / interval).max(1);
+            shared.inject_case.store(case + 1, Ordering::SeqCst);
+            shared.inject_packets.store(packets, Ordering::SeqCst);
+            deferred(format!("INJECT_FAULT case={case} packets={packets}"))
+        }
+        Some("SPIKE") => {
+            let Some(field) = parts.next().and_then(CorruptField::parse) else {
+                return reject(
+                    shared,
+                    DropReason::Malformed,
+                    "SPIKE expected temp|battery|antenna",
+                );
+            };
+            let value: i64 = match parts.next().map(str::parse) {
+                Some(Ok(v)) => v,
+                _ => {
+                    return reject(shared, DropReason::Malformed, "SPIKE missing or invalid value")
+                }
+            };
+            let in_range = match field {
+                CorruptField::Battery => (0..=u16::MAX as i64).contains(&value),
+                _ => (i16::MIN as i64..=i16::MAX as i64).contains(&value),
+            };
+            if !in_range {
+                return reject(
+                    shared,
+                    DropReason::Malformed,
+                    format!("SPIKE {} {value} outside the wire range", field.name()),
+                );
+            }
+            shared.request_spike(field, value as i32);
+            deferred(format!("SPIKE {}={value} for one packet", field.name()))
+        }
+        Some("SET_ANTENNA") => match parts.next().map(str::parse::<i32>) {
+            Some(Ok(deg)) if (-180..=180).contains(&deg) => {
+                shared.antenna_setpoint_deg.store(deg, Ordering::SeqCst);
+                ok(format!("SET_ANTENNA {deg}"))
+            }
+            Some(Ok(deg)) => reject(
+                shared,
+                DropReason::Malformed,
+                format!("SET_ANTENNA {deg} out of range -180..=180"),
+            ),
+            _ => reject(
+                shared,
+                DropReason::Malformed,
+                "SET_ANTENNA missing or invalid degrees",
+            ),
+        },
+        Some("SEND_TELEMETRY") => {
+            let (Some(temp), Some(batt), Some(angle), None) =
+                (parts.next(), parts.next(), parts.next(), parts.next())
+            else {
+                return reject(
+                    shared,
+                    DropReason::Malformed,
+                    "SEND_TELEMETRY expected <temp> <battery> <angle>",
+                );
+            };
+            // Parse wide, then validate against the wire-field ranges so the
+            // NAK names the offending field instead of a generic parse error.
+            let (Ok(temp), Ok(batt), Ok(angle)) =
+                (temp.parse::<i64>(), batt.parse::<i64>(), angle.parse::<i64>())
+            else {
+                return reject(
+                    shared,
+                    DropReason::Malformed,
+                    "SEND_TELEMETRY non-numeric value",
+                );
+            };
+            if !(i16::MIN as i64..=i16::MAX as i64).contains(&temp) {
+                return reject(
+                    shared,
+                    DropReason::Malformed,
+                    format!("SEND_TELEMETRY temp {temp} outside i16 range"),
+                );
+            }
+            if !(0..=u16::MAX as i64).contains(&batt) {
+                return reject(
+                    shared,
+                    DropReason::Malformed,
+                    format!("SEND_TELEMETRY battery {batt} outside u16 range"),
+                );
+            }
+            if !(i16::MIN as i64..=i16::MAX as i64).contains(&angle) {
+                return reject(
+                    shared,
+                    DropReason::Malformed,
+                    format!("SEND_TELEMETRY angle {angle} outside i16 range"),
+                );
+            }
+            shared.inject_telemetry(temp as i16, batt as u16, angle as i16);
+            deferred(format!(
+                "SEND_TELEMETRY seq={}",
+                shared.next_seq.load(Ordering::SeqCst)
+            ))
+        }
+        Some("RESET") => {
+            let policy = match parts.next() {
+                None | Some("restart") => 2u8,
+                Some("continue") => 1,
+                Some(_) => {
+                    return reject(
+                        shared,
+                        DropReason::Malformed,
+                        "RESET expected restart|continue",
+                    )
+                }
+            };
+            shared.reset_pending.store(policy, Ordering::SeqCst);
+            deferred(format!(
+                "RESET policy={}",
+                if policy == 2 { "restart" } else { "continue" }
+            ))
+        }
+        Some("SNAPSHOT") => match (parts.next(), parts.next()) {
+            (Some(name), None) => {
+                shared.request_snapshot(SnapshotOp::Save, name.to_string());
+                deferred(format!("SNAPSHOT {name}"))
+            }
+            _ => reject(shared, DropReason::Malformed, "SNAPSHOT expected one name"),
+        },
+        // Deferred like RESET: the ack only proves the request was queued.
+        // Restoring a name that was never captured is a logged no-op on the
+        // send loop, which is the only thread that knows the slots.
+        Some("RESTORE") => match (parts.next(), parts.next()) {
+            (Some(name), None) => {
+                shared.request_snapshot(SnapshotOp::Load, name.to_string());
+                deferred(format!("RESTORE {name}"))
+            }
+            _ => reject(shared, DropReason::Malformed, "RESTORE expected one name"),
+        },
+        Some("PING") => match parts.next() {
+            // The token (the GCS's send timestamp) is echoed untouched so
+            // the prober can match replies and compute the round trip.
+            Some(token) => ok(format!("PONG {token}")),
+            None => ok("PONG"),
+        },
+        Some("PAUSE") => {
+            shared.paused.store(true, Ordering::SeqCst);
+            ok("PAUSE")
+        }
+        Some("RESUME") => {
+            shared.paused.store(false, Ordering::SeqCst);
+            ok("RESUME")
+        }
+        Some("GET_STATUS") => ok(format!(
+            "STATUS mode={} interval_ms={} edge_ratio={} antenna_setpoint={} antenna_actual={} paused={} auto_safe={}",
+            Mode::from_u8(shared.mode.load(Ordering::SeqCst)).name(),
+            shared.interval_ms.load(Ordering::SeqCst),
+            shared.edge_ratio(),
+            shared.antenna_setpoint_deg.load(Ordering::SeqCst),
+            shared.antenna_actual_deg.load(Ordering::SeqCst),
+            shared.paused.load(Ordering::SeqCst),
+            shared.auto_safe_latched.load(Ordering::SeqCst),
+        )),
+        Some(other) => reject(
+            shared,
+            DropReason::Unknown,
+            format!("unknown command {other}"),
+        ),
+        None => reject(shared, DropReason::Malformed, "empty command"),
+    }
+}
+
+/// Bounded priority queue between the command socket reader and the executor.
+///
+/// A command's `PRIO=n` token (default [`DEFAULT_COMMAND_PRIORITY`]) decides
+/// where it lands: higher priorities are executed first, equal priorities
+/// stay FIFO. When commands arrive faster than they can be executed, the
+/// tail is dropped (and counted as `QueueFull`) rather than letting the
+/// backlog grow without bound.
+pub struct CommandQueue {
+    inner: Mutex<VecDeque<(u8, String, std::net::SocketAddr)>>,
+    not_empty: Condvar,
+    capacity: usize,
+}
+
+impl CommandQueue {
+    pub fn new(capacity: usize) -> Self {
+        CommandQueue {
+            inner: Mutex::new(VecDeque::with_capacity(capacity)),
+            not_empty: Condvar::new(),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Enqueues a command, or drops it (counting `QueueFull`) when full.
+    /// Returns whether the command was accepted. A malformed `PRIO=` token is
+    /// queued at the default priority so the executor NAKs it in order.
+    pub fn push(&self, shared: &OcsShared, line: String, from: std::net::SocketAddr) -> bool {
+        let priority = split_priority(&line)
+            .map(|(_, p)| p.unwrap_or(DEFAULT_COMMAND_PRIORITY))
+            .unwrap_or(DEFAULT_COMMAND_PRIORITY);
+        let mut queue = self.inner.lock().unwrap();
+        if queue.len() == self.capacity {
+            shared.drop_command(DropReason::QueueFull);
+            return false;
+        }
+        // Ahead of every strictly lower priority, behind its equals.
+        let at = queue
+            .iter()
+            .position(|(p, ..)| *p < priority)
+            .unwrap_or(queue.len());
+        queue.insert(at, (priority, line, from));
+        self.not_empty.notify_one();
+        true
+    }
+
+    /// Pops the most urgent pending command, blocking up to `timeout`.
+    pub fn pop(&self, timeout: std::time::Duration) -> Option<(String, std::net::SocketAddr)> {
+        let mut queue = self.inner.lock().unwrap();
+        if queue.is_empty() {
+            let (guard, _) = self.not_empty.wait_timeout(queue, timeout).unwrap();
+            queue = guard;
+        }
+        queue.pop_front().map(|(_, line, from)| (line, from))
+    }
+
+    /// Pops and executes the most urgent pending command, returning the
+    /// command line, its structured outcome, and the sender to ack. The wire
+    /// reply is derived from the outcome via
+    /// [`CommandOutcome::wire_reply`].
+    pub fn execute_next(
+        &self,
+        shared: &OcsShared,
+        timeout: std::time::Duration,
+    ) -> Option<(String, CommandOutcome, std::net::SocketAddr)> {
+        let (line, from) = self.pop(timeout)?;
+        let outcome = execute_line(shared, &line);
+        Some((line, outcome, from))
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// UDP command receiver running on its own thread.
+pub struct CommandReceiver {
+    socket: UdpSocket,
+    shared: Arc<OcsShared>,
+    /// Uplink side of the link-asymmetry simulation; the downlink side
+    /// lives on the [`MockOCS`](super::MockOCS).
+    impairment: Option<LinkImpairment>,
+}
+
+impl CommandReceiver {
+    /// Binds the command port on all interfaces.
+    pub fn bind(port: u16, shared: Arc<OcsShared>) -> io::Result<Self> {
+        Self::bind_with(port, shared, false)
+    }
+
+    /// Like [`CommandReceiver::bind`], optionally with `SO_REUSEADDR`.
+    pub fn bind_with(port: u16, shared: Arc<OcsShared>, reuse_addr: bool) -> io::Result<Self> {
+        let socket = crate::util::bind_udp("OCS command", port, reuse_addr)?;
+        Ok(CommandReceiver {
+            socket,
+            shared,
+            impairment: None,
+        })
+    }
+
+    /// Impairs the command uplink independently of the telemetry downlink.
+    /// A lost command never reaches the executor — the GCS sees a missing
+    /// ack and retries — and a delayed one is held before queueing, pushing
+    /// its ack toward (or past) the sender's deadline.
+    pub fn set_impairment(&mut self, impairment: LinkImpairment) {
+        println!("[OCS] uplink impairment: {}", impairment.describe());
+        self.impairment = Some(impairment);
+    }
+
+    /// The bound address of the command socket (useful with port 0 in tests).
+    pub fn local_addr(&self) -> io::Result<std::net::SocketAddr> {
+        self.socket.local_addr()
+    }
+
+    /// Spawns the receive pipeline; it runs for the life of the process.
+    ///
+    /// The socket reader and the command executor are separate threads with a
+    /// bounded [`CommandQueue`] between them, so a command burst cannot stall
+    /// the socket — excess commands are dropped and counted instead.
+    pub fn spawn(self) -> JoinHandle<()> {
+        let queue = Arc::new(CommandQueue::new(COMMAND_QUEUE_CAPACITY));
+        let shared = Arc::clone(&self.shared);
+        let reply_socket = self
+            .socket
+            .try_clone()
+            .expect("clone command socket for replies");
+        let executor_queue = Arc::clone(&queue);
+        thread::spawn(move || loop {
+            let Some((line, outcome, from)) =
+                executor_queue.execute_next(&shared, std::time::Duration::from_millis(100))
+            else {
+                continue;
+            };
+            let reply = outcome.wire_reply();
+            println!("[OCS-CMD] {line} -> {reply} [{}]", outcome.code.name());
+            if let Err(e) = reply_socket.send_to(reply.as_bytes(), from) {
+                eprintln!("[OCS-CMD] ack send error: {e}");
+            }
+        });
+
+        thread::spawn(move || {
+            let mut buf = [0u8; 256];
+            let mut impairment = self.impairment;
+            loop {
+                let (len, from) = match self.socket.recv_from(&mut buf) {
+                    Ok(r) => r,
+                    Err(e) => {
+                        eprintln!("[OCS-CMD] recv error: {e}");
+                        continue;
+                    }
+                };
+                // Uplink asymmetry, applied before the queue so an impaired
+                // command costs the sender its full retry machinery.
+                if let Some(link) = &mut impairment {
+                    if link.drops() {
+                        self.shared.drop_command(DropReason::Impaired);
+                        continue;
+                    }
+                    if let Some(delay) = link.delay() {
+                        thread::sleep(delay);
+                    }
+                }
+                let line = String::from_utf8_lossy(&buf[..len]).trim().to_string();
+                if !queue.push(&self.shared, line.clone(), from) {
+                    eprintln!("[OCS-CMD] command queue full; dropped '{line}'");
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_interval_updates_state_and_bumps_epoch() {
+        let shared = OcsShared::new(1000, Mode::Normal);
+        let reply = process_command(&shared, "SET_INTERVAL 250");
+        assert_eq!(reply, "ACK SET_INTERVAL 250");
+        assert_eq!(shared.interval_ms.load(Ordering::SeqCst), 250);
+        assert_eq!(shared.interval_epoch.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn set_interval_rejects_out_of_range() {
+        let shared = OcsShared::new(1000, Mode::Normal);
+        assert!(process_command(&shared, "SET_INTERVAL 0").starts_with("NAK"));
+        assert!(process_command(&shared, "SET_INTERVAL 60001").starts_with("NAK"));
+        assert!(process_command(&shared, "SET_INTERVAL abc").starts_with("NAK"));
+        // State untouched on rejection.
+        assert_eq!(shared.interval_ms.load(Ordering::SeqCst), 1000);
+        assert_eq!(shared.interval_epoch.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn set_mode_and_status() {
+        let shared = OcsShared::new(500, Mode::Normal);
+        assert_eq!(process_command(&shared, "SET_MODE safe"), "ACK SET_MODE safe");
+        assert_eq!(
+            process_command(&shared, "GET_STATUS"),
+            "ACK STATUS mode=safe interval_ms=500 edge_ratio=0.2 antenna_setpoint=0 antenna_actual=0 paused=false auto_safe=false"
+        );
+    }
+
+    #[test]
+    fn set_mode_is_refused_while_auto_safe_is_latched() {
+        let shared = OcsShared::new(500, Mode::Safe);
+        shared.auto_safe_latched.store(true, Ordering::SeqCst);
+        assert!(process_command(&shared, "SET_MODE normal").starts_with("NAK SET_MODE refused"));
+        assert_eq!(shared.mode.load(Ordering::SeqCst), Mode::Safe as u8);
+        assert_eq!(shared.command_drops.count(DropReason::Refused), 1);
+        // Re-commanding safe is harmless, and the latch releasing restores
+        // normal mode authority.
+        assert_eq!(process_command(&shared, "SET_MODE safe"), "ACK SET_MODE safe");
+        shared.auto_safe_latched.store(false, Ordering::SeqCst);
+        assert_eq!(process_command(&shared, "SET_MODE normal"), "ACK SET_MODE normal");
+    }
+
+    #[test]
+    fn set_edge_ratio_validates_range_and_shows_in_status() {
+        let shared = OcsShared::new(500, Mode::Mixed);
+        assert_eq!(shared.edge_ratio(), DEFAULT_EDGE_RATIO);
+        assert_eq!(
+            process_command(&shared, "SET_EDGE_RATIO 0.75"),
+            "ACK SET_EDGE_RATIO 0.75"
+        );
+        assert_eq!(shared.edge_ratio(), 0.75);
+        assert!(process_command(&shared, "GET_STATUS").contains("edge_ratio=0.75"));
+        // Boundaries are legal: 0 disables edges, 1 makes every packet one.
+        assert_eq!(process_command(&shared, "SET_EDGE_RATIO 0"), "ACK SET_EDGE_RATIO 0");
+        assert_eq!(process_command(&shared, "SET_EDGE_RATIO 1"), "ACK SET_EDGE_RATIO 1");
+        assert!(process_command(&shared, "SET_EDGE_RATIO 1.1").starts_with("NAK"));
+        assert!(process_command(&shared, "SET_EDGE_RATIO -0.1").starts_with("NAK"));
+        assert!(process_command(&shared, "SET_EDGE_RATIO abc").starts_with("NAK"));
+        // Rejections leave the ratio untouched.
+        assert_eq!(shared.edge_ratio(), 1.0);
+        assert_eq!(shared.command_drops.count(DropReason::Malformed), 3);
+    }
+
+    #[test]
+    fn pause_and_resume_toggle_shared_state() {
+        let shared = OcsShared::new(500, Mode::Normal);
+        assert_eq!(process_command(&shared, "PAUSE"), "ACK PAUSE");
+        assert!(shared.paused.load(Ordering::SeqCst));
+        assert!(process_command(&shared, "GET_STATUS").contains("paused=true"));
+        assert_eq!(process_command(&shared, "RESUME"), "ACK RESUME");
+        assert!(!shared.paused.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn set_antenna_validates_range() {
+        let shared = OcsShared::new(500, Mode::Normal);
+        assert_eq!(process_command(&shared, "SET_ANTENNA 90"), "ACK SET_ANTENNA 90");
+        assert_eq!(shared.antenna_setpoint_deg.load(Ordering::SeqCst), 90);
+        assert!(process_command(&shared, "SET_ANTENNA 181").starts_with("NAK"));
+        assert!(process_command(&shared, "SET_ANTENNA x").starts_with("NAK"));
+    }
+
+    #[test]
+    fn id_token_is_echoed_in_reply() {
+        let shared = OcsShared::new(1000, Mode::Normal);
+        assert_eq!(
+            handle_line(&shared, "ID=9 SET_INTERVAL 100"),
+            "ID=9 ACK SET_INTERVAL 100"
+        );
+        assert_eq!(handle_line(&shared, "ID=bogus SET_MODE safe"), "NAK malformed ID token");
+    }
+
+    #[test]
+    fn get_history_returns_bounded_recent_samples() {
+        let shared = OcsShared::with_history_capacity(1000, Mode::Normal, 3);
+        for seq in 0..5u32 {
+            shared.push_history(Telemetry {
+                seq,
+                timestamp_ms: seq as u64 * 100,
+                temperature: 20,
+                battery_mv: 12_000,
+                antenna_angle: 0,
+                boot_id: 0,
+                mode: None,
+                command_echo: None,
+            });
+        }
+        // Capacity 3: only seq 2..=4 retained; asking for 10 acks what exists.
+        let reply = process_command(&shared, "GET_HISTORY 10");
+        assert!(reply.starts_with("ACK HISTORY 3"));
+        assert!(reply.contains("seq=2"));
+        assert!(!reply.contains("seq=1"));
+        let reply = process_command(&shared, "GET_HISTORY 1");
+        assert!(reply.starts_with("ACK HISTORY 1"));
+        assert!(reply.contains("seq=4"));
+        assert!(process_command(&shared, "GET_HISTORY 0").starts_with("NAK"));
+    }
+
+    #[test]
+    fn send_telemetry_queues_exact_values_and_acks_the_seq() {
+        let shared = OcsShared::new(500, Mode::Normal);
+        shared.next_seq.store(42, Ordering::SeqCst);
+        assert_eq!(
+            process_command(&shared, "SEND_TELEMETRY 150 0 90"),
+            "ACK SEND_TELEMETRY seq=42"
+        );
+        assert_eq!(shared.take_injected(), Some((150, 0, 90)));
+        // One-shot: consumed exactly once.
+        assert_eq!(shared.take_injected(), None);
+    }
+
+    #[test]
+    fn send_telemetry_rejects_out_of_range_and_malformed_values() {
+        let shared = OcsShared::new(500, Mode::Normal);
+        assert!(process_command(&shared, "SEND_TELEMETRY 40000 0 0")
+            .contains("temp 40000 outside i16 range"));
+        assert!(process_command(&shared, "SEND_TELEMETRY 20 -1 0")
+            .contains("battery -1 outside u16 range"));
+        assert!(process_command(&shared, "SEND_TELEMETRY 20 12000 32768")
+            .contains("angle 32768 outside i16 range"));
+        assert!(process_command(&shared, "SEND_TELEMETRY 20 12000").starts_with("NAK"));
+        assert!(process_command(&shared, "SEND_TELEMETRY a b c").starts_with("NAK"));
+        assert_eq!(shared.take_injected(), None);
+        assert_eq!(shared.command_drops.count(DropReason::Malformed), 5);
+    }
+
+    #[test]
+    fn reset_command_stores_the_sequence_policy() {
+        let shared = OcsShared::new(500, Mode::Normal);
+        assert_eq!(process_command(&shared, "RESET"), "ACK RESET policy=restart");
+        assert_eq!(shared.reset_pending.load(Ordering::SeqCst), 2);
+        assert_eq!(
+            process_command(&shared, "RESET continue"),
+            "ACK RESET policy=continue"
+        );
+        assert_eq!(shared.reset_pending.load(Ordering::SeqCst), 1);
+        assert!(process_command(&shared, "RESET sideways").starts_with("NAK"));
+    }
+
+    #[test]
+    fn snapshot_commands_queue_one_shot_requests_for_the_send_loop() {
+        let shared = OcsShared::new(500, Mode::Normal);
+        assert_eq!(process_command(&shared, "SNAPSHOT alpha"), "ACK SNAPSHOT alpha");
+        assert_eq!(
+            shared.take_snapshot_op(),
+            Some((SnapshotOp::Save, "alpha".to_string()))
+        );
+        assert_eq!(shared.take_snapshot_op(), None, "request is one-shot");
+        assert_eq!(process_command(&shared, "RESTORE alpha"), "ACK RESTORE alpha");
+        assert_eq!(
+            shared.take_snapshot_op(),
+            Some((SnapshotOp::Load, "alpha".to_string()))
+        );
+        assert!(process_command(&shared, "SNAPSHOT").starts_with("NAK"));
+        assert!(process_command(&shared, "RESTORE a b").starts_with("NAK"));
+        assert_eq!(shared.take_snapshot_op(), None, "rejections queue nothing");
+    }
+
+    #[test]
+    fn result_codes_classify_outcomes_and_derive_the_wire_reply() {
+        let shared = OcsShared::new(1000, Mode::Normal);
+        let outcome = execute_command(&shared, "SET_INTERVAL 250");
+        assert_eq!(outcome.code, ResultCode::Ok);
+        assert_eq!(outcome.wire_reply(), "ACK SET_INTERVAL 250");
+
+        // Applied by the send loop later, not on the ack.
+        let outcome = execute_command(&shared, "RESET");
+        assert_eq!(outcome.code, ResultCode::Deferred);
+        assert_eq!(outcome.wire_reply(), "ACK RESET policy=restart");
+
+        // Never accepted: the reason rides on the code, the NAK derives.
+        let outcome = execute_command(&shared, "SET_INTERVAL abc");
+        let ResultCode::Rejected(reason) = &outcome.code else {
+            panic!("expected rejection, got {outcome:?}");
+        };
+        assert_eq!(outcome.wire_reply(), format!("NAK {reason}"));
+
+        // Well-formed but refused by onboard protection: a failure, not a
+        // rejection, so accounting can separate the two.
+        shared.auto_safe_latched.store(true, Ordering::SeqCst);
+        let outcome = execute_command(&shared, "SET_MODE normal");
+        assert!(matches!(outcome.code, ResultCode::Failed(_)), "{outcome:?}");
+        assert!(outcome.wire_reply().starts_with("NAK SET_MODE refused"));
+    }
+
+    #[test]
+    fn execute_line_carries_the_id_token_on_the_outcome() {
+        let shared = OcsShared::new(1000, Mode::Normal);
+        let outcome = execute_line(&shared, "ID=9 SET_INTERVAL 100");
+        assert_eq!(outcome.id, Some(9));
+        assert_eq!(outcome.code, ResultCode::Ok);
+        assert_eq!(outcome.wire_reply(), "ID=9 ACK SET_INTERVAL 100");
+    }
+
+    #[test]
+    fn accepted_commands_update_the_echoed_last_command_id() {
+        let shared = OcsShared::new(500, Mode::Normal);
+        assert!(execute_line(&shared, "ID=5 SET_MODE safe").code.is_ack());
+        assert_eq!(shared.last_command_id.load(Ordering::SeqCst), 5);
+        // A rejected command was not processed and must not claim the echo.
+        assert!(!execute_line(&shared, "ID=6 BOGUS").code.is_ack());
+        assert_eq!(shared.last_command_id.load(Ordering::SeqCst), 5);
+        // Untagged commands have no id for the GCS to match; echo unchanged.
+        assert!(execute_line(&shared, "PING").code.is_ack());
+        assert_eq!(shared.last_command_id.load(Ordering::SeqCst), 5);
+    }
+
+    #[test]
+    fn execute_next_returns_the_structured_result() {
+        let shared = OcsShared::new(500, Mode::Normal);
+        let queue = CommandQueue::new(4);
+        let from: std::net::SocketAddr = "127.0.0.1:1".parse().unwrap();
+        queue.push(&shared, "PAUSE".into(), from);
+        let (line, outcome, sender) = queue
+            .execute_next(&shared, std::time::Duration::from_millis(10))
+            .expect("queued command executes");
+        assert_eq!(line, "PAUSE");
+        assert_eq!(outcome.code, ResultCode::Ok);
+        assert_eq!(sender, from);
+        assert!(shared.paused.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn ping_echoes_its_token() {
+        let shared = OcsShared::new(500, Mode::Normal);
+        assert_eq!(process_command(&shared, "PING 1234"), "ACK PONG 1234");
+        assert_eq!(process_command(&shared, "PING"), "ACK PONG");
+    }
+
+    #[test]
+    fn unknown_command_nak() {
+        let shared = OcsShared::new(500, Mode::Normal);
+        assert!(process_command(&shared, "FROBNICATE").starts_with("NAK"));
+    }
+
+    #[test]
+    fn rejections_are_counted_by_reason() {
+        let shared = OcsShared::new(500, Mode::Normal);
+        process_command(&shared, "FROBNICATE");
+        process_command(&shared, "SET_INTERVAL abc");
+        process_command(&shared, "SET_MODE sideways");
+        handle_line(&shared, "ID=bogus SET_MODE safe");
+        assert_eq!(shared.command_drops.count(DropReason::Unknown), 1);
+        assert_eq!(shared.command_drops.count(DropReason::Malformed), 3);
+        assert_eq!(shared.command_drops.total(), 4);
+    }
+
+    #[test]
+    fn prio_token_is_stripped_validated_and_echoed() {
+        let shared = OcsShared::new(500, Mode::Normal);
+        assert_eq!(
+            process_command(&shared, "SET_INTERVAL 750 PRIO=5"),
+            "ACK SET_INTERVAL 750 PRIO=5"
+        );
+        assert_eq!(shared.interval_ms.load(Ordering::SeqCst), 750);
+        // Omitted token: plain ACK, no echo.
+        assert_eq!(process_command(&shared, "PAUSE"), "ACK PAUSE");
+        assert_eq!(
+            process_command(&shared, "SET_INTERVAL 500 PRIO=900"),
+            "NAK PRIO \"900\" outside 0..=255"
+        );
+        assert_eq!(shared.command_drops.count(DropReason::Malformed), 1);
+    }
+
+    #[test]
+    fn higher_prio_commands_jump_the_queue() {
+        let shared = OcsShared::new(500, Mode::Normal);
+        let queue = CommandQueue::new(4);
+        let from: std::net::SocketAddr = "127.0.0.1:1".parse().unwrap();
+        queue.push(&shared, "PAUSE".into(), from);
+        queue.push(&shared, "RESUME PRIO=2".into(), from);
+        queue.push(&shared, "GET_STATUS PRIO=2".into(), from);
+        queue.push(&shared, "SET_MODE safe PRIO=9".into(), from);
+        let timeout = std::time::Duration::from_millis(10);
+        assert_eq!(queue.pop(timeout).unwrap().0, "SET_MODE safe PRIO=9");
+        // Equal priorities stay FIFO; the default-priority command goes last.
+        assert_eq!(queue.pop(timeout).unwrap().0, "RESUME PRIO=2");
+        assert_eq!(queue.pop(timeout).unwrap().0, "GET_STATUS PRIO=2");
+        assert_eq!(queue.pop(timeout).unwrap().0, "PAUSE");
+    }
+
+    #[test]
+    fn queue_overflow_counts_queue_full_drops() {
+        let shared = OcsShared::new(500, Mode::Normal);
+        let queue = CommandQueue::new(2);
+        let from: std::net::SocketAddr = "127.0.0.1:1".parse().unwrap();
+        assert!(queue.push(&shared, "SET_MODE safe".into(), from));
+        assert!(queue.push(&shared, "SET_MODE edge".into(), from));
+        assert!(!queue.push(&shared, "SET_MODE normal".into(), from));
+        assert!(!queue.push(&shared, "GET_STATUS".into(), from));
+        assert_eq!(shared.command_drops.count(DropReason::QueueFull), 2);
+        // FIFO: the oldest accepted command comes out first.
+        let (line, _) = queue.pop(std::time::Duration::from_millis(10)).unwrap();
+        assert_eq!(line, "SET_MODE safe");
+        assert_eq!(queue.len(), 1);
+    }
+}
@@ -0,0 +1,488 @@
+//! Telemetry value generation.
+//!
+//! Models a slowly draining battery, a temperature that oscillates around a
+//! nominal point with sensor noise, and an antenna angle that jitters around
+//! boresight. Edge cases drive one field at a time to an out-of-limits value
+//! so the GCS's fault classification can be exercised deterministically.
+
+use crate::angle::{normalize_angle, AngleConvention};
+use crate::expr::Expr;
+use crate::rng::Rng;
+use crate::telemetry::Telemetry;
+
+/// Nominal temperature in degrees C.
+const NOMINAL_TEMP_C: i16 = 20;
+/// Fully charged battery level in millivolts.
+const BATTERY_FULL_MV: u16 = 12_600;
+
+/// Number of distinct edge-case variants cycled by `generate_edge_case`.
+pub const EDGE_CASE_COUNT: u8 = 6;
+
+/// Default antenna slew limit in degrees per packet.
+pub const DEFAULT_SLEW_RATE_DEG: f64 = 10.0;
+
+/// Baseline antenna-angle noise amplitude in degrees.
+const ANTENNA_NOISE_DEG: i32 = 5;
+
+/// Named edge-case variants, addressing the same out-of-limits values as
+/// [`TelemetryGenerator::generate_edge_case`] without the index arithmetic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeKind {
+    HotTemperature,
+    ColdTemperature,
+    DeadBattery,
+    Overvolt,
+    AntennaHigh,
+    AntennaLow,
+}
+
+impl EdgeKind {
+    pub fn parse(s: &str) -> Option<EdgeKind> {
+        match s {
+            "hot" => Some(EdgeKind::HotTemperature),
+            "cold" => Some(EdgeKind::ColdTemperature),
+            "battery" => Some(EdgeKind::DeadBattery),
+            "overvolt" => Some(EdgeKind::Overvolt),
+            "antenna-high" => Some(EdgeKind::AntennaHigh),
+            "antenna-low" => Some(EdgeKind::AntennaLow),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            EdgeKind::HotTemperature => "hot",
+            EdgeKind::ColdTemperature => "cold",
+            EdgeKind::DeadBattery => "battery",
+            EdgeKind::Overvolt => "overvolt",
+            EdgeKind::AntennaHigh => "antenna-high",
+            EdgeKind::AntennaLow => "antenna-low",
+        }
+    }
+
+    /// The `generate_edge_case` variant index this kind names.
+    fn case(&self) -> u8 {
+        match self {
+            EdgeKind::HotTemperature => 0,
+            EdgeKind::ColdTemperature => 1,
+            EdgeKind::DeadBattery => 2,
+            EdgeKind::Overvolt => 3,
+            EdgeKind::AntennaHigh => 4,
+            EdgeKind::AntennaLow => 5,
+        }
+    }
+}
+
+/// Stateful generator for telemetry samples.
+pub struct TelemetryGenerator {
+    battery_mv: f64,
+    antenna_actual: f64,
+    antenna_setpoint: f64,
+    slew_rate_deg: f64,
+    /// Extra degrees of antenna noise per degree C away from nominal,
+    /// modeling thermal stress on the pointing mechanism (0 = independent).
+    thermal_antenna_coupling: f64,
+    rng: Rng,
+    /// User waveforms overriding the built-in models; see [`crate::expr`].
+    temp_expr: Option<Expr>,
+    battery_expr: Option<Expr>,
+    /// Timestamp of the first expression-driven packet, anchoring `t = 0`.
+    expr_epoch_ms: Option<u64>,
+    /// Cursor into the explicit edge order of `generate_edge_sequence`.
+    edge_sequence_pos: usize,
+    /// Wrap convention emitted antenna angles are normalized into.
+    angle_convention: AngleConvention,
+}
+
+/// The generator's mutable state as carried by an OCS snapshot; see
+/// [`crate::mock_ocs::state::OcsSnapshot`]. Configuration (slew rate,
+/// coupling, expressions, angle convention) is deliberately absent: a
+/// snapshot restores where the simulation *was*, not how it is set up.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeneratorState {
+    pub battery_mv: f64,
+    pub antenna_actual: f64,
+    pub antenna_setpoint: f64,
+    pub rng_state: u64,
+    pub expr_epoch_ms: Option<u64>,
+    pub edge_sequence_pos: usize,
+}
+
+impl TelemetryGenerator {
+    pub fn new(seed: u64) -> Self {
+        TelemetryGenerator {
+            battery_mv: BATTERY_FULL_MV as f64,
+            antenna_actual: 0.0,
+            antenna_setpoint: 0.0,
+            slew_rate_deg: DEFAULT_SLEW_RATE_DEG,
+            thermal_antenna_coupling: 0.0,
+            rng: Rng::new(seed),
+            temp_expr: None,
+            battery_expr: None,
+            expr_epoch_ms: None,
+            edge_sequence_pos: 0,
+            angle_convention: AngleConvention::Signed180,
+        }
+    }
+
+    /// Sets the wrap convention emitted antenna angles are normalized into.
+    /// Configuration, so like the slew rate it survives a [`reset`].
+    ///
+    /// [`reset`]: TelemetryGenerator::reset
+    pub fn set_angle_convention(&mut self, convention: AngleConvention) {
+        self.angle_convention = convention;
+    }
+
+    /// Reinitializes the mutable state as an onboard reboot would: battery
+    /// back to full, antenna to boresight, expression time re-anchored.
+    /// Configuration (slew rate, coupling, expressions) and the RNG stream
+    /// survive, like settings stored in nonvolatile memory.
+    pub fn reset(&mut self) {
+        self.battery_mv = BATTERY_FULL_MV as f64;
+        self.antenna_actual = 0.0;
+        self.antenna_setpoint = 0.0;
+        self.expr_epoch_ms = None;
+        self.edge_sequence_pos = 0;
+    }
+
+    /// Captures the mutable state for an OCS snapshot.
+    pub fn state(&self) -> GeneratorState {
+        GeneratorState {
+            battery_mv: self.battery_mv,
+            antenna_actual: self.antenna_actual,
+            antenna_setpoint: self.antenna_setpoint,
+            rng_state: self.rng.state(),
+            expr_epoch_ms: self.expr_epoch_ms,
+            edge_sequence_pos: self.edge_sequence_pos,
+        }
+    }
+
+    /// Restores state captured by [`TelemetryGenerator::state`]. The RNG
+    /// stream resumes at the captured point, so post-restore samples replay
+    /// exactly the branch the original run took from there.
+    pub fn restore(&mut self, state: &GeneratorState) {
+        self.battery_mv = state.battery_mv;
+        self.antenna_actual = state.antenna_actual;
+        self.antenna_setpoint = state.antenna_setpoint;
+        self.rng.set_state(state.rng_state);
+        self.expr_epoch_ms = state.expr_epoch_ms;
+        self.edge_sequence_pos = state.edge_sequence_pos;
+    }
+
+    /// Drives temperature from a user expression instead of the thermal model.
+    pub fn set_temp_expr(&mut self, expr: Expr) {
+        self.temp_expr = Some(expr);
+    }
+
+    /// Drives the battery level from a user expression instead of the drain
+    /// model.
+    pub fn set_battery_expr(&mut self, expr: Expr) {
+        self.battery_expr = Some(expr);
+    }
+
+    /// Elapsed seconds since the first generated packet, for expression `t`.
+    fn expr_t(&mut self, timestamp_ms: u64) -> f64 {
+        let epoch = *self.expr_epoch_ms.get_or_insert(timestamp_ms);
+        timestamp_ms.saturating_sub(epoch) as f64 / 1000.0
+    }
+
+    /// Commands the antenna toward a new setpoint; the actual angle ramps
+    /// there at most `slew_rate_deg` degrees per packet, like a real actuator.
+    pub fn set_antenna_setpoint(&mut self, deg: f64) {
+        self.antenna_setpoint = deg;
+    }
+
+    /// Configures the maximum antenna movement per packet.
+    pub fn set_slew_rate(&mut self, deg_per_packet: f64) {
+        self.slew_rate_deg = deg_per_packet.max(0.0);
+    }
+
+    /// Couples antenna noise to thermal deviation: each degree C away from
+    /// nominal widens the noise band by `factor` degrees of pointing, so
+    /// hot/cold excursions produce jumpier pointing. Zero keeps the antenna
+    /// noise independent of temperature.
+    pub fn set_thermal_antenna_coupling(&mut self, factor: f64) {
+        self.thermal_antenna_coupling = factor.max(0.0);
+    }
+
+    /// The antenna's current (post-slew) mechanical angle.
+    pub fn antenna_actual(&self) -> f64 {
+        self.antenna_actual
+    }
+
+    /// Advances the antenna one packet's worth of travel toward the setpoint.
+    fn slew_antenna(&mut self) {
+        let delta = self.antenna_setpoint - self.antenna_actual;
+        self.antenna_actual += delta.clamp(-self.slew_rate_deg, self.slew_rate_deg);
+    }
+
+    /// Current modeled battery level in millivolts.
+    pub fn battery_mv(&self) -> u16 {
+        self.battery_mv as u16
+    }
+
+    /// Overrides the battery level, e.g. when restoring persisted state.
+    pub fn set_battery_mv(&mut self, mv: u16) {
+        self.battery_mv = mv as f64;
+    }
+
+    /// Returns `true` with probability `p`.
+    pub fn chance(&mut self, p: f64) -> bool {
+        self.rng.next_f64() < p
+    }
+
+    /// Nominal telemetry: slow battery drain, thermal oscillation, antenna jitter.
+    pub fn generate_normal(&mut self, seq: u32, timestamp_ms: u64) -> Telemetry {
+        let expr_t = self.expr_t(timestamp_ms);
+        if let Some(expr) = &self.battery_expr {
+            self.battery_mv = expr.eval(expr_t, seq as f64).clamp(0.0, u16::MAX as f64);
+        } else {
+            self.battery_mv = (self.battery_mv - 0.5).max(0.0);
+        }
+        let t = timestamp_ms as f64 / 1000.0;
+        let temperature = if let Some(expr) = &self.temp_expr {
+            expr.eval(expr_t, seq as f64)
+                .clamp(i16::MIN as f64, i16::MAX as f64) as i16
+        } else {
+            NOMINAL_TEMP_C + (10.0 * (t / 60.0).sin()) as i16 + self.rng.range_i32(-2, 2) as i16
+        };
+        self.slew_antenna();
+        let deviation = (temperature - NOMINAL_TEMP_C).unsigned_abs() as f64;
+        let noise_deg =
+            ANTENNA_NOISE_DEG + (self.thermal_antenna_coupling * deviation) as i32;
+        let raw_angle = self.antenna_actual + self.rng.range_i32(-noise_deg, noise_deg) as f64;
+        let antenna_angle = normalize_angle(raw_angle, self.angle_convention) as i16;
+        Telemetry {
+            seq,
+            timestamp_ms,
+            temperature,
+            battery_mv: self.battery_mv as u16,
+            antenna_angle,
+            boot_id: 0,
+            mode: None,
+            command_echo: None,
+        }
+    }
+
+    /// Safe-mode telemetry: all fields pinned to quiet nominal values.
+    pub fn generate_safe(&mut self, seq: u32, timestamp_ms: u64) -> Telemetry {
+        self.battery_mv = (self.battery_mv - 0.1).max(0.0);
+        Telemetry {
+            seq,
+            timestamp_ms,
+            temperature: NOMINAL_TEMP_C,
+            battery_mv: self.battery_mv as u16,
+            antenna_angle: 0,
+            boot_id: 0,
+            mode: None,
+            command_echo: None,
+        }
+    }
+
+    /// One of [`EDGE_CASE_COUNT`] out-of-limits samples, selected by
+    /// `case_type % EDGE_CASE_COUNT`.
+    pub fn generate_edge_case(&mut self, seq: u32, timestamp_ms: u64, case_type: u8) -> Telemetry {
+        let mut t = self.generate_normal(seq, timestamp_ms);
+        match case_type % EDGE_CASE_COUNT {
+            0 => t.temperature = 150,      // thermal runaway
+            1 => t.temperature = -90,      // deep cold
+            2 => t.battery_mv = 0,         // battery collapse
+            3 => t.battery_mv = 16_000,    // charge regulator overvolt
+            4 => t.antenna_angle = 90,     // gross misalignment +
+            _ => t.antenna_angle = -90,    // gross misalignment -
+        }
+        t
+    }
+
+    /// Edge cases in the explicit order given, cycling deterministically so a
+    /// test can assert the GCS classifies each kind in turn. An empty list
+    /// degrades to a normal sample.
+    pub fn generate_edge_sequence(
+        &mut self,
+        seq: u32,
+        timestamp_ms: u64,
+        kinds: &[EdgeKind],
+    ) -> Telemetry {
+        if kinds.is_empty() {
+            return self.generate_normal(seq, timestamp_ms);
+        }
+        let kind = kinds[self.edge_sequence_pos % kinds.len()];
+        self.edge_sequence_pos += 1;
+        self.generate_edge_case(seq, timestamp_ms, kind.case())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normal_values_stay_in_plausible_range() {
+        let mut generator = TelemetryGenerator::new(1);
+        for i in 0..500 {
+            let t = generator.generate_normal(i, i as u64 * 100);
+            assert!((-50..=100).contains(&t.temperature));
+            assert!(t.battery_mv <= BATTERY_FULL_MV);
+            assert!(t.antenna_angle.abs() <= 45);
+        }
+    }
+
+    #[test]
+    fn antenna_ramps_at_slew_rate_toward_setpoint() {
+        let mut generator = TelemetryGenerator::new(1);
+        generator.set_slew_rate(10.0);
+        generator.set_antenna_setpoint(90.0);
+        let mut previous = 0.0;
+        for i in 1..=9 {
+            generator.generate_normal(i, i as u64 * 100);
+            let actual = generator.antenna_actual();
+            assert!(actual > previous, "antenna should keep ramping");
+            assert!(
+                actual - previous <= 10.0 + 1e-9,
+                "step {} exceeded slew rate",
+                actual - previous
+            );
+            previous = actual;
+        }
+        assert!((generator.antenna_actual() - 90.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn unsigned_convention_wraps_negative_angles_into_0_360() {
+        let mut generator = TelemetryGenerator::new(1);
+        generator.set_angle_convention(AngleConvention::Unsigned360);
+        generator.set_antenna_setpoint(-20.0);
+        let mut t = generator.generate_normal(0, 0);
+        for i in 1..10 {
+            t = generator.generate_normal(i, i as u64 * 100);
+        }
+        // -20 with up to 5 degrees of noise reads as roughly 340.
+        assert!((300..360).contains(&t.antenna_angle), "got {}", t.antenna_angle);
+    }
+
+    #[test]
+    fn expressions_override_field_models() {
+        let mut generator = TelemetryGenerator::new(1);
+        generator.set_temp_expr(Expr::parse("20 + 30*sin(t/10)").unwrap());
+        generator.set_battery_expr(Expr::parse("12000 - i*100").unwrap());
+        // t anchors at the first packet's timestamp.
+        let first = generator.generate_normal(0, 5_000);
+        assert_eq!(first.temperature, 20);
+        assert_eq!(first.battery_mv, 12_000);
+        let later = generator.generate_normal(10, 20_000); // t = 15 s
+        let expected = 20.0 + 30.0 * (15.0_f64 / 10.0).sin();
+        assert_eq!(later.temperature, expected as i16);
+        assert_eq!(later.battery_mv, 11_000);
+    }
+
+    #[test]
+    fn expression_battery_saturates_to_field_range() {
+        let mut generator = TelemetryGenerator::new(1);
+        generator.set_battery_expr(Expr::parse("0 - 500").unwrap());
+        assert_eq!(generator.generate_normal(0, 0).battery_mv, 0);
+    }
+
+    /// Sample variance of antenna angles at a fixed temperature.
+    fn angle_variance(temp_expr: &str, coupling: f64) -> f64 {
+        let mut generator = TelemetryGenerator::new(9);
+        generator.set_temp_expr(Expr::parse(temp_expr).unwrap());
+        generator.set_thermal_antenna_coupling(coupling);
+        let angles: Vec<f64> = (0..500)
+            .map(|i| generator.generate_normal(i, i as u64 * 100).antenna_angle as f64)
+            .collect();
+        let mean = angles.iter().sum::<f64>() / angles.len() as f64;
+        angles.iter().map(|a| (a - mean).powi(2)).sum::<f64>() / angles.len() as f64
+    }
+
+    #[test]
+    fn hot_antenna_points_jumpier_than_nominal() {
+        let nominal = angle_variance("20", 0.5);
+        let hot = angle_variance("90", 0.5);
+        assert!(
+            hot > nominal * 2.0,
+            "hot variance {hot} not clearly above nominal {nominal}"
+        );
+        // Zero coupling reproduces the independent behavior.
+        let decoupled = angle_variance("90", 0.0);
+        assert!(
+            (decoupled - nominal).abs() < nominal,
+            "decoupled variance {decoupled} should match nominal {nominal}"
+        );
+    }
+
+    #[test]
+    fn reset_restores_base_state_but_keeps_configuration() {
+        let mut generator = TelemetryGenerator::new(1);
+        generator.set_slew_rate(3.0);
+        generator.set_antenna_setpoint(90.0);
+        for i in 0..50 {
+            generator.generate_normal(i, i as u64 * 100);
+        }
+        assert!(generator.battery_mv() < BATTERY_FULL_MV);
+        assert!(generator.antenna_actual() > 0.0);
+
+        generator.reset();
+        assert_eq!(generator.battery_mv(), BATTERY_FULL_MV);
+        assert_eq!(generator.antenna_actual(), 0.0);
+        // Configuration survives: the slew limit still applies post-reset.
+        generator.set_antenna_setpoint(90.0);
+        generator.generate_normal(50, 5_000);
+        assert!((generator.antenna_actual() - 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn edge_sequence_cycles_the_explicit_order() {
+        let mut generator = TelemetryGenerator::new(1);
+        let order = [
+            EdgeKind::HotTemperature,
+            EdgeKind::DeadBattery,
+            EdgeKind::AntennaHigh,
+        ];
+        // Two full cycles: each position yields its named kind, in order.
+        for (i, expected) in order.iter().cycle().take(6).enumerate() {
+            let t = generator.generate_edge_sequence(i as u32, i as u64 * 100, &order);
+            match expected {
+                EdgeKind::HotTemperature => assert_eq!(t.temperature, 150),
+                EdgeKind::DeadBattery => assert_eq!(t.battery_mv, 0),
+                EdgeKind::AntennaHigh => assert_eq!(t.antenna_angle, 90),
+                _ => unreachable!(),
+            }
+        }
+        // Empty list degrades to a normal sample.
+        let t = generator.generate_edge_sequence(6, 600, &[]);
+        assert!((-50..=100).contains(&t.temperature));
+    }
+
+    #[test]
+    fn edge_kind_names_round_trip() {
+        for kind in [
+            EdgeKind::HotTemperature,
+            EdgeKind::ColdTemperature,
+            EdgeKind::DeadBattery,
+            EdgeKind::Overvolt,
+            EdgeKind::AntennaHigh,
+            EdgeKind::AntennaLow,
+        ] {
+            assert_eq!(EdgeKind::parse(kind.name()), Some(kind));
+        }
+        assert_eq!(EdgeKind::parse("sideways"), None);
+    }
+
+    #[test]
+    fn edge_cases_cycle_all_variants() {
+        let mut generator = TelemetryGenerator::new(1);
+        let mut out_of_limits = 0;
+        for case in 0..EDGE_CASE_COUNT {
+            let t = generator.generate_edge_case(0, 0, case);
+            if t.temperature > 100
+                || t.temperature < -50
+                || t.battery_mv == 0
+                || t.battery_mv > 15_000
+                || t.antenna_angle.abs() > 45
+            {
+                out_of_limits += 1;
+            }
+        }
+        assert_eq!(out_of_limits, EDGE_CASE_COUNT);
+    }
+}
@@ -0,0 +1,370 @@
+//! Cold-start state persistence for the OCS.
+//!
+//! On clean shutdown the OCS can write its last sequence number, battery
+//! level and mode to a small `key=value` text file; on the next startup that
+//! file is reloaded so the downlink continues the sequence instead of
+//! restarting from zero and confusing a long-running GCS. A missing or
+//! corrupt file is not fatal — the caller logs a notice and starts fresh.
+//!
+//! Separately, [`OcsSnapshot`] captures the *complete* mutable state — the
+//! generator's models and RNG stream included — so a scenario test can run
+//! to a known point, checkpoint, and explore several branches from exactly
+//! that state.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::mock_ocs::command::Mode;
+use crate::mock_ocs::generator::GeneratorState;
+use crate::telemetry::Telemetry;
+
+/// The subset of OCS state that survives a restart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PersistedState {
+    pub seq: u32,
+    pub battery_mv: u16,
+    pub mode: Mode,
+    /// Monotonic boot counter; files from builds that predate it load as 0.
+    pub boot_id: u8,
+}
+
+impl PersistedState {
+    /// Serializes to the `key=value` file format.
+    fn to_file_format(self) -> String {
+        format!(
+            "seq={}\nbattery_mv={}\nmode={}\nboot_id={}\n",
+            self.seq,
+            self.battery_mv,
+            self.mode.name(),
+            self.boot_id
+        )
+    }
+
+    /// Parses the `key=value` file format, rejecting missing or bad fields.
+    fn parse(text: &str) -> Result<PersistedState, String> {
+        let mut seq = None;
+        let mut battery_mv = None;
+        let mut mode = None;
+        let mut boot_id = None;
+        for (lineno, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("line {}: expected key=value", lineno + 1))?;
+            match key {
+                "seq" => {
+                    seq = Some(value.parse().map_err(|_| format!("bad seq {value:?}"))?)
+                }
+                "battery_mv" => {
+                    battery_mv =
+                        Some(value.parse().map_err(|_| format!("bad battery_mv {value:?}"))?)
+                }
+                "mode" => {
+                    mode = Some(Mode::parse(value).ok_or_else(|| format!("bad mode {value:?}"))?)
+                }
+                "boot_id" => {
+                    boot_id =
+                        Some(value.parse().map_err(|_| format!("bad boot_id {value:?}"))?)
+                }
+                other => return Err(format!("unknown key {other:?}")),
+            }
+        }
+        Ok(PersistedState {
+            seq: seq.ok_or("missing seq")?,
+            battery_mv: battery_mv.ok_or("missing battery_mv")?,
+            mode: mode.ok_or("missing mode")?,
+            // Optional for compatibility with files written before the
+            // counter existed.
+            boot_id: boot_id.unwrap_or(0),
+        })
+    }
+
+    /// Writes the state file, replacing any previous contents.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_file_format())
+    }
+
+    /// Loads the state file. `Ok(None)` means no file exists (first boot);
+    /// `Err` means the file exists but could not be parsed.
+    pub fn load(path: &Path) -> io::Result<Option<PersistedState>> {
+        let text = match fs::read_to_string(path) {
+            Ok(t) => t,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        Self::parse(&text)
+            .map(Some)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+/// The complete mutable state of a running OCS, for test checkpointing.
+///
+/// Included: the sequence and edge-case counters, the warm-up countdown,
+/// the boot counter, the operational mode, the timestamp base, the
+/// generator's models and RNG stream, any samples waiting in a partially
+/// filled batch, and the clock reading at capture so restored time
+/// continues from the snapshot point. Excluded: sockets and transport,
+/// metrics, logs, and degradation configuration (corruption, flatline,
+/// chaos, duty cycle), which describe how the test is set up rather than
+/// where the simulation was.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OcsSnapshot {
+    pub seq: u32,
+    pub edge_counter: u64,
+    pub warmup_remaining: u64,
+    pub boot_id: u8,
+    pub mode: Mode,
+    pub timestamp_base_ms: u64,
+    /// The clock reading at capture, anchoring restored timestamps.
+    pub captured_at_ms: u64,
+    pub generator: GeneratorState,
+    /// Samples waiting in a partially filled batch container.
+    pub pending: Vec<Telemetry>,
+}
+
+impl OcsSnapshot {
+    /// Serializes to the `key=value` file format. Floats use Rust's
+    /// shortest round-tripping display form; pending samples are their v1
+    /// wire frames in hex, comma-separated.
+    fn to_file_format(&self) -> String {
+        let pending: Vec<String> = self.pending.iter().map(|t| hex(&t.to_bytes())).collect();
+        format!(
+            "seq={}\nedge_counter={}\nwarmup_remaining={}\nboot_id={}\nmode={}\n\
+             timestamp_base_ms={}\ncaptured_at_ms={}\nbattery_mv={}\nantenna_actual={}\n\
+             antenna_setpoint={}\nrng_state={}\nexpr_epoch_ms={}\nedge_sequence_pos={}\n\
+             pending={}\n",
+            self.seq,
+            self.edge_counter,
+            self.warmup_remaining,
+            self.boot_id,
+            self.mode.name(),
+            self.timestamp_base_ms,
+            self.captured_at_ms,
+            self.generator.battery_mv,
+            self.generator.antenna_actual,
+            self.generator.antenna_setpoint,
+            self.generator.rng_state,
+            self.generator
+                .expr_epoch_ms
+                .map_or_else(|| "none".to_string(), |ms| ms.to_string()),
+            self.generator.edge_sequence_pos,
+            pending.join(","),
+        )
+    }
+
+    /// Parses the `key=value` file format, rejecting missing or bad fields.
+    /// Pending frames go through the normal v1 decode, CRC check included.
+    fn parse(text: &str) -> Result<OcsSnapshot, String> {
+        fn require<T>(field: Option<T>, key: &str) -> Result<T, String> {
+            field.ok_or_else(|| format!("missing {key}"))
+        }
+        let mut fields = std::collections::HashMap::new();
+        for (lineno, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("line {}: expected key=value", lineno + 1))?;
+            fields.insert(key, value);
+        }
+        fn parsed<T: std::str::FromStr>(
+            fields: &std::collections::HashMap<&str, &str>,
+            key: &str,
+        ) -> Result<T, String> {
+            let value = fields.get(key).ok_or_else(|| format!("missing {key}"))?;
+            value.parse().map_err(|_| format!("bad {key} {value:?}"))
+        }
+        let mode_text = require(fields.get("mode"), "mode")?;
+        let epoch_text = require(fields.get("expr_epoch_ms"), "expr_epoch_ms")?;
+        let expr_epoch_ms = if *epoch_text == "none" {
+            None
+        } else {
+            Some(
+                epoch_text
+                    .parse()
+                    .map_err(|_| format!("bad expr_epoch_ms {epoch_text:?}"))?,
+            )
+        };
+        let mut pending = Vec::new();
+        for frame in require(fields.get("pending"), "pending")?
+            .split(',')
+            .filter(|s| !s.is_empty())
+        {
+            let bytes = unhex(frame).ok_or_else(|| format!("bad pending hex {frame:?}"))?;
+            pending.push(
+                Telemetry::from_bytes(&bytes)
+                    .ok_or_else(|| format!("bad pending frame {frame:?}"))?,
+            );
+        }
+        Ok(OcsSnapshot {
+            seq: parsed(&fields, "seq")?,
+            edge_counter: parsed(&fields, "edge_counter")?,
+            warmup_remaining: parsed(&fields, "warmup_remaining")?,
+            boot_id: parsed(&fields, "boot_id")?,
+            mode: Mode::parse(mode_text).ok_or_else(|| format!("bad mode {mode_text:?}"))?,
+            timestamp_base_ms: parsed(&fields, "timestamp_base_ms")?,
+            captured_at_ms: parsed(&fields, "captured_at_ms")?,
+            generator: GeneratorState {
+                battery_mv: parsed(&fields, "battery_mv")?,
+                antenna_actual: parsed(&fields, "antenna_actual")?,
+                antenna_setpoint: parsed(&fields, "antenna_setpoint")?,
+                rng_state: parsed(&fields, "rng_state")?,
+                expr_epoch_ms,
+                edge_sequence_pos: parsed(&fields, "edge_sequence_pos")?,
+            },
+            pending,
+        })
+    }
+
+    /// Writes the snapshot file, replacing any previous contents.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_file_format())
+    }
+
+    /// Loads a snapshot file. Unlike [`PersistedState::load`] a missing file
+    /// is an error: nobody restores a checkpoint they never took.
+    pub fn load(path: &Path) -> io::Result<OcsSnapshot> {
+        let text = fs::read_to_string(path)?;
+        Self::parse(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn unhex(s: &str) -> Option<Vec<u8>> {
+    if !s.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("wewinthis-state-test-{}-{name}", std::process::id()))
+    }
+
+    #[test]
+    fn round_trip() {
+        let path = temp_path("roundtrip");
+        let state = PersistedState {
+            seq: 4321,
+            battery_mv: 11_500,
+            mode: Mode::Mixed,
+            boot_id: 3,
+        };
+        state.save(&path).unwrap();
+        assert_eq!(PersistedState::load(&path).unwrap(), Some(state));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn missing_file_is_first_boot() {
+        let path = temp_path("missing");
+        assert_eq!(PersistedState::load(&path).unwrap(), None);
+    }
+
+    #[test]
+    fn file_without_boot_id_loads_as_boot_zero() {
+        let path = temp_path("no-boot");
+        fs::write(&path, "seq=1\nbattery_mv=100\nmode=normal\n").unwrap();
+        assert_eq!(PersistedState::load(&path).unwrap().unwrap().boot_id, 0);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn snapshot_round_trips_through_the_file_format() {
+        let path = temp_path("snapshot");
+        let snap = OcsSnapshot {
+            seq: 901,
+            edge_counter: 17,
+            warmup_remaining: 3,
+            boot_id: 2,
+            mode: Mode::Mixed,
+            timestamp_base_ms: 86_400_000,
+            captured_at_ms: 12_345,
+            generator: GeneratorState {
+                battery_mv: 11_499.5,
+                antenna_actual: -12.25,
+                antenna_setpoint: 30.0,
+                rng_state: 0xDEAD_BEEF_CAFE_F00D,
+                expr_epoch_ms: Some(1_000),
+                edge_sequence_pos: 4,
+            },
+            pending: vec![
+                Telemetry {
+                    seq: 902,
+                    timestamp_ms: 86_412_345,
+                    temperature: 21,
+                    battery_mv: 11_499,
+                    antenna_angle: -12,
+                    boot_id: 0,
+                    mode: None,
+                    command_echo: None,
+                },
+            ],
+        };
+        snap.save(&path).unwrap();
+        assert_eq!(OcsSnapshot::load(&path).unwrap(), snap);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn snapshot_with_no_epoch_or_pending_round_trips() {
+        let path = temp_path("snapshot-empty");
+        let snap = OcsSnapshot {
+            seq: 0,
+            edge_counter: 0,
+            warmup_remaining: 0,
+            boot_id: 0,
+            mode: Mode::Normal,
+            timestamp_base_ms: 0,
+            captured_at_ms: 0,
+            generator: GeneratorState {
+                battery_mv: 12_600.0,
+                antenna_actual: 0.0,
+                antenna_setpoint: 0.0,
+                rng_state: 42,
+                expr_epoch_ms: None,
+                edge_sequence_pos: 0,
+            },
+            pending: Vec::new(),
+        };
+        snap.save(&path).unwrap();
+        assert_eq!(OcsSnapshot::load(&path).unwrap(), snap);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn corrupt_snapshot_is_an_error() {
+        let path = temp_path("snapshot-corrupt");
+        assert!(OcsSnapshot::load(&path).is_err(), "missing file must fail");
+        fs::write(&path, "seq=1\n").unwrap();
+        assert!(OcsSnapshot::load(&path).is_err(), "missing fields must fail");
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn corrupt_file_is_an_error() {
+        let path = temp_path("corrupt");
+        fs::write(&path, "seq=not-a-number\n").unwrap();
+        assert!(PersistedState::load(&path).is_err());
+        fs::write(&path, "seq=1\nbattery_mv=100\n").unwrap();
+        assert!(PersistedState::load(&path).is_err(), "missing mode must fail");
+        fs::remove_file(&path).unwrap();
+    }
+}
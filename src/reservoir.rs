@@ -0,0 +1,133 @@
+//! Uniform reservoir sampling for bounded-memory metrics.
+//!
+//! Several metrics accumulate one sample per packet; over a long soak run an
+//! unbounded `Vec` grows without limit, and simply capping it would bias any
+//! percentile toward whichever end of the run was kept. A reservoir instead
+//! keeps a fixed-size uniform sample of *everything* seen (Vitter's
+//! algorithm R), so statistics computed from it stay representative of the
+//! whole run at constant memory.
+//!
+//! The tradeoff: aggregates become estimates once more than `capacity`
+//! samples have been seen. With the default capacity of 4096 a p95 is
+//! typically within a fraction of a percentile of the exact value, which is
+//! well inside the noise of the timing measurements fed into it. Exact
+//! extremes (true min/max) should be tracked separately if they matter.
+
+use crate::rng::Rng;
+
+/// Default number of retained samples; plenty for stable percentiles.
+pub const DEFAULT_RESERVOIR_CAPACITY: usize = 4096;
+
+/// Fixed-capacity uniform sample of a stream (algorithm R).
+pub struct Reservoir<T> {
+    samples: Vec<T>,
+    capacity: usize,
+    seen: u64,
+    rng: Rng,
+}
+
+impl<T: Copy> Reservoir<T> {
+    /// Creates a reservoir holding at most `capacity` samples. The seed makes
+    /// the retained subset reproducible for tests.
+    pub fn new(capacity: usize, seed: u64) -> Self {
+        let capacity = capacity.max(1);
+        Reservoir {
+            samples: Vec::with_capacity(capacity),
+            capacity,
+            seen: 0,
+            rng: Rng::new(seed),
+        }
+    }
+
+    /// Offers one sample. Until the reservoir fills, every sample is kept;
+    /// after that, the i-th sample replaces a random slot with probability
+    /// `capacity / i`, which keeps the retained set uniform over the stream.
+    pub fn push(&mut self, value: T) {
+        self.seen += 1;
+        if self.samples.len() < self.capacity {
+            self.samples.push(value);
+        } else {
+            let slot = self.rng.next_u64() % self.seen;
+            if (slot as usize) < self.capacity {
+                self.samples[slot as usize] = value;
+            }
+        }
+    }
+
+    /// The retained samples, in no meaningful order.
+    pub fn as_slice(&self) -> &[T] {
+        &self.samples
+    }
+
+    /// Total samples offered over the stream (not just retained).
+    pub fn seen(&self) -> u64 {
+        self.seen
+    }
+
+    /// Number of samples currently retained.
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.samples.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_everything_until_capacity() {
+        let mut r = Reservoir::new(10, 1);
+        for i in 0..10 {
+            r.push(i);
+        }
+        assert_eq!(r.len(), 10);
+        assert_eq!(r.seen(), 10);
+        let mut kept: Vec<_> = r.as_slice().to_vec();
+        kept.sort_unstable();
+        assert_eq!(kept, (0..10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn stays_bounded_and_counts_the_stream() {
+        let mut r = Reservoir::new(100, 1);
+        for i in 0..10_000 {
+            r.push(i);
+        }
+        assert_eq!(r.len(), 100);
+        assert_eq!(r.seen(), 10_000);
+    }
+
+    #[test]
+    fn sample_is_roughly_uniform_over_the_stream() {
+        // Push 0..10_000 and check the retained sample's mean is near the
+        // stream mean — a cap-and-drop buffer would sit near one end instead.
+        let mut r = Reservoir::new(500, 42);
+        for i in 0..10_000u64 {
+            r.push(i as f64);
+        }
+        let mean = r.iter().sum::<f64>() / r.len() as f64;
+        assert!(
+            (mean - 5_000.0).abs() < 500.0,
+            "sample mean {mean} far from stream mean"
+        );
+    }
+
+    #[test]
+    fn same_seed_retains_the_same_subset() {
+        let mut a = Reservoir::new(50, 7);
+        let mut b = Reservoir::new(50, 7);
+        for i in 0..5_000 {
+            a.push(i);
+            b.push(i);
+        }
+        assert_eq!(a.as_slice(), b.as_slice());
+    }
+}
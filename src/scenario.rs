@@ -0,0 +1,240 @@
+//! Deterministic scenario scripts for end-to-end demos.
+//!
+//! A scenario is a line-oriented script driving the OCS over the uplink while
+//! watching the downlink for expected reactions:
+//!
+//! ```text
+//! # thermal fault demo
+//! WAIT 2s
+//! INJECT temp 500ms
+//! EXPECT fault=HighTemperature within 3s
+//! SET_MODE safe
+//! ```
+//!
+//! `WAIT` pauses, `INJECT field dur` maps to the OCS `INJECT_FAULT` command,
+//! `EXPECT fault=F within T` fails the scenario (with its line number) unless
+//! the fault is observed in received telemetry before the deadline, and any
+//! other line is sent to the OCS verbatim as a command.
+
+use std::io;
+use std::net::UdpSocket;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::gcs::{classify_faults, Fault, Limits};
+use crate::telemetry::DecoderRegistry;
+use crate::uplink::CommandSender;
+
+/// One parsed script step, tagged with its 1-based source line.
+#[derive(Debug, PartialEq)]
+pub enum Step {
+    Wait(Duration),
+    Inject { field: String, duration: Duration },
+    Command(String),
+    Expect { fault: Fault, within: Duration },
+}
+
+/// Parses `2s` / `500ms` / bare milliseconds.
+pub fn parse_duration(s: &str) -> Option<Duration> {
+    if let Some(ms) = s.strip_suffix("ms") {
+        return Some(Duration::from_millis(ms.parse().ok()?));
+    }
+    if let Some(secs) = s.strip_suffix('s') {
+        return Some(Duration::from_secs_f64(secs.parse().ok()?));
+    }
+    Some(Duration::from_millis(s.parse().ok()?))
+}
+
+fn parse_fault(name: &str) -> Option<Fault> {
+    [
+        Fault::HighTemperature,
+        Fault::LowTemperature,
+        Fault::LowBattery,
+        Fault::HighBattery,
+        Fault::AntennaMisalignment,
+        Fault::LossOfContact,
+    ]
+    .into_iter()
+    .find(|f| f.name() == name)
+}
+
+/// Parses a script into `(line_number, step)` pairs; blank lines and `#`
+/// comments are skipped. Errors carry the offending line number.
+pub fn parse_script(text: &str) -> Result<Vec<(usize, Step)>, (usize, String)> {
+    let mut steps = Vec::new();
+    for (idx, raw) in text.lines().enumerate() {
+        let lineno = idx + 1;
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut parts = line.split_whitespace();
+        let step = match parts.next().unwrap() {
+            "WAIT" => {
+                let dur = parts
+                    .next()
+                    .and_then(parse_duration)
+                    .ok_or((lineno, "WAIT expects a duration (e.g. 2s, 500ms)".into()))?;
+                Step::Wait(dur)
+            }
+            "INJECT" => {
+                let field = parts
+                    .next()
+                    .ok_or((lineno, "INJECT expects a field name".into()))?
+                    .to_string();
+                let duration = parts
+                    .next()
+                    .and_then(parse_duration)
+                    .ok_or((lineno, "INJECT expects a duration".into()))?;
+                Step::Inject { field, duration }
+            }
+            "EXPECT" => {
+                let fault = parts
+                    .next()
+                    .and_then(|t| t.strip_prefix("fault="))
+                    .and_then(parse_fault)
+                    .ok_or((lineno, "EXPECT expects fault=<FaultName>".into()))?;
+                if parts.next() != Some("within") {
+                    return Err((lineno, "EXPECT expects `within <duration>`".into()));
+                }
+                let within = parts
+                    .next()
+                    .and_then(parse_duration)
+                    .ok_or((lineno, "EXPECT expects a duration after `within`".into()))?;
+                Step::Expect { fault, within }
+            }
+            _ => Step::Command(line.to_string()),
+        };
+        steps.push((lineno, step));
+    }
+    Ok(steps)
+}
+
+/// Executes a parsed scenario: commands go to the OCS, expectations are
+/// checked against telemetry arriving on the runner's own listen socket.
+pub struct ScenarioRunner {
+    sender: CommandSender,
+    socket: UdpSocket,
+    decoders: DecoderRegistry,
+    limits: Limits,
+}
+
+impl ScenarioRunner {
+    /// `listen_port` must be where the OCS downlinks its telemetry.
+    pub fn new(ocs_command_addr: &str, listen_port: u16) -> io::Result<Self> {
+        let socket = UdpSocket::bind(("0.0.0.0", listen_port))?;
+        Ok(ScenarioRunner {
+            sender: CommandSender::new(ocs_command_addr)?,
+            socket,
+            decoders: DecoderRegistry::with_defaults(),
+            limits: Limits::default(),
+        })
+    }
+
+    /// Runs all steps; the first failure aborts with its line number.
+    pub fn run(&mut self, steps: &[(usize, Step)]) -> Result<(), (usize, String)> {
+        for (lineno, step) in steps {
+            match step {
+                Step::Wait(dur) => {
+                    println!("[SCENARIO:{lineno}] WAIT {dur:?}");
+                    thread::sleep(*dur);
+                }
+                Step::Inject { field, duration } => {
+                    let cmd = format!("INJECT_FAULT {field} {}", duration.as_millis());
+                    println!("[SCENARIO:{lineno}] {cmd}");
+                    self.sender
+                        .send(&cmd)
+                        .map_err(|e| (*lineno, format!("inject failed: {e}")))?;
+                }
+                Step::Command(cmd) => {
+                    println!("[SCENARIO:{lineno}] {cmd}");
+                    self.sender
+                        .send(cmd)
+                        .map_err(|e| (*lineno, format!("command failed: {e}")))?;
+                }
+                Step::Expect { fault, within } => {
+                    println!(
+                        "[SCENARIO:{lineno}] EXPECT fault={} within {within:?}",
+                        fault.name()
+                    );
+                    if !self.await_fault(*fault, *within) {
+                        return Err((
+                            *lineno,
+                            format!("{} not observed within {within:?}", fault.name()),
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Receives telemetry until `fault` is classified or the deadline passes.
+    fn await_fault(&mut self, fault: Fault, within: Duration) -> bool {
+        let deadline = Instant::now() + within;
+        let mut buf = [0u8; 64];
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return false;
+            }
+            if self.socket.set_read_timeout(Some(remaining)).is_err() {
+                return false;
+            }
+            match self.socket.recv_from(&mut buf) {
+                Ok((len, _)) => {
+                    if let Ok(t) = self.decoders.decode(&buf[..len]) {
+                        if classify_faults(&t, &self.limits).contains(&fault) {
+                            return true;
+                        }
+                    }
+                }
+                Err(_) => return false,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn durations_parse_with_units() {
+        assert_eq!(parse_duration("2s"), Some(Duration::from_secs(2)));
+        assert_eq!(parse_duration("500ms"), Some(Duration::from_millis(500)));
+        assert_eq!(parse_duration("250"), Some(Duration::from_millis(250)));
+        assert_eq!(parse_duration("abc"), None);
+    }
+
+    #[test]
+    fn script_parses_all_step_kinds() {
+        let script = "\
+# demo
+WAIT 1s
+INJECT temp 500ms
+EXPECT fault=HighTemperature within 100ms
+SET_MODE safe
+";
+        let steps = parse_script(script).unwrap();
+        assert_eq!(steps.len(), 4);
+        assert_eq!(steps[0], (2, Step::Wait(Duration::from_secs(1))));
+        assert_eq!(
+            steps[2],
+            (
+                4,
+                Step::Expect {
+                    fault: Fault::HighTemperature,
+                    within: Duration::from_millis(100)
+                }
+            )
+        );
+        assert_eq!(steps[3], (5, Step::Command("SET_MODE safe".into())));
+    }
+
+    #[test]
+    fn parse_errors_carry_line_numbers() {
+        let err = parse_script("WAIT 1s\nEXPECT fault=NoSuchFault within 1s\n").unwrap_err();
+        assert_eq!(err.0, 2);
+    }
+}
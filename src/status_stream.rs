@@ -0,0 +1,127 @@
+//! Machine-readable status streaming for a supervising process.
+//!
+//! The GCS can listen on a Unix domain socket and write its periodic status
+//! lines and fault events to every connected consumer, so an embedding system
+//! reads structured events instead of scraping stdout. Consumers may connect
+//! and disconnect at any time; a broken or stalled consumer is dropped without
+//! disturbing the receive loop. On platforms without Unix sockets the same
+//! interface degrades to a TCP listener on localhost (the path argument is
+//! then interpreted as a port number).
+
+use std::io::{self, Write};
+
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+#[cfg(not(unix))]
+use std::net::{TcpListener as UnixListener, TcpStream as UnixStream};
+
+/// Fan-out publisher for status lines and fault events.
+pub struct StatusStream {
+    listener: UnixListener,
+    consumers: Vec<UnixStream>,
+}
+
+impl StatusStream {
+    /// Creates the listening socket. An existing socket file at `path` from a
+    /// previous run is removed first so a restart does not fail with
+    /// `AddrInUse`.
+    #[cfg(unix)]
+    pub fn bind(path: &str) -> io::Result<Self> {
+        if std::fs::metadata(path).is_ok() {
+            std::fs::remove_file(path)?;
+        }
+        let listener = UnixListener::bind(path)?;
+        listener.set_nonblocking(true)?;
+        Ok(StatusStream {
+            listener,
+            consumers: Vec::new(),
+        })
+    }
+
+    #[cfg(not(unix))]
+    pub fn bind(path: &str) -> io::Result<Self> {
+        let port: u16 = path.parse().map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "no Unix sockets on this platform; pass a TCP port number instead",
+            )
+        })?;
+        let listener = UnixListener::bind(("127.0.0.1", port))?;
+        listener.set_nonblocking(true)?;
+        Ok(StatusStream {
+            listener,
+            consumers: Vec::new(),
+        })
+    }
+
+    /// Number of currently connected consumers.
+    pub fn consumer_count(&self) -> usize {
+        self.consumers.len()
+    }
+
+    /// Writes one line to every connected consumer, accepting any newly
+    /// arrived connections first. A consumer whose write fails (disconnected,
+    /// or so far behind its buffer is full) is dropped; it can reconnect.
+    pub fn publish(&mut self, line: &str) {
+        loop {
+            match self.listener.accept() {
+                Ok((stream, _)) => {
+                    if stream.set_nonblocking(true).is_ok() {
+                        self.consumers.push(stream);
+                    }
+                }
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+                Err(_) => break,
+            }
+        }
+        self.consumers
+            .retain_mut(|c| writeln!(c, "{line}").is_ok());
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader};
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("status-stream-{}-{name}.sock", std::process::id()))
+    }
+
+    #[test]
+    fn consumer_receives_published_lines() {
+        let path = temp_path("rx");
+        let mut stream = StatusStream::bind(path.to_str().unwrap()).unwrap();
+        let consumer = UnixStream::connect(&path).unwrap();
+        stream.publish("hello=1");
+        assert_eq!(stream.consumer_count(), 1);
+        let mut line = String::new();
+        BufReader::new(consumer).read_line(&mut line).unwrap();
+        assert_eq!(line, "hello=1\n");
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn disconnected_consumer_is_dropped_not_fatal() {
+        let path = temp_path("drop");
+        let mut stream = StatusStream::bind(path.to_str().unwrap()).unwrap();
+        let consumer = UnixStream::connect(&path).unwrap();
+        stream.publish("first");
+        assert_eq!(stream.consumer_count(), 1);
+        drop(consumer);
+        // First publish after the hangup may still buffer; the consumer is
+        // gone within a couple of writes and the stream keeps working.
+        stream.publish("second");
+        stream.publish("third");
+        assert_eq!(stream.consumer_count(), 0);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn rebind_over_stale_socket_file_succeeds() {
+        let path = temp_path("stale");
+        drop(StatusStream::bind(path.to_str().unwrap()).unwrap());
+        assert!(StatusStream::bind(path.to_str().unwrap()).is_ok());
+        let _ = std::fs::remove_file(&path);
+    }
+}
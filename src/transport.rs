@@ -0,0 +1,143 @@
+//! Reliable TCP transport for telemetry.
+//!
+//! UDP is the default downlink, but some deployments prefer a reliable
+//! stream. In TCP mode the OCS connects to a GCS listener and writes frames
+//! with a 2-byte big-endian length prefix, which restores the message
+//! boundaries a byte stream lacks. A dropped connection is re-established on
+//! the next send, so a GCS restart does not require restarting the OCS.
+
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+/// Writes one length-prefixed frame to the stream.
+pub fn write_frame(w: &mut impl Write, frame: &[u8]) -> io::Result<()> {
+    if frame.len() > u16::MAX as usize {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "frame too large for u16 length prefix",
+        ));
+    }
+    w.write_all(&(frame.len() as u16).to_be_bytes())?;
+    w.write_all(frame)
+}
+
+/// Reads one length-prefixed frame from the stream.
+pub fn read_frame(r: &mut impl Read) -> io::Result<Vec<u8>> {
+    let mut len_buf = [0u8; 2];
+    r.read_exact(&mut len_buf)?;
+    let mut frame = vec![0u8; u16::from_be_bytes(len_buf) as usize];
+    r.read_exact(&mut frame)?;
+    Ok(frame)
+}
+
+/// Splits complete frames off the front of an accumulation buffer.
+///
+/// TCP delivers arbitrary byte chunks; the receive loop appends whatever
+/// arrives and calls this to extract every frame that is fully buffered,
+/// leaving a partial frame in place for the next read.
+pub fn drain_frames(buf: &mut Vec<u8>) -> Vec<Vec<u8>> {
+    let mut frames = Vec::new();
+    loop {
+        if buf.len() < 2 {
+            break;
+        }
+        let len = u16::from_be_bytes([buf[0], buf[1]]) as usize;
+        if buf.len() < 2 + len {
+            break;
+        }
+        frames.push(buf[2..2 + len].to_vec());
+        buf.drain(..2 + len);
+    }
+    frames
+}
+
+/// Connect-on-demand TCP sender for the OCS downlink.
+///
+/// The connection is established lazily on the first send and re-established
+/// after any send error, so the OCS tolerates the GCS coming up late or
+/// restarting mid-run.
+pub struct TcpDownlink {
+    target: SocketAddr,
+    stream: Option<TcpStream>,
+}
+
+impl TcpDownlink {
+    /// Resolves the target; no connection is attempted yet.
+    pub fn new(target: &str) -> io::Result<Self> {
+        let target = target
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "unresolvable target"))?;
+        Ok(TcpDownlink {
+            target,
+            stream: None,
+        })
+    }
+
+    /// Sends one frame, (re)connecting first if necessary. On failure the
+    /// connection is dropped so the next call starts a fresh one.
+    pub fn send(&mut self, frame: &[u8]) -> io::Result<()> {
+        if self.stream.is_none() {
+            let stream = TcpStream::connect_timeout(&self.target, Duration::from_millis(500))?;
+            stream.set_nodelay(true)?;
+            println!("[OCS] tcp downlink connected to {}", self.target);
+            self.stream = Some(stream);
+        }
+        let stream = self.stream.as_mut().unwrap();
+        if let Err(e) = write_frame(stream, frame) {
+            self.stream = None;
+            eprintln!("[OCS] tcp downlink lost ({e}); will reconnect");
+            return Err(e);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_round_trip() {
+        let mut wire = Vec::new();
+        write_frame(&mut wire, b"hello").unwrap();
+        write_frame(&mut wire, b"").unwrap();
+        write_frame(&mut wire, b"world").unwrap();
+        let mut cursor = io::Cursor::new(wire);
+        assert_eq!(read_frame(&mut cursor).unwrap(), b"hello");
+        assert_eq!(read_frame(&mut cursor).unwrap(), b"");
+        assert_eq!(read_frame(&mut cursor).unwrap(), b"world");
+        assert!(read_frame(&mut cursor).is_err());
+    }
+
+    #[test]
+    fn drain_frames_handles_partial_buffers() {
+        let mut wire = Vec::new();
+        write_frame(&mut wire, b"one").unwrap();
+        write_frame(&mut wire, b"two").unwrap();
+        // Deliver everything but the last byte: only the first frame is ready.
+        let mut buf: Vec<u8> = wire[..wire.len() - 1].to_vec();
+        let frames = drain_frames(&mut buf);
+        assert_eq!(frames, vec![b"one".to_vec()]);
+        buf.push(*wire.last().unwrap());
+        assert_eq!(drain_frames(&mut buf), vec![b"two".to_vec()]);
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn downlink_reconnects_after_listener_appears() {
+        // Grab a port that nothing is listening on, then release it.
+        let port = {
+            let probe = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            probe.local_addr().unwrap().port()
+        };
+        let mut downlink = TcpDownlink::new(&format!("127.0.0.1:{port}")).unwrap();
+        assert!(downlink.send(b"frame").is_err());
+
+        let listener = std::net::TcpListener::bind(("127.0.0.1", port)).unwrap();
+        downlink.send(b"frame").unwrap();
+        let (mut conn, _) = listener.accept().unwrap();
+        assert_eq!(read_frame(&mut conn).unwrap(), b"frame");
+    }
+}
@@ -0,0 +1,209 @@
+//! Reliable-ish uplink command delivery for the GCS.
+//!
+//! UDP commands can be lost, so every command carries an `ID=n` token the OCS
+//! echoes back in its ack. The sender waits for the matching ack up to a
+//! deadline and retransmits on timeout, up to a configurable retry count,
+//! before declaring the command failed. Acks for earlier attempts of the same
+//! command (late acks racing a retransmission) are deduplicated by id.
+
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::time::{Duration, Instant};
+
+/// Default per-attempt ack deadline.
+pub const DEFAULT_ACK_TIMEOUT_MS: u64 = 500;
+/// Default number of retransmissions after the first attempt.
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// Outcome counters for the command uplink.
+#[derive(Debug, Default)]
+pub struct UplinkMetrics {
+    pub commands_sent: u64,
+    pub commands_acked: u64,
+    pub commands_failed: u64,
+    pub retransmissions: u64,
+    pub late_acks: u64,
+    pub ack_latencies_ms: Vec<f64>,
+}
+
+impl UplinkMetrics {
+    /// Prints the uplink summary.
+    pub fn report(&self) {
+        println!("----- Uplink Command Report -----");
+        println!("Commands sent:   {}", self.commands_sent);
+        println!("Acked:           {}", self.commands_acked);
+        println!("Failed:          {}", self.commands_failed);
+        println!("Retransmissions: {}", self.retransmissions);
+        println!("Late acks:       {}", self.late_acks);
+        if !self.ack_latencies_ms.is_empty() {
+            let avg =
+                self.ack_latencies_ms.iter().sum::<f64>() / self.ack_latencies_ms.len() as f64;
+            println!("Avg ack latency: {avg:.2} ms");
+        }
+        println!("---------------------------------");
+    }
+}
+
+/// Sends text commands to the OCS command port and waits for matching acks.
+pub struct CommandSender {
+    socket: UdpSocket,
+    target: SocketAddr,
+    next_id: u32,
+    ack_timeout: Duration,
+    max_retries: u32,
+    pub metrics: UplinkMetrics,
+}
+
+impl CommandSender {
+    pub fn new(target: impl ToSocketAddrs) -> io::Result<Self> {
+        let socket = UdpSocket::bind("0.0.0.0:0")?;
+        let target = target
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "unresolvable target"))?;
+        Ok(CommandSender {
+            socket,
+            target,
+            next_id: 1,
+            ack_timeout: Duration::from_millis(DEFAULT_ACK_TIMEOUT_MS),
+            max_retries: DEFAULT_MAX_RETRIES,
+            metrics: UplinkMetrics::default(),
+        })
+    }
+
+    /// Overrides the per-attempt ack deadline and retry budget.
+    pub fn set_reliability(&mut self, ack_timeout_ms: u64, max_retries: u32) {
+        self.ack_timeout = Duration::from_millis(ack_timeout_ms);
+        self.max_retries = max_retries;
+    }
+
+    /// How many command ids have been issued; ids are assigned sequentially
+    /// from 1, so the commands sent between two readings carried
+    /// `before + 1 ..= after`. Lets a caller register just-sent ids for
+    /// in-band (telemetry command-echo) confirmation tracking.
+    pub fn ids_issued(&self) -> u32 {
+        self.next_id.wrapping_sub(1)
+    }
+
+    /// Sends one command, retransmitting until acked or out of retries.
+    /// Returns the ack text (without the `ID=` prefix) on success.
+    pub fn send(&mut self, command: &str) -> io::Result<String> {
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1);
+        let wire = format!("ID={id} {command}");
+        self.metrics.commands_sent += 1;
+
+        let send_start = Instant::now();
+        for attempt in 0..=self.max_retries {
+            if attempt > 0 {
+                self.metrics.retransmissions += 1;
+                println!("[GCS-CMD] retry {attempt} for command {id}");
+            }
+            self.socket.send_to(wire.as_bytes(), self.target)?;
+            match self.wait_for_ack(id)? {
+                Some(reply) => {
+                    self.metrics.commands_acked += 1;
+                    self.metrics
+                        .ack_latencies_ms
+                        .push(send_start.elapsed().as_secs_f64() * 1000.0);
+                    return Ok(reply);
+                }
+                None => continue,
+            }
+        }
+
+        self.metrics.commands_failed += 1;
+        Err(io::Error::new(
+            io::ErrorKind::TimedOut,
+            format!("command {id} not acked after {} attempts", self.max_retries + 1),
+        ))
+    }
+
+    /// Waits one deadline for the ack matching `id`; `Ok(None)` is a timeout.
+    /// Acks carrying an older id (late arrivals after a retransmission) are
+    /// counted and skipped rather than mistaken for the current command.
+    fn wait_for_ack(&mut self, id: u32) -> io::Result<Option<String>> {
+        let deadline = Instant::now() + self.ack_timeout;
+        let mut buf = [0u8; 512];
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Ok(None);
+            }
+            self.socket.set_read_timeout(Some(remaining))?;
+            match self.socket.recv_from(&mut buf) {
+                Ok((len, _)) => {
+                    let reply = String::from_utf8_lossy(&buf[..len]).into_owned();
+                    match parse_ack_id(&reply) {
+                        Some((reply_id, rest)) if reply_id == id => {
+                            return Ok(Some(rest.to_string()))
+                        }
+                        Some(_) => {
+                            self.metrics.late_acks += 1;
+                        }
+                        None => {
+                            // Ack without an id: pre-id peer, accept as-is.
+                            return Ok(Some(reply));
+                        }
+                    }
+                }
+                Err(e)
+                    if e.kind() == io::ErrorKind::WouldBlock
+                        || e.kind() == io::ErrorKind::TimedOut =>
+                {
+                    return Ok(None)
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+/// Splits an `ID=n ...` reply into the id and the remaining text.
+pub fn parse_ack_id(reply: &str) -> Option<(u32, &str)> {
+    let rest = reply.strip_prefix("ID=")?;
+    let (id, tail) = rest.split_once(' ')?;
+    Some((id.parse().ok()?, tail))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_ocs::command::{CommandReceiver, Mode, OcsShared};
+    use std::sync::Arc;
+
+    #[test]
+    fn command_is_acked_end_to_end() {
+        let shared = Arc::new(OcsShared::new(1000, Mode::Normal));
+        let receiver = CommandReceiver::bind(0, Arc::clone(&shared)).unwrap();
+        let addr = receiver.local_addr().unwrap();
+        receiver.spawn();
+
+        let mut sender = CommandSender::new(("127.0.0.1", addr.port())).unwrap();
+        let reply = sender.send("SET_INTERVAL 250").unwrap();
+        assert_eq!(reply, "ACK SET_INTERVAL 250");
+        assert_eq!(sender.metrics.commands_acked, 1);
+        assert_eq!(sender.metrics.ack_latencies_ms.len(), 1);
+    }
+
+    #[test]
+    fn unacked_command_retries_then_fails() {
+        // A bound socket that never replies.
+        let silent = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let addr = silent.local_addr().unwrap();
+
+        let mut sender = CommandSender::new(addr).unwrap();
+        sender.set_reliability(20, 2);
+        let err = sender.send("GET_STATUS").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+        assert_eq!(sender.metrics.commands_failed, 1);
+        assert_eq!(sender.metrics.retransmissions, 2);
+    }
+
+    #[test]
+    fn ack_id_parsing() {
+        assert_eq!(parse_ack_id("ID=7 ACK SET_MODE safe"), Some((7, "ACK SET_MODE safe")));
+        assert_eq!(parse_ack_id("ACK SET_MODE safe"), None);
+        assert_eq!(parse_ack_id("ID=x ACK"), None);
+    }
+}
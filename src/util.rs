@@ -0,0 +1,317 @@
+//! Small process-level helpers shared by the binaries.
+
+use std::io;
+use std::net::UdpSocket;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Binds a UDP socket on all interfaces, turning the cryptic `AddrInUse`
+/// errno into an actionable message and keeping which port failed in the
+/// context of other errors. With `reuse_addr` set, `SO_REUSEADDR` is applied
+/// before binding so a restart can grab a port still in TIME_WAIT-ish state.
+pub fn bind_udp(label: &str, port: u16, reuse_addr: bool) -> io::Result<UdpSocket> {
+    let result = if reuse_addr {
+        bind_reuse(port)
+    } else {
+        UdpSocket::bind(("0.0.0.0", port))
+    };
+    result.map_err(|e| {
+        if e.kind() == io::ErrorKind::AddrInUse {
+            io::Error::new(
+                io::ErrorKind::AddrInUse,
+                format!("{label} port {port} already in use; is another instance running? (try --reuse-addr)"),
+            )
+        } else {
+            io::Error::new(e.kind(), format!("{label} bind to 0.0.0.0:{port} failed: {e}"))
+        }
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn bind_reuse(port: u16) -> io::Result<UdpSocket> {
+    use std::os::fd::FromRawFd;
+
+    #[repr(C)]
+    struct SockaddrIn {
+        sin_family: u16,
+        sin_port: u16,
+        sin_addr: u32,
+        sin_zero: [u8; 8],
+    }
+    extern "C" {
+        fn socket(domain: i32, ty: i32, protocol: i32) -> i32;
+        fn setsockopt(fd: i32, level: i32, name: i32, value: *const i32, len: u32) -> i32;
+        fn bind(fd: i32, addr: *const SockaddrIn, len: u32) -> i32;
+        fn close(fd: i32) -> i32;
+    }
+    const AF_INET: i32 = 2;
+    const SOCK_DGRAM: i32 = 2;
+    const SOL_SOCKET: i32 = 1;
+    const SO_REUSEADDR: i32 = 2;
+
+    unsafe {
+        let fd = socket(AF_INET, SOCK_DGRAM, 0);
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let one: i32 = 1;
+        if setsockopt(fd, SOL_SOCKET, SO_REUSEADDR, &one, 4) != 0 {
+            let e = io::Error::last_os_error();
+            close(fd);
+            return Err(e);
+        }
+        let addr = SockaddrIn {
+            sin_family: AF_INET as u16,
+            sin_port: port.to_be(),
+            sin_addr: 0,
+            sin_zero: [0; 8],
+        };
+        if bind(fd, &addr, std::mem::size_of::<SockaddrIn>() as u32) != 0 {
+            let e = io::Error::last_os_error();
+            close(fd);
+            return Err(e);
+        }
+        Ok(UdpSocket::from_raw_fd(fd))
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn bind_reuse(port: u16) -> io::Result<UdpSocket> {
+    eprintln!("--reuse-addr is not supported on this platform; binding normally");
+    UdpSocket::bind(("0.0.0.0", port))
+}
+
+/// Parses a human-friendly duration: a bare number is seconds, with optional
+/// `ms`, `s`, `m` or `h` suffixes (`1500ms`, `60s`, `5m`, `1h`).
+pub fn parse_duration(spec: &str) -> Option<std::time::Duration> {
+    let (digits, unit_ms): (&str, u64) = if let Some(d) = spec.strip_suffix("ms") {
+        (d, 1)
+    } else if let Some(d) = spec.strip_suffix('s') {
+        (d, 1_000)
+    } else if let Some(d) = spec.strip_suffix('m') {
+        (d, 60_000)
+    } else if let Some(d) = spec.strip_suffix('h') {
+        (d, 3_600_000)
+    } else {
+        (spec, 1_000)
+    };
+    let value: u64 = digits.parse().ok()?;
+    Some(std::time::Duration::from_millis(value.checked_mul(unit_ms)?))
+}
+
+/// Parses a DSCP code point: a well-known name (`EF`, `CS0`-`CS7`,
+/// `AF11`-`AF43`, `DF`) or a raw value `0..=63`.
+pub fn parse_dscp(spec: &str) -> Option<u8> {
+    let named = match spec.to_ascii_uppercase().as_str() {
+        "DF" | "CS0" => 0,
+        "CS1" => 8,
+        "CS2" => 16,
+        "CS3" => 24,
+        "CS4" => 32,
+        "CS5" => 40,
+        "CS6" => 48,
+        "CS7" => 56,
+        "AF11" => 10,
+        "AF12" => 12,
+        "AF13" => 14,
+        "AF21" => 18,
+        "AF22" => 20,
+        "AF23" => 22,
+        "AF31" => 26,
+        "AF32" => 28,
+        "AF33" => 30,
+        "AF41" => 34,
+        "AF42" => 36,
+        "AF43" => 38,
+        "EF" => 46,
+        _ => return spec.parse::<u8>().ok().filter(|v| *v <= 63),
+    };
+    Some(named)
+}
+
+/// Marks outgoing datagrams with a DSCP code point via `IP_TOS`, so
+/// DiffServ-aware routers can prioritize the traffic. Honored on Linux and
+/// the BSDs; Windows ignores `IP_TOS` set per-socket (it wants the system QoS
+/// policy API), so on unsupported platforms this warns and continues with
+/// unmarked traffic.
+#[cfg(target_os = "linux")]
+pub fn set_dscp(socket: &UdpSocket, dscp: u8) -> io::Result<()> {
+    use std::os::fd::AsRawFd;
+
+    extern "C" {
+        fn setsockopt(fd: i32, level: i32, name: i32, value: *const i32, len: u32) -> i32;
+    }
+    const IPPROTO_IP: i32 = 0;
+    const IP_TOS: i32 = 1;
+
+    // The DSCP occupies the top six bits of the TOS byte; the low two are ECN.
+    let tos: i32 = (dscp as i32) << 2;
+    if unsafe { setsockopt(socket.as_raw_fd(), IPPROTO_IP, IP_TOS, &tos, 4) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn set_dscp(_socket: &UdpSocket, _dscp: u8) -> io::Result<()> {
+    eprintln!("DSCP marking is not supported on this platform; sending unmarked");
+    Ok(())
+}
+
+/// Pins the calling thread to one CPU so the receive loop stops migrating
+/// between cores (each migration costs cache warmth and shows up as jitter).
+/// Compare the report's jitter figures with and without pinning to judge the
+/// effect. On platforms without an affinity API this warns and continues.
+#[cfg(target_os = "linux")]
+pub fn pin_to_cpu(cpu: usize) -> io::Result<()> {
+    extern "C" {
+        fn sched_setaffinity(pid: i32, cpusetsize: usize, mask: *const u64) -> i32;
+    }
+    // A 1024-bit mask matches the kernel's default CPU set size.
+    let mut mask = [0u64; 16];
+    if cpu >= mask.len() * 64 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("CPU index {cpu} out of range"),
+        ));
+    }
+    mask[cpu / 64] = 1 << (cpu % 64);
+    if unsafe { sched_setaffinity(0, std::mem::size_of_val(&mask), mask.as_ptr()) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn pin_to_cpu(_cpu: usize) -> io::Result<()> {
+    eprintln!("CPU pinning is not supported on this platform; continuing unpinned");
+    Ok(())
+}
+
+/// Raises the calling thread to `SCHED_FIFO` real-time priority (1-99) so
+/// timer wakeups preempt normal workloads. Typically needs root or
+/// `CAP_SYS_NICE`; callers should treat failure as a warning, not fatal.
+#[cfg(target_os = "linux")]
+pub fn set_realtime_priority(priority: i32) -> io::Result<()> {
+    #[repr(C)]
+    struct SchedParam {
+        sched_priority: i32,
+    }
+    extern "C" {
+        fn pthread_self() -> usize;
+        fn pthread_setschedparam(thread: usize, policy: i32, param: *const SchedParam) -> i32;
+    }
+    const SCHED_FIFO: i32 = 1;
+    if !(1..=99).contains(&priority) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("real-time priority {priority} outside 1..=99"),
+        ));
+    }
+    let param = SchedParam {
+        sched_priority: priority,
+    };
+    // pthread functions return the errno directly instead of setting it.
+    let rc = unsafe { pthread_setschedparam(pthread_self(), SCHED_FIFO, &param) };
+    if rc != 0 {
+        return Err(io::Error::from_raw_os_error(rc));
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn set_realtime_priority(_priority: i32) -> io::Result<()> {
+    eprintln!("real-time priority is not supported on this platform; continuing at normal priority");
+    Ok(())
+}
+
+static SHUTDOWN: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn handle_signal(_signum: i32) {
+    SHUTDOWN.store(true, Ordering::SeqCst);
+}
+
+/// Installs a SIGINT handler that sets a shutdown flag, and returns the flag.
+///
+/// Both binaries poll this flag from their main loops so Ctrl+C produces a
+/// final report instead of killing the process mid-run.
+#[cfg(unix)]
+pub fn install_shutdown_flag() -> &'static AtomicBool {
+    const SIGINT: i32 = 2;
+    extern "C" {
+        fn signal(signum: i32, handler: usize) -> usize;
+    }
+    unsafe {
+        signal(SIGINT, handle_signal as *const () as usize);
+    }
+    &SHUTDOWN
+}
+
+#[cfg(not(unix))]
+pub fn install_shutdown_flag() -> &'static AtomicBool {
+    &SHUTDOWN
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn addr_in_use_gets_actionable_message() {
+        let first = bind_udp("test", 0, false).unwrap();
+        let port = first.local_addr().unwrap().port();
+        let err = bind_udp("test", port, false).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AddrInUse);
+        assert!(err.to_string().contains(&format!("port {port} already in use")));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn reuse_addr_bind_succeeds() {
+        let socket = bind_udp("test", 0, true).unwrap();
+        assert!(socket.local_addr().is_ok());
+    }
+
+    #[test]
+    fn durations_parse_with_and_without_suffixes() {
+        use std::time::Duration;
+        assert_eq!(parse_duration("60"), Some(Duration::from_secs(60)));
+        assert_eq!(parse_duration("60s"), Some(Duration::from_secs(60)));
+        assert_eq!(parse_duration("1500ms"), Some(Duration::from_millis(1500)));
+        assert_eq!(parse_duration("5m"), Some(Duration::from_secs(300)));
+        assert_eq!(parse_duration("1h"), Some(Duration::from_secs(3600)));
+        assert_eq!(parse_duration("fast"), None);
+        assert_eq!(parse_duration("1.5s"), None);
+    }
+
+    #[test]
+    fn dscp_names_and_raw_values_parse() {
+        assert_eq!(parse_dscp("EF"), Some(46));
+        assert_eq!(parse_dscp("ef"), Some(46));
+        assert_eq!(parse_dscp("CS6"), Some(48));
+        assert_eq!(parse_dscp("AF41"), Some(34));
+        assert_eq!(parse_dscp("0"), Some(0));
+        assert_eq!(parse_dscp("63"), Some(63));
+        assert_eq!(parse_dscp("64"), None);
+        assert_eq!(parse_dscp("gold"), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn dscp_marking_applies_to_a_bound_socket() {
+        let socket = bind_udp("test", 0, false).unwrap();
+        set_dscp(&socket, 46).unwrap();
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn cpu_pinning_validates_the_index() {
+        pin_to_cpu(0).unwrap();
+        assert!(pin_to_cpu(100_000).is_err());
+    }
+
+    #[test]
+    fn realtime_priority_rejects_out_of_range_values() {
+        assert!(set_realtime_priority(0).is_err() || cfg!(not(target_os = "linux")));
+        assert!(set_realtime_priority(100).is_err() || cfg!(not(target_os = "linux")));
+    }
+}